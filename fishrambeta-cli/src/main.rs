@@ -1,8 +1,10 @@
 use clap::Parser;
 use clap::ValueEnum;
-use fishrambeta::math::{Equation, Variable};
-use fishrambeta::parser;
-use num_rational::Rational64;
+use fishrambeta::math::{
+    Derivative, Dimension, Equation, QuadratureOptions, Quantity, RootOptions, Series,
+    SimplifyOptions, System, Unit, Variable, VectorValue,
+};
+use fishrambeta::parser::{self, ParseError};
 use std::collections::BTreeMap;
 use std::fmt;
 
@@ -21,114 +23,524 @@ pub struct Args {
     //Assume consecutive letters multiply variables with those names
     #[arg(long, default_value_t = false)]
     implicit_multiplication: bool,
-    //Variables to propagate errors of, seperated by commas
-    #[arg(long, default_value = "")]
-    propagate_variables: String,
+    //Variables to propagate errors of, seperated by commas, every letter if not given, the uncertainty of x is \sigma_{x}
+    #[arg(long)]
+    propagate_variables: Option<String>,
+    //The variable to solve for or differentiate to
+    #[arg(long, default_value = "x")]
+    variable: String,
+    //The unknowns to solve a system of equations for, seperated by commas, every letter if not given
+    #[arg(long)]
+    unknowns: Option<String>,
+    //Differentiate this many times to --variable
+    #[arg(long, default_value_t = 1)]
+    nth: usize,
+    //Variables to differentiate to in turn for a mixed partial derivative, seperated by commas
+    #[arg(long, conflicts_with = "nth")]
+    partial: Option<String>,
+    //Integrate numerically from --lower to --upper instead of finding an antiderivative
+    #[arg(long, default_value_t = false, requires_all = ["lower", "upper"])]
+    numeric: bool,
+    //The lower bound of a numerical integral or of the interval to find roots in
+    #[arg(long, allow_negative_numbers = true)]
+    lower: Option<f64>,
+    //The upper bound of a numerical integral or of the interval to find roots in
+    #[arg(long, allow_negative_numbers = true)]
+    upper: Option<f64>,
+    //Print the rule used for every subexpression when differentiating once to --variable
+    #[arg(long, default_value_t = false, conflicts_with_all = ["nth", "partial"])]
+    steps: bool,
+    //Do not multiply out products of sums when simplifying
+    #[arg(long, default_value_t = false)]
+    keep_factored: bool,
+    //The point to expand a series around formatted in LaTeX
+    #[arg(long, default_value = "0", allow_hyphen_values = true)]
+    point: String,
+    //The highest power to include in a series
+    #[arg(long, default_value_t = 5)]
+    order: u32,
+    //The components of a vector to calculate with as name=x,y,z, can be given more than once
+    #[arg(long)]
+    vector: Vec<String>,
+    //A value with a unit to calculate with as name=value unit, like m=2 kg, can be given more than once
+    #[arg(long)]
+    quantity: Vec<String>,
+    //The unit to give the result of a calculation in, like km/h
+    #[arg(long)]
+    to: Option<String>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
 enum Operation {
     Simplify,
+    Expand,
+    Factor,
     Solve,
+    SolveSystem,
+    Roots,
     Calculate,
+    Units,
     Differentiate,
+    Gradient,
+    Hessian,
+    Determinant,
+    Transpose,
+    Inverse,
     Integrate,
+    Series,
     Error,
 }
 
 #[derive(Debug)]
 enum Result {
     Equation(Equation),
+    Equations(Vec<Equation>),
+    Solutions(Vec<Vec<Equation>>),
+    Series(Series),
+    Matrix(Vec<Vec<Equation>>),
     Value(f64),
+    Values(Vec<f64>),
+    Quantity(f64, String),
+    Dimension(Dimension),
+    Vector(Vec<f64>),
 }
 
 impl fmt::Display for Result {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Result::Equation(equation) => write!(f, "{}", equation),
+            Result::Equations(equations) => write!(
+                f,
+                "{}",
+                equations
+                    .iter()
+                    .map(|equation| equation.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+            Result::Solutions(solutions) => write!(
+                f,
+                "{}",
+                solutions
+                    .iter()
+                    .map(|solution| solution
+                        .iter()
+                        .map(|equation| equation.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+            Result::Series(series) => write!(f, "{}", series),
+            Result::Matrix(rows) => write!(
+                f,
+                "\\begin{{bmatrix}}{}\\end{{bmatrix}}",
+                rows.iter()
+                    .map(|row| row
+                        .iter()
+                        .map(|entry| entry.to_string())
+                        .collect::<Vec<_>>()
+                        .join("&"))
+                    .collect::<Vec<_>>()
+                    .join("\\\\")
+            ),
             Result::Value(value) => write!(f, "{}", value),
+            Result::Quantity(value, unit) => write!(f, "{} {}", value, unit),
+            Result::Dimension(dimension) => write!(f, "{}", dimension),
+            Result::Values(values) => write!(
+                f,
+                "{}",
+                values
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+            Result::Vector(components) => write!(
+                f,
+                "({})",
+                components
+                    .iter()
+                    .map(|component| component.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
         }
     }
 }
 
 fn main() {
     let args = Args::parse();
-    let equation = parser::IR::latex_to_equation(
-        args.equation.chars().collect::<Vec<_>>(),
-        args.implicit_multiplication,
-    );
-
-    println!("Input equation: {}", equation);
     use std::time::Instant;
-    let now = Instant::now();
-    let value_dict = fishrambeta::physicsvalues::physics_values();
-    let result = process_operation(
-        equation.clone(),
-        args.operation,
-        &value_dict,
-        &args.propagate_variables,
-    );
+    let now;
+    let result = if args.operation == Operation::SolveSystem {
+        let system = exit_on_parse_error(
+            parser::IR::latex_to_system(
+                args.equation.chars().collect::<Vec<_>>(),
+                args.implicit_multiplication,
+            ),
+            &args.equation,
+        );
+        println!("Input system: {}", system);
+        now = Instant::now();
+        solve_system(&system, &args)
+    } else {
+        let equation = exit_on_parse_error(
+            parser::IR::latex_to_equation(
+                args.equation.chars().collect::<Vec<_>>(),
+                args.implicit_multiplication,
+            ),
+            &args.equation,
+        );
+        println!("Input equation: {}", equation);
+        now = Instant::now();
+        let value_dict = fishrambeta::physicsvalues::physics_values();
+        process_operation(equation.clone(), &args, &value_dict)
+    };
     let elapsed = now.elapsed();
     println!("Elapsed: {:.2?}", elapsed);
     println!("{}", result);
 }
 
+fn exit_on_parse_error<T>(parsed: std::result::Result<T, ParseError>, latex: &str) -> T {
+    match parsed {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            eprintln!("{}", latex);
+            eprintln!("{}^", " ".repeat(error.span.start));
+            eprintln!("Error: {}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn solve_system(system: &System, args: &Args) -> Result {
+    let unknowns = match &args.unknowns {
+        Some(unknowns) => unknowns
+            .split(',')
+            .map(|unknown| Variable::Letter(unknown.trim().to_string()))
+            .collect(),
+        None => system.letters(),
+    };
+    let solutions = system.solve(&unknowns);
+    if solutions.is_empty() {
+        println!(
+            "No solutions found for {}",
+            unknowns
+                .iter()
+                .map(|unknown| Equation::Variable(unknown.clone()).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    Result::Solutions(solutions)
+}
+
 fn process_operation(
     equation: Equation,
-    operation: Operation,
+    args: &Args,
     value_dict: &BTreeMap<Variable, f64>,
-    propagate_variables: &str,
 ) -> Result {
-    match operation {
+    let variable = &Variable::Letter(args.variable.clone());
+    let numeric_bounds = args.lower.zip(args.upper).filter(|_| args.numeric);
+    match args.operation {
         Operation::Simplify => {
-            let mut equation = equation.clone();
-            equation = equation.simplify_until_complete_with_print();
-            println!("{}", equation.to_numpy());
-            Result::Equation(equation)
+            let options = SimplifyOptions {
+                trace: true,
+                keep_factored: args.keep_factored,
+                ..Default::default()
+            };
+            let simplification = equation.simplify_with(&options);
+            for (i, rewrite) in simplification.trace.iter().enumerate() {
+                println!("{}: {}", i + 1, rewrite);
+            }
+            println!("{}", simplification.equation.to_numpy());
+            Result::Equation(simplification.equation)
+        }
+        Operation::Expand => Result::Equation(equation.expand()),
+        Operation::Factor => Result::Equation(equation.factor()),
+        Operation::SolveSystem => solve_system(
+            &System {
+                equations: vec![equation],
+            },
+            args,
+        ),
+        Operation::Solve => {
+            let solutions = equation.solve(variable);
+            if solutions.is_empty() {
+                println!(
+                    "No solutions found for {}",
+                    Equation::Variable(variable.clone())
+                );
+            }
+            Result::Equations(solutions)
+        }
+        Operation::Roots => {
+            let Some(interval) = args.lower.zip(args.upper) else {
+                eprintln!("Error: finding roots needs --lower and --upper");
+                std::process::exit(1);
+            };
+            let options = RootOptions {
+                values: value_dict.clone(),
+                ..Default::default()
+            };
+            match equation.find_roots(variable, interval, &options) {
+                Ok(roots) => {
+                    if roots.is_empty() {
+                        println!(
+                            "No roots found for {}",
+                            Equation::Variable(variable.clone())
+                        );
+                    }
+                    Result::Values(roots)
+                }
+                Err(error) => {
+                    eprintln!("Error: {}", error);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Operation::Calculate if !args.quantity.is_empty() || args.to.is_some() => {
+            let quantity = match equation.calculate_with_units(&parse_quantities(&args.quantity)) {
+                Ok(quantity) => quantity,
+                Err(error) => {
+                    eprintln!("Error: {}", error);
+                    std::process::exit(1);
+                }
+            };
+            let Some(to) = &args.to else {
+                return Result::Quantity(quantity.value, quantity.dimension.to_string());
+            };
+            match quantity.value_in(&parse_unit(to)) {
+                Some(value) => Result::Quantity(value, to.clone()),
+                None => {
+                    eprintln!("Error: cannot convert {} to {}", quantity, to);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Operation::Calculate if !args.vector.is_empty() => {
+            match equation.calculate_vector(value_dict, &parse_vectors(&args.vector)) {
+                Ok(VectorValue::Scalar(value)) => Result::Value(value),
+                Ok(VectorValue::Vector(components)) => Result::Vector(components),
+                Err(error) => {
+                    eprintln!("Error: {}", error);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Operation::Calculate => match equation.try_calculate(value_dict) {
+            Ok(value) => Result::Value(value),
+            Err(error) => {
+                eprintln!("Error: {}", error);
+                std::process::exit(1);
+            }
+        },
+        Operation::Units => {
+            let dimensions = parse_quantities(&args.quantity)
+                .into_iter()
+                .map(|(variable, quantity)| (variable, quantity.dimension))
+                .collect();
+            match equation.dimension(&dimensions) {
+                Ok(dimension) => Result::Dimension(dimension),
+                Err(error) => {
+                    eprintln!("Error: {}", error);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Operation::Differentiate if args.steps => {
+            let (derivative, steps) = equation.differentiate_with_steps(variable);
+            for (i, step) in steps.iter().enumerate() {
+                println!(
+                    "{}: {}: \\frac{{d}}{{d{}}}({}) = {}",
+                    i + 1,
+                    step.rule,
+                    Equation::Variable(variable.clone()),
+                    step.expression,
+                    step.derivative
+                );
+            }
+            Result::Equation(derivative)
+        }
+        Operation::Differentiate if args.partial.is_some() || args.nth > 1 => {
+            let variables = match &args.partial {
+                Some(partial) => partial
+                    .split(',')
+                    .map(|variable| Variable::Letter(variable.trim().to_string()))
+                    .collect(),
+                None => vec![variable.clone(); args.nth],
+            };
+            Result::Equation(equation.partial_derivative(&variables))
         }
-        Operation::Calculate => Result::Value(equation.calculate(value_dict)),
         Operation::Differentiate => {
-            let mut equation = equation
-                .clone()
-                .differentiate(&Variable::Letter("x".to_string()));
+            let mut equation = equation.clone().differentiate(variable);
             println!("Unsimplified: {}", equation);
             equation = equation.simplify_until_complete_with_print();
             Result::Equation(equation)
         }
-        Operation::Integrate => {
-            println!("Start integrate");
-            let mut equation = equation
-                .clone()
-                .integrate(&Variable::Letter("x".to_string()));
-            println!("Unsimplified: {}", equation);
-            equation = equation.simplify_until_complete_with_print();
-            Result::Equation(equation)
+        Operation::Gradient => {
+            let letters = equation.letters();
+            let gradient = equation.gradient(&letters);
+            Result::Equations(
+                letters
+                    .into_iter()
+                    .zip(gradient)
+                    .map(|(letter, component)| {
+                        let partial = Equation::Derivative(Box::new(Derivative {
+                            expression: equation.clone(),
+                            variables: vec![letter],
+                        }));
+                        Equation::Equals(Box::new((partial, component)))
+                    })
+                    .collect(),
+            )
         }
-        Operation::Error => {
-            let variables = propagate_variables.split(',').collect::<Vec<_>>();
-            let mut terms: Vec<Equation> = Vec::new();
-            for variable in variables {
-                let mut derivative =
-                    equation.differentiate(&Variable::Letter(variable.to_string()));
-                derivative = derivative.simplify_until_complete_with_print();
-                let term = Equation::Power(Box::new((
-                    Equation::Multiplication(vec![
-                        derivative,
-                        Equation::Variable(Variable::Letter(format!("s_{}", variable))),
-                    ]),
-                    Equation::Variable(Variable::Integer(2)),
-                )));
-                terms.push(term);
+        Operation::Hessian => {
+            let letters = equation.letters();
+            println!(
+                "Variables: {}",
+                letters
+                    .iter()
+                    .map(|letter| Equation::Variable(letter.clone()).to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            Result::Matrix(equation.hessian(&letters))
+        }
+        Operation::Determinant | Operation::Transpose | Operation::Inverse => {
+            let matrix = equation.simplify_until_complete();
+            let (result, requirement) = match args.operation {
+                Operation::Determinant => (matrix.determinant(), "a square matrix"),
+                Operation::Transpose => (matrix.transpose(), "a matrix"),
+                _ => (matrix.inverse(), "an invertible square matrix"),
+            };
+            match result {
+                Some(result) => Result::Equation(result.simplify_until_complete()),
+                None => {
+                    eprintln!("Error: {} is not {}", matrix, requirement);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Operation::Integrate if numeric_bounds.is_some() => {
+            let (lower, upper) = numeric_bounds.unwrap();
+            let options = QuadratureOptions {
+                values: value_dict.clone(),
+                ..Default::default()
+            };
+            match equation.integrate_numeric(variable, lower, upper, &options) {
+                Ok(quadrature) => {
+                    println!("Estimated error: {:e}", quadrature.error);
+                    Result::Value(quadrature.value)
+                }
+                Err(error) => {
+                    eprintln!("Error: {}", error);
+                    std::process::exit(1);
+                }
             }
-            let mut result = Equation::Power(Box::new((
-                Equation::Addition(terms),
-                Equation::Variable(Variable::Rational(Rational64::new(1, 2))),
-            )));
-            result = result.simplify_until_complete_with_print();
-            Result::Equation(result)
         }
-        _ => {
-            panic!("Operation not yet supported")
+        Operation::Integrate => match equation.integrate(variable) {
+            Some(integrated) => Result::Equation(integrated),
+            None => {
+                eprintln!("Error: no antiderivative found for {}", equation);
+                std::process::exit(1);
+            }
+        },
+        Operation::Series => {
+            let point = match parser::IR::latex_to_equation(
+                args.point.chars().collect::<Vec<_>>(),
+                args.implicit_multiplication,
+            ) {
+                Ok(point) => point,
+                Err(error) => {
+                    eprintln!("Error: {}", error);
+                    std::process::exit(1);
+                }
+            };
+            match equation.series(variable, &point, args.order) {
+                Some(series) => Result::Series(series),
+                None => {
+                    eprintln!("Error: no series found for {}", equation);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Operation::Error => {
+            let variables: Vec<String> = match &args.propagate_variables {
+                Some(variables) => variables
+                    .split(',')
+                    .map(|variable| variable.trim().to_string())
+                    .collect(),
+                None => equation
+                    .letters()
+                    .into_iter()
+                    .map(|letter| Equation::Variable(letter).to_string())
+                    .collect(),
+            };
+            let uncertainties = variables
+                .into_iter()
+                .map(|variable| {
+                    let uncertainty = Variable::Letter(format!("\\sigma_{{{}}}", variable));
+                    (Variable::Letter(variable), uncertainty)
+                })
+                .collect::<Vec<_>>();
+            Result::Equation(equation.propagate_uncertainty(&uncertainties))
         }
     }
 }
+
+///The physical constants with the quantities formatted as name=value unit, where the value is 1 if it is left out
+fn parse_quantities(quantities: &[String]) -> BTreeMap<Variable, Quantity> {
+    let mut parsed = fishrambeta::physicsvalues::physics_quantities();
+    for quantity in quantities {
+        let Some((name, quantity)) = quantity.split_once('=') else {
+            eprintln!(
+                "Error: quantity {} is not formatted as name=value unit",
+                quantity
+            );
+            std::process::exit(1);
+        };
+        let quantity = quantity.trim();
+        //The longest start that is a number is the value, so 2kg and 2 kg both work
+        let (value, unit) = (0..=quantity.len())
+            .rev()
+            .filter(|i| quantity.is_char_boundary(*i))
+            .find_map(|i| Some((quantity[..i].parse::<f64>().ok()?, &quantity[i..])))
+            .unwrap_or((1., quantity));
+        parsed.insert(
+            Variable::Letter(name.trim().to_string()),
+            parse_unit(unit.trim()).quantity(value),
+        );
+    }
+    parsed
+}
+
+fn parse_unit(unit: &str) -> Unit {
+    Unit::parse(unit).unwrap_or_else(|| {
+        eprintln!("Error: {} is not a known unit", unit);
+        std::process::exit(1);
+    })
+}
+
+fn parse_vectors(vectors: &[String]) -> BTreeMap<Variable, Vec<f64>> {
+    vectors
+        .iter()
+        .map(|vector| {
+            let Some((name, components)) = vector.split_once('=') else {
+                eprintln!("Error: vector {} is not formatted as name=x,y,z", vector);
+                std::process::exit(1);
+            };
+            let components = components
+                .split(',')
+                .map(|component| {
+                    component.trim().parse::<f64>().unwrap_or_else(|_| {
+                        eprintln!("Error: {} is not a number", component);
+                        std::process::exit(1);
+                    })
+                })
+                .collect();
+            (Variable::Vector(name.trim().to_string()), components)
+        })
+        .collect()
+}
@@ -1,3 +1,122 @@
+use clap::Parser;
+use fishrambeta::math::{Equation, Variable};
+use fishrambeta::parser;
+use render::Plot;
+use sample::Curve;
+
+mod render;
+mod sample;
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    //The equations to plot formatted in LaTeX, can be given more than once
+    #[arg(short, long, required = true)]
+    equation: Vec<String>,
+    //Also plot the derivative of every equation
+    #[arg(short, long, default_value_t = false)]
+    derivative: bool,
+    //The variable on the horizontal axis
+    #[arg(long, default_value = "x")]
+    variable: String,
+    #[arg(long, default_value_t = -10., allow_negative_numbers = true)]
+    from: f64,
+    #[arg(long, default_value_t = 10., allow_negative_numbers = true)]
+    to: f64,
+    //The range of the vertical axis, chosen to fit the curves if not given
+    #[arg(long, allow_negative_numbers = true, requires = "y_max")]
+    y_min: Option<f64>,
+    #[arg(long, allow_negative_numbers = true, requires = "y_min")]
+    y_max: Option<f64>,
+    #[arg(long, default_value_t = 1000)]
+    samples: usize,
+    //The file to write to, a png if it ends in .png and an svg otherwise
+    #[arg(short, long, default_value = "plot.svg")]
+    output: String,
+    #[arg(long, default_value_t = 800)]
+    width: u32,
+    #[arg(long, default_value_t = 600)]
+    height: u32,
+    //Assume consecutive letters multiply variables with those names
+    #[arg(long, default_value_t = false)]
+    implicit_multiplication: bool,
+}
+
 fn main() {
-    println!("Hello, world!");
+    let args = Args::parse();
+    if args.from >= args.to || args.samples < 2 {
+        exit_with_error("the range must be increasing and have at least 2 samples");
+    }
+    if let (Some(y_min), Some(y_max)) = (args.y_min, args.y_max) {
+        if y_min >= y_max {
+            exit_with_error("the y range must be increasing");
+        }
+    }
+    let variable = Variable::Letter(args.variable.clone());
+    let value_dict = fishrambeta::physicsvalues::physics_values();
+
+    let mut equations = vec![];
+    for latex in &args.equation {
+        let equation = parse(latex, args.implicit_multiplication);
+        if args.derivative {
+            let derivative = equation.differentiate(&variable).simplify_until_complete();
+            let label = format!("\\frac{{d}}{{d{}}}: {}", args.variable, derivative);
+            equations.push((equation.to_latex(), equation));
+            equations.push((label, derivative));
+        } else {
+            equations.push((equation.to_latex(), equation));
+        }
+    }
+
+    let curves = equations
+        .into_iter()
+        .map(|(label, equation)| {
+            Curve::sample(
+                &equation,
+                &variable,
+                &value_dict,
+                (args.from, args.to),
+                args.samples,
+                label,
+            )
+            .unwrap_or_else(|error| exit_with_error(&error.to_string()))
+        })
+        .collect::<Vec<_>>();
+    let y_range = args
+        .y_min
+        .zip(args.y_max)
+        .unwrap_or_else(|| Plot::automatic_y_range(&curves));
+    let plot = Plot {
+        curves,
+        x_range: (args.from, args.to),
+        y_range,
+        width: args.width,
+        height: args.height,
+    };
+
+    let written = if args.output.ends_with(".png") {
+        plot.to_png()
+            .and_then(|png| std::fs::write(&args.output, png).map_err(|error| error.to_string()))
+    } else {
+        std::fs::write(&args.output, plot.to_svg()).map_err(|error| error.to_string())
+    };
+    match written {
+        Ok(()) => println!("Wrote {}", args.output),
+        Err(error) => exit_with_error(&error),
+    }
+}
+
+fn parse(latex: &str, implicit_multiplication: bool) -> Equation {
+    match parser::IR::latex_to_equation(latex.chars().collect(), implicit_multiplication) {
+        Ok(equation) => equation,
+        Err(error) => {
+            eprintln!("{}", latex);
+            eprintln!("{}^", " ".repeat(error.span.start));
+            exit_with_error(&error.to_string())
+        }
+    }
+}
+
+fn exit_with_error(error: &str) -> ! {
+    eprintln!("Error: {}", error);
+    std::process::exit(1);
 }
@@ -0,0 +1,222 @@
+use crate::sample::{robust_range, Curve};
+use resvg::{tiny_skia, usvg};
+use std::fmt::Write;
+
+const COLORS: [&str; 6] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b",
+];
+///Space around the plot area for tick labels, left, right, top and bottom
+const MARGINS: (f64, f64, f64, f64) = (60., 20., 20., 40.);
+
+pub struct Plot {
+    pub curves: Vec<Curve>,
+    pub x_range: (f64, f64),
+    pub y_range: (f64, f64),
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Plot {
+    ///A y range that contains most of every curve with some padding, ignoring the values near poles
+    pub fn automatic_y_range(curves: &[Curve]) -> (f64, f64) {
+        let (low, high) = robust_range(
+            curves
+                .iter()
+                .flat_map(|curve| curve.segments.iter().flatten().map(|point| point.1)),
+        );
+        let padding = if high > low { (high - low) * 0.1 } else { 1. };
+        (low - padding, high + padding)
+    }
+
+    pub fn to_svg(&self) -> String {
+        let (width, height) = (self.width as f64, self.height as f64);
+        let (left, right, top, bottom) =
+            (MARGINS.0, width - MARGINS.1, MARGINS.2, height - MARGINS.3);
+        let to_x = |x: f64| {
+            left + (x - self.x_range.0) / (self.x_range.1 - self.x_range.0) * (right - left)
+        };
+        let to_y = |y: f64| {
+            bottom - (y - self.y_range.0) / (self.y_range.1 - self.y_range.0) * (bottom - top)
+        };
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="DejaVu Sans, Helvetica, Arial, sans-serif" font-size="12">"#,
+            self.width, self.height, self.width, self.height
+        )
+        .unwrap();
+        writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+        writeln!(
+            svg,
+            r#"<clipPath id="area"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+            left,
+            top,
+            right - left,
+            bottom - top
+        )
+        .unwrap();
+
+        for x in ticks(self.x_range) {
+            let position = to_x(x);
+            writeln!(
+                svg,
+                r##"<line x1="{position:.2}" y1="{top}" x2="{position:.2}" y2="{bottom}" stroke="#e0e0e0"/>"##
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{position:.2}" y="{}" text-anchor="middle">{}</text>"#,
+                bottom + 16.,
+                format_tick(x, self.x_range)
+            )
+            .unwrap();
+        }
+        for y in ticks(self.y_range) {
+            let position = to_y(y);
+            writeln!(
+                svg,
+                r##"<line x1="{left}" y1="{position:.2}" x2="{right}" y2="{position:.2}" stroke="#e0e0e0"/>"##
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{}" y="{:.2}" text-anchor="end">{}</text>"#,
+                left - 6.,
+                position + 4.,
+                format_tick(y, self.y_range)
+            )
+            .unwrap();
+        }
+        if self.x_range.0 <= 0. && 0. <= self.x_range.1 {
+            let position = to_x(0.);
+            writeln!(
+                svg,
+                r#"<line x1="{position:.2}" y1="{top}" x2="{position:.2}" y2="{bottom}" stroke="black"/>"#
+            )
+            .unwrap();
+        }
+        if self.y_range.0 <= 0. && 0. <= self.y_range.1 {
+            let position = to_y(0.);
+            writeln!(
+                svg,
+                r#"<line x1="{left}" y1="{position:.2}" x2="{right}" y2="{position:.2}" stroke="black"/>"#
+            )
+            .unwrap();
+        }
+        writeln!(
+            svg,
+            r##"<rect x="{left}" y="{top}" width="{}" height="{}" fill="none" stroke="#808080"/>"##,
+            right - left,
+            bottom - top
+        )
+        .unwrap();
+
+        writeln!(
+            svg,
+            r#"<g clip-path="url(#area)" fill="none" stroke-width="2">"#
+        )
+        .unwrap();
+        for (curve, color) in self.curves.iter().zip(COLORS.iter().cycle()) {
+            for segment in &curve.segments {
+                let points = segment
+                    .iter()
+                    //Points far outside of the plot area are clamped, very large coordinates are not drawn reliably
+                    .map(|(x, y)| {
+                        format!(
+                            "{:.2},{:.2}",
+                            to_x(*x),
+                            to_y(*y).clamp(-height, 2. * height)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(svg, r#"<polyline points="{points}" stroke="{color}"/>"#).unwrap();
+            }
+        }
+        writeln!(svg, "</g>").unwrap();
+
+        let longest_label = self.curves.iter().map(|curve| curve.label.len()).max();
+        writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="white" fill-opacity="0.8"/>"#,
+            left + 4.,
+            top + 4.,
+            40. + 7. * longest_label.unwrap_or(0) as f64,
+            18. * self.curves.len() as f64 + 6.
+        )
+        .unwrap();
+        for (i, (curve, color)) in self.curves.iter().zip(COLORS.iter().cycle()).enumerate() {
+            let y = top + 16. + 18. * i as f64;
+            writeln!(
+                svg,
+                r#"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="{color}" stroke-width="2"/>"#,
+                left + 10.,
+                left + 30.
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{}" y="{}">{}</text>"#,
+                left + 36.,
+                y + 4.,
+                escape(&curve.label)
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    ///Renders the svg to a png, text is drawn with the fonts installed on the system
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        let mut options = usvg::Options::default();
+        options.fontdb_mut().load_system_fonts();
+        let tree =
+            usvg::Tree::from_str(&self.to_svg(), &options).map_err(|error| error.to_string())?;
+        let mut pixmap = tiny_skia::Pixmap::new(self.width, self.height)
+            .ok_or_else(|| format!("cannot create a {}x{} image", self.width, self.height))?;
+        resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+        pixmap.encode_png().map_err(|error| error.to_string())
+    }
+}
+
+///Evenly spaced values within the range, with a step of 1, 2 or 5 times a power of ten
+fn ticks((low, high): (f64, f64)) -> Vec<f64> {
+    let step = tick_step((low, high));
+    let first = (low / step).ceil() as i64;
+    let last = (high / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+fn tick_step((low, high): (f64, f64)) -> f64 {
+    let rough = (high - low) / 8.;
+    if !rough.is_normal() || rough < 0. {
+        return 1.;
+    }
+    let magnitude = 10f64.powf(rough.log10().floor());
+    [1., 2., 5.]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough)
+        .unwrap_or(10. * magnitude)
+}
+
+fn format_tick(value: f64, range: (f64, f64)) -> String {
+    let decimals = (-tick_step(range).log10().floor()).max(0.) as usize;
+    let formatted = format!("{:.*}", decimals, value);
+    if formatted
+        .trim_start_matches('-')
+        .chars()
+        .all(|c| c == '0' || c == '.')
+    {
+        return formatted.trim_start_matches('-').to_string();
+    }
+    formatted
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
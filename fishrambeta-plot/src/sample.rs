@@ -0,0 +1,143 @@
+use fishrambeta::math::{Equation, EvalError, Variable};
+use std::collections::BTreeMap;
+
+///A sampled function, split into segments wherever it has a pole, a jump or no value
+pub struct Curve {
+    pub label: String,
+    pub segments: Vec<Vec<(f64, f64)>>,
+}
+
+impl Curve {
+    ///Samples the equation at evenly spaced values of the variable between from and to
+    pub fn sample(
+        equation: &Equation,
+        variable: &Variable,
+        values: &BTreeMap<Variable, f64>,
+        (from, to): (f64, f64),
+        samples: usize,
+        label: String,
+    ) -> Result<Self, EvalError> {
+        let mut values = values.clone();
+        values.insert(variable.clone(), (from + to) / 2.);
+        //Errors at a single point are gaps in the curve
+        match equation.try_calculate(&values) {
+            Ok(_)
+            | Err(
+                EvalError::DivisionByZero(_)
+                | EvalError::NonPositiveLogarithm(_, _)
+                | EvalError::NegativeRoot(_, _)
+                | EvalError::OutsideDomain(_, _),
+            ) => {}
+            Err(error) => return Err(error),
+        }
+        let mut f = |x: f64| {
+            values.insert(variable.clone(), x);
+            equation.try_calculate(&values).unwrap_or(f64::NAN)
+        };
+
+        let points = (0..samples)
+            .map(|i| {
+                let x = from + (to - from) * i as f64 / (samples - 1).max(1) as f64;
+                (x, f(x))
+            })
+            .collect::<Vec<_>>();
+        let (low, high) = robust_range(points.iter().map(|point| point.1));
+        let span = (high - low).max(1e-12);
+
+        let mut segments = vec![];
+        let mut segment: Vec<(f64, f64)> = vec![];
+        for &(x, y) in &points {
+            if !y.is_finite() {
+                segments.push(std::mem::take(&mut segment));
+                continue;
+            }
+            if let Some(&previous) = segment.last() {
+                if (y - previous.1).abs() > 0.05 * span
+                    && is_discontinuous(&mut f, previous, (x, y))
+                {
+                    segments.push(std::mem::take(&mut segment));
+                }
+            }
+            segment.push((x, y));
+        }
+        segments.push(segment);
+        segments.retain(|segment| !segment.is_empty());
+        Ok(Curve { label, segments })
+    }
+}
+
+///The 5th and 95th percentile of the finite values, so a pole does not decide the scale of the plot
+pub fn robust_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let mut values = values.filter(|value| value.is_finite()).collect::<Vec<_>>();
+    if values.is_empty() {
+        return (-1., 1.);
+    }
+    values.sort_by(f64::total_cmp);
+    let percentile = |p: f64| values[((values.len() - 1) as f64 * p).round() as usize];
+    (percentile(0.05), percentile(0.95))
+}
+
+///Bisects towards the largest change between two samples, a continuous function has a vanishing change while a jump or pole keeps it
+fn is_discontinuous(
+    f: &mut impl FnMut(f64) -> f64,
+    (mut x0, mut y0): (f64, f64),
+    (mut x1, mut y1): (f64, f64),
+) -> bool {
+    let jump = (y1 - y0).abs();
+    for _ in 0..30 {
+        let x = (x0 + x1) / 2.;
+        let y = f(x);
+        if !y.is_finite() {
+            return true;
+        }
+        if (y - y0).abs() > (y1 - y).abs() {
+            (x1, y1) = (x, y);
+        } else {
+            (x0, y0) = (x, y);
+        }
+    }
+    (y1 - y0).abs() > jump / 2.
+}
+
+#[cfg(test)]
+mod test {
+    use super::Curve;
+    use fishrambeta::math::Variable;
+    use fishrambeta::parser::IR;
+    use std::collections::BTreeMap;
+
+    fn segments(latex: &str) -> usize {
+        let equation = IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+        let x = Variable::Letter("x".to_string());
+        Curve::sample(
+            &equation,
+            &x,
+            &BTreeMap::new(),
+            (-5., 5.),
+            500,
+            String::new(),
+        )
+        .unwrap()
+        .segments
+        .len()
+    }
+
+    #[test]
+    fn test_poles_split_segments() {
+        assert_eq!(segments("x^3-x"), 1);
+        assert_eq!(segments("\\frac{1}{x-1}"), 2);
+        assert_eq!(segments("\\tan(x)"), 5);
+        assert_eq!(segments("\\frac{\\sqrt{x^2}}{x}"), 2);
+        assert_eq!(segments("\\ln(x)"), 1);
+    }
+
+    #[test]
+    fn test_vectors_are_not_sampled() {
+        let equation =
+            IR::latex_to_equation("\\begin{pmatrix}x\\end{pmatrix}".chars().collect(), false)
+                .unwrap();
+        let x = Variable::Letter("x".to_string());
+        let curve = Curve::sample(&equation, &x, &BTreeMap::new(), (0., 1.), 10, String::new());
+        assert!(curve.is_err());
+    }
+}
@@ -1,46 +1,136 @@
-use fishrambeta::{self, math::Variable, physicsvalues};
+use fishrambeta::{
+    self,
+    math::{Equation, Measurement, RootOptions, Unit, Variable},
+    physicsvalues,
+};
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
-pub fn simplify(equation: &str) -> String {
-    let parsed = fishrambeta::parser::IR::latex_to_equation(
-        equation.to_string().chars().collect::<Vec<_>>(),
-        true,
-    );
+pub fn simplify(equation: &str) -> Result<String, JsError> {
+    let parsed = parse(equation)?;
     let simplified = parsed.simplify_until_complete();
-    
-    fishrambeta::parser::IR::equation_to_latex(simplified, true)
+
+    Ok(fishrambeta::parser::IR::equation_to_latex(simplified, true))
 }
 
+///Differentiates to each of the variables, which are seperated by commas, in turn, so "x,x" is the second derivative to x
 #[wasm_bindgen]
-pub fn differentiate(equation: &str) -> String {
-    let parsed = fishrambeta::parser::IR::latex_to_equation(
-        equation.to_string().chars().collect::<Vec<_>>(),
+pub fn differentiate(equation: &str, variables: &str) -> Result<String, JsError> {
+    let parsed = parse(equation)?;
+    let variables = variables
+        .split(',')
+        .map(|variable| Variable::Letter(variable.trim().to_string()))
+        .collect::<Vec<_>>();
+    let differentiated = parsed.partial_derivative(&variables);
+
+    Ok(fishrambeta::parser::IR::equation_to_latex(
+        differentiated,
         true,
-    );
-    let differentiated = parsed
-        .differentiate(&Variable::Letter("x".to_string()))
-        .simplify_until_complete();
-    
-    fishrambeta::parser::IR::equation_to_latex(differentiated, true)
+    ))
+}
+
+///A rule applied while differentiating, the expression it was applied to and its derivative are LaTeX
+#[wasm_bindgen(getter_with_clone)]
+pub struct DifferentiationStep {
+    pub rule: String,
+    pub expression: String,
+    pub derivative: String,
+}
+
+///The rules used to differentiate with respect to x, outer expressions before the subexpressions they need
+#[wasm_bindgen]
+pub fn differentiate_with_steps(equation: &str) -> Result<Vec<DifferentiationStep>, JsError> {
+    let parsed = parse(equation)?;
+    let (_, steps) = parsed.differentiate_with_steps(&Variable::Letter("x".to_string()));
+
+    Ok(steps
+        .into_iter()
+        .map(|step| DifferentiationStep {
+            rule: step.rule.to_string(),
+            expression: step.expression,
+            derivative: step.derivative,
+        })
+        .collect())
+}
+
+///The gradient to every letter in the equation in sorted order, as a column vector
+#[wasm_bindgen]
+pub fn gradient(equation: &str) -> Result<String, JsError> {
+    let parsed = parse(equation)?;
+    let gradient = parsed.gradient(&parsed.letters());
+
+    Ok(matrix_to_latex(
+        gradient
+            .into_iter()
+            .map(|component| vec![component])
+            .collect(),
+    ))
 }
 
+///The Hessian to every letter in the equation in sorted order
 #[wasm_bindgen]
-pub fn integrate(equation: &str) -> String {
-    let parsed = fishrambeta::parser::IR::latex_to_equation(
-        equation.to_string().chars().collect::<Vec<_>>(),
+pub fn hessian(equation: &str) -> Result<String, JsError> {
+    let parsed = parse(equation)?;
+
+    Ok(matrix_to_latex(parsed.hessian(&parsed.letters())))
+}
+
+///The determinant of the matrix after simplifying it, so products of matrices are multiplied out first
+#[wasm_bindgen]
+pub fn determinant(equation: &str) -> Result<String, JsError> {
+    let parsed = parse(equation)?.simplify_until_complete();
+    let determinant = parsed
+        .determinant()
+        .ok_or_else(|| JsError::new("not a square matrix"))?;
+
+    Ok(fishrambeta::parser::IR::equation_to_latex(
+        determinant.simplify_until_complete(),
         true,
-    );
-    let differentiated = parsed
+    ))
+}
+
+#[wasm_bindgen]
+pub fn inverse(equation: &str) -> Result<String, JsError> {
+    let parsed = parse(equation)?.simplify_until_complete();
+    let inverse = parsed
+        .inverse()
+        .ok_or_else(|| JsError::new("not an invertible square matrix"))?;
+
+    Ok(fishrambeta::parser::IR::equation_to_latex(
+        inverse.simplify_until_complete(),
+        true,
+    ))
+}
+
+#[wasm_bindgen]
+pub fn integrate(equation: &str) -> Result<String, JsError> {
+    let parsed = parse(equation)?;
+    let integrated = parsed
         .integrate(&Variable::Letter("x".to_string()))
-        .simplify_until_complete();
-    
-    fishrambeta::parser::IR::equation_to_latex(differentiated, true)
+        .ok_or_else(|| JsError::new("no antiderivative found"))?;
+
+    Ok(fishrambeta::parser::IR::equation_to_latex(integrated, true))
 }
 
+///The series of the equation in x around the point up to and including the power order
 #[wasm_bindgen]
-pub fn calculate(equation: &str, user_values_keys: &str, user_values_values: &[f64]) -> f64 {
+pub fn series(equation: &str, point: &str, order: u32) -> Result<String, JsError> {
+    let parsed = parse(equation)?;
+    let point = parse(point)?;
+    let series = parsed
+        .series(&Variable::Letter("x".to_string()), &point, order)
+        .ok_or_else(|| JsError::new("no series found"))?;
+
+    Ok(series.to_latex())
+}
+
+#[wasm_bindgen]
+pub fn calculate(
+    equation: &str,
+    user_values_keys: &str,
+    user_values_values: &[f64],
+) -> Result<f64, JsError> {
     console_error_panic_hook::set_once();
     let mut values = physicsvalues::physics_values();
     let user_values_hashmap = user_values_to_hashmap(
@@ -48,11 +138,242 @@ pub fn calculate(equation: &str, user_values_keys: &str, user_values_values: &[f
         user_values_values,
     );
     values.extend(user_values_hashmap);
-    let equationstring = equation.to_string().chars().collect::<Vec<_>>();
-    let parsed: fishrambeta::math::Equation =
-        fishrambeta::parser::IR::latex_to_equation(equationstring, true);
-    
-    parsed.calculate(&values)
+    let parsed = parse(equation)?;
+
+    parsed
+        .try_calculate(&values)
+        .map_err(|error| JsError::new(&error.to_string()))
+}
+
+///Calculates the equation with units, the units of the user values are seperated like their keys, and gives the result in the unit to or in SI base units if to is empty
+#[wasm_bindgen]
+pub fn calculate_with_units(
+    equation: &str,
+    user_values_keys: &str,
+    user_values_values: &[f64],
+    user_values_units: &str,
+    to: &str,
+) -> Result<String, JsError> {
+    console_error_panic_hook::set_once();
+    let mut quantities = physicsvalues::physics_quantities();
+    for ((key, value), unit) in user_values_keys
+        .split("\\n\\n")
+        .zip(user_values_values)
+        .zip(user_values_units.split("\\n\\n"))
+    {
+        let unit = parse_unit(unit)?;
+        quantities.insert(Variable::Letter(key.to_string()), unit.quantity(*value));
+    }
+    let parsed = parse(equation)?;
+
+    let quantity = parsed
+        .calculate_with_units(&quantities)
+        .map_err(|error| JsError::new(&error.to_string()))?;
+    if to.trim().is_empty() {
+        return Ok(quantity.to_string());
+    }
+    match quantity.value_in(&parse_unit(to)?) {
+        Some(value) => Ok(format!("{} {}", value, to)),
+        None => Err(JsError::new(&format!(
+            "cannot convert {} to {}",
+            quantity, to
+        ))),
+    }
+}
+
+///The roots of the equation in the variable between lower and upper, with the physical constants as values of the other variables
+#[wasm_bindgen]
+pub fn find_roots(
+    equation: &str,
+    variable: &str,
+    lower: f64,
+    upper: f64,
+) -> Result<Vec<f64>, JsError> {
+    console_error_panic_hook::set_once();
+    let options = RootOptions {
+        values: physicsvalues::physics_values(),
+        ..Default::default()
+    };
+    parse(equation)?
+        .find_roots(
+            &Variable::Letter(variable.to_string()),
+            (lower, upper),
+            &options,
+        )
+        .map_err(|error| JsError::new(&error.to_string()))
+}
+
+///Solves the equations, seperated by \\\\, commas or newlines, for the unknowns, which are seperated by commas, with one solution per line
+#[wasm_bindgen]
+pub fn solve_system(equations: &str, unknowns: &str) -> Result<String, JsError> {
+    let system =
+        fishrambeta::parser::IR::latex_to_system(equations.chars().collect::<Vec<_>>(), true)
+            .map_err(|error| JsError::new(&error.to_string()))?;
+    let unknowns = unknowns
+        .split(',')
+        .map(|unknown| Variable::Letter(unknown.trim().to_string()))
+        .collect::<Vec<_>>();
+
+    Ok(system
+        .solve(&unknowns)
+        .into_iter()
+        .map(|solution| {
+            solution
+                .into_iter()
+                .map(|equation| fishrambeta::parser::IR::equation_to_latex(equation, true))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+#[wasm_bindgen]
+pub fn propagate_uncertainty(equation: &str, variables: &str) -> Result<String, JsError> {
+    propagate_correlated_uncertainty(equation, variables, "")
+}
+
+///The uncertainty of the equation where the pairs in correlated, seperated by commas and with a space between the two variables, are correlated, the covariance of x and y is \\sigma_{xy}
+#[wasm_bindgen]
+pub fn propagate_correlated_uncertainty(
+    equation: &str,
+    variables: &str,
+    correlated: &str,
+) -> Result<String, JsError> {
+    let parsed = parse(equation)?;
+    let uncertainties = variables
+        .split(',')
+        .map(|variable| {
+            let variable = variable.trim();
+            (
+                Variable::Letter(variable.to_string()),
+                Variable::Letter(format!("\\sigma_{{{}}}", variable)),
+            )
+        })
+        .collect::<Vec<_>>();
+    let covariances = correlated
+        .split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(
+            |pair| match pair.split_whitespace().collect::<Vec<_>>()[..] {
+                [first, second] => Ok((
+                    Variable::Letter(first.to_string()),
+                    Variable::Letter(second.to_string()),
+                    Variable::Letter(format!("\\sigma_{{{}{}}}", first, second)),
+                )),
+                _ => Err(JsError::new(&format!(
+                    "{} is not two variables seperated by a space",
+                    pair.trim()
+                ))),
+            },
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(fishrambeta::parser::IR::equation_to_latex(
+        parsed.propagate_correlated_uncertainty(&uncertainties, &covariances),
+        true,
+    ))
+}
+
+///Returns the value and its uncertainty, measurements have a value and an uncertainty while the other user values are exact
+#[wasm_bindgen]
+pub fn calculate_with_uncertainty(
+    equation: &str,
+    user_values_keys: &str,
+    user_values_values: &[f64],
+    measurement_keys: &str,
+    measurement_values: &[f64],
+    measurement_uncertainties: &[f64],
+) -> Result<Vec<f64>, JsError> {
+    calculate_with_correlated_uncertainty(
+        equation,
+        user_values_keys,
+        user_values_values,
+        measurement_keys,
+        measurement_values,
+        measurement_uncertainties,
+        "",
+        &[],
+    )
+}
+
+///Returns the value and its uncertainty like calculate_with_uncertainty, the covariance keys are pairs of measurements seperated by a comma
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_with_correlated_uncertainty(
+    equation: &str,
+    user_values_keys: &str,
+    user_values_values: &[f64],
+    measurement_keys: &str,
+    measurement_values: &[f64],
+    measurement_uncertainties: &[f64],
+    covariance_keys: &str,
+    covariance_values: &[f64],
+) -> Result<Vec<f64>, JsError> {
+    console_error_panic_hook::set_once();
+    let mut values = physicsvalues::physics_values();
+    values.extend(user_values_to_hashmap(
+        user_values_keys.split("\\n\\n").collect::<Vec<_>>(),
+        user_values_values,
+    ));
+    let measurements = measurement_keys
+        .split("\\n\\n")
+        .zip(measurement_values.iter().zip(measurement_uncertainties))
+        .map(|(key, (value, uncertainty))| {
+            (
+                Variable::Letter(key.to_string()),
+                Measurement {
+                    value: *value,
+                    uncertainty: *uncertainty,
+                },
+            )
+        })
+        .collect();
+    let covariances = covariance_keys
+        .split("\\n\\n")
+        .zip(covariance_values)
+        .map(|(key, covariance)| match key.split_once(',') {
+            Some((first, second)) => Ok((
+                (
+                    Variable::Letter(first.trim().to_string()),
+                    Variable::Letter(second.trim().to_string()),
+                ),
+                *covariance,
+            )),
+            None => Err(JsError::new(&format!(
+                "{} is not two measurements seperated by a comma",
+                key
+            ))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let parsed = parse(equation)?;
+
+    let result = parsed
+        .calculate_with_uncertainty(&measurements, &covariances, &values)
+        .map_err(|error| JsError::new(&error.to_string()))?;
+    Ok(vec![result.value, result.uncertainty])
+}
+
+fn parse(equation: &str) -> Result<Equation, JsError> {
+    fishrambeta::parser::IR::latex_to_equation(equation.chars().collect::<Vec<_>>(), true)
+        .map_err(|error| JsError::new(&error.to_string()))
+}
+
+fn parse_unit(unit: &str) -> Result<Unit, JsError> {
+    Unit::parse(unit).ok_or_else(|| JsError::new(&format!("{} is not a known unit", unit)))
+}
+
+fn matrix_to_latex(rows: Vec<Vec<Equation>>) -> String {
+    let rows = rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|entry| fishrambeta::parser::IR::equation_to_latex(entry, true))
+                .collect::<Vec<_>>()
+                .join("&")
+        })
+        .collect::<Vec<_>>();
+    format!("\\begin{{bmatrix}}{}\\end{{bmatrix}}", rows.join("\\\\"))
 }
 
 fn user_values_to_hashmap(keys: Vec<&str>, values: &[f64]) -> HashMap<Variable, f64> {
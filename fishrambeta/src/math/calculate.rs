@@ -1,5 +1,6 @@
-use crate::math::{Equation, Variable};
+use crate::math::{Constant, Dimension, Equation, Variable};
 use std::collections::BTreeMap;
+use std::fmt;
 
 impl Equation {
     pub fn calculate(&self, values: &BTreeMap<Variable, f64>) -> f64 {
@@ -10,31 +11,322 @@ impl Equation {
                     Variable::Rational(rational) => {
                         return *(*rational).numer() as f64 / *(*rational).denom() as f64
                     }
+                    Variable::Constant(Constant::Infinity) => return f64::INFINITY,
                     _ => {}
                 }
-                values[&variable]
+                values[variable]
             }
             Equation::Negative(negative) => -negative.calculate(values),
-            Equation::Addition(addition) => {
-                return addition.iter().map(|x| x.calculate(values)).sum()
-            }
+            Equation::Addition(addition) => addition.iter().map(|x| x.calculate(values)).sum(),
             Equation::Multiplication(multiplication) => {
-                return multiplication
-                    .iter()
-                    .map(|x| x.calculate(values))
-                    .product()
+                multiplication.iter().map(|x| x.calculate(values)).product()
             }
             Equation::Division(division) => {
                 division.0.calculate(values) / division.1.calculate(values)
             }
-            Equation::Power(power) => {
-                power.0.calculate(values).powf(power.1.calculate(values))
-            }
+            Equation::Power(power) => power.0.calculate(values).powf(power.1.calculate(values)),
             Equation::Ln(ln) => ln.calculate(values).ln(),
+            Equation::Exp(exp) => exp.calculate(values).exp(),
             Equation::Sin(sin) => sin.calculate(values).sin(),
             Equation::Cos(cos) => cos.calculate(values).cos(),
             Equation::Abs(abs) => abs.calculate(values).abs(),
+            Equation::Arcsin(arcsin) => arcsin.calculate(values).asin(),
+            Equation::Tan(tan) => tan.calculate(values).tan(),
+            Equation::Arccos(arccos) => arccos.calculate(values).acos(),
+            Equation::Arctan(arctan) => arctan.calculate(values).atan(),
+            Equation::Sinh(sinh) => sinh.calculate(values).sinh(),
+            Equation::Cosh(cosh) => cosh.calculate(values).cosh(),
+            Equation::Tanh(tanh) => tanh.calculate(values).tanh(),
+            Equation::Integral(integral) => integral.calculate(values),
+            Equation::Limit(limit) => limit
+                .evaluate()
+                .map_or(f64::NAN, |value| value.calculate(values)),
+            Equation::Derivative(derivative) => derivative.evaluate().calculate(values),
+            Equation::Dot(_) | Equation::Cross(_) => {
+                panic!("Cannot calculate vectors, use calculate_vector")
+            }
+            Equation::Matrix(_) => panic!("Cannot calculate matrices, calculate their entries"),
             Equation::Equals(_) => panic!("Cannot calculate equals"),
         }
     }
 }
+
+///An error encountered while numerically evaluating an equation
+#[derive(Clone, Debug, PartialEq)]
+pub enum EvalError {
+    ///Every variable without a value, in order of first occurrence
+    UnboundVariables(Vec<Variable>),
+    ///The denominator that evaluated to zero
+    DivisionByZero(Equation),
+    ///The argument of a logarithm that is zero or negative, with its value
+    NonPositiveLogarithm(Equation, f64),
+    ///A negative base raised to a non-integer power, with the value of the base
+    NegativeRoot(Equation, f64),
+    ///The argument of an inverse trigonometric function outside of [-1, 1], with its value
+    OutsideDomain(Equation, f64),
+    ///Equations containing = do not have a value
+    Equals,
+    ///An integral without bounds does not have a value
+    IndefiniteIntegral(Equation),
+    ///A definite integral that could not be calculated, for example because it diverges
+    Divergent(Equation),
+    ///A limit that does not exist or could not be calculated
+    NoLimit(Equation),
+    ///A numerical integral that stayed above the tolerance, with its value and estimated error
+    NotConverged(f64, f64),
+    ///A vector where only a scalar is allowed, like the argument of a function
+    NotAScalar(Equation),
+    ///A scalar in a dot or cross product
+    NotAVector(Equation),
+    ///Vectors with a different number of components, a vector added to a scalar or a cross product of vectors without three components
+    DimensionMismatch(Equation),
+    ///Terms of a sum or sides of = with different units, with both of their dimensions
+    IncompatibleUnits(Equation, Box<(Dimension, Dimension)>),
+    ///A unit where only a number is allowed, like the argument of a function or an exponent
+    NotDimensionless(Equation, Box<Dimension>),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::UnboundVariables(variables) => write!(
+                f,
+                "no value for {}",
+                variables
+                    .iter()
+                    .map(|variable| Equation::Variable(variable.clone()).to_latex())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            EvalError::DivisionByZero(denominator) => {
+                write!(f, "division by zero, {} is 0", denominator)
+            }
+            EvalError::NonPositiveLogarithm(argument, value) => {
+                write!(f, "logarithm of {} which is {}", argument, value)
+            }
+            EvalError::NegativeRoot(base, value) => {
+                write!(f, "non-integer power of {} which is {}", base, value)
+            }
+            EvalError::OutsideDomain(argument, value) => {
+                write!(f, "{} is {}, which is outside of [-1, 1]", argument, value)
+            }
+            EvalError::Equals => write!(f, "cannot calculate an equation containing ="),
+            EvalError::IndefiniteIntegral(integral) => {
+                write!(f, "cannot calculate the indefinite integral {}", integral)
+            }
+            EvalError::Divergent(integral) => write!(f, "the integral {} diverges", integral),
+            EvalError::NoLimit(limit) => write!(f, "could not find the limit {}", limit),
+            EvalError::NotConverged(value, error) => write!(
+                f,
+                "numerical integration did not converge, got {} with an estimated error of {}",
+                value, error
+            ),
+            EvalError::NotAScalar(vector) => write!(f, "{} is a vector, expected a scalar", vector),
+            EvalError::NotAVector(scalar) => write!(f, "{} is a scalar, expected a vector", scalar),
+            EvalError::DimensionMismatch(equation) => {
+                write!(f, "the dimensions in {} do not match", equation)
+            }
+            EvalError::IncompatibleUnits(equation, dimensions) => write!(
+                f,
+                "the units in {} do not match, {} and {}",
+                equation, dimensions.0, dimensions.1
+            ),
+            EvalError::NotDimensionless(equation, dimension) => {
+                write!(f, "{} has unit {}, expected a number", equation, dimension)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+impl Equation {
+    ///Calculates the value like calculate, but reports unbound variables and domain errors instead of panicking or returning NaN
+    pub fn try_calculate(&self, values: &BTreeMap<Variable, f64>) -> Result<f64, EvalError> {
+        let mut unbound_variables = vec![];
+        self.collect_unbound_variables(values, &mut unbound_variables);
+        if !unbound_variables.is_empty() {
+            return Err(EvalError::UnboundVariables(unbound_variables));
+        }
+        self.checked_calculate(values)
+    }
+
+    ///The variables that need a value to calculate the equation, in order of first occurrence
+    pub fn free_variables(&self) -> Vec<Variable> {
+        let mut variables = vec![];
+        self.collect_unbound_variables(&BTreeMap::new(), &mut variables);
+        variables
+    }
+
+    fn collect_unbound_variables(
+        &self,
+        values: &BTreeMap<Variable, f64>,
+        unbound_variables: &mut Vec<Variable>,
+    ) {
+        match self {
+            Equation::Variable(
+                Variable::Integer(_) | Variable::Rational(_) | Variable::Constant(_),
+            ) => {}
+            Equation::Variable(variable) => {
+                if !values.contains_key(variable) && !unbound_variables.contains(variable) {
+                    unbound_variables.push(variable.clone());
+                }
+            }
+            Equation::Addition(terms) | Equation::Multiplication(terms) => {
+                for term in terms {
+                    term.collect_unbound_variables(values, unbound_variables);
+                }
+            }
+            Equation::Division(pair)
+            | Equation::Power(pair)
+            | Equation::Equals(pair)
+            | Equation::Dot(pair)
+            | Equation::Cross(pair) => {
+                pair.0.collect_unbound_variables(values, unbound_variables);
+                pair.1.collect_unbound_variables(values, unbound_variables);
+            }
+            Equation::Matrix(rows) => {
+                for entry in rows.iter().flatten() {
+                    entry.collect_unbound_variables(values, unbound_variables);
+                }
+            }
+            Equation::Negative(x)
+            | Equation::Ln(x)
+            | Equation::Exp(x)
+            | Equation::Sin(x)
+            | Equation::Cos(x)
+            | Equation::Abs(x)
+            | Equation::Tan(x)
+            | Equation::Arcsin(x)
+            | Equation::Arccos(x)
+            | Equation::Arctan(x)
+            | Equation::Sinh(x)
+            | Equation::Cosh(x)
+            | Equation::Tanh(x) => x.collect_unbound_variables(values, unbound_variables),
+            Equation::Integral(integral) => {
+                let mut integrand_variables = vec![];
+                integral
+                    .integrand
+                    .collect_unbound_variables(values, &mut integrand_variables);
+                for variable in integrand_variables {
+                    if variable != integral.variable && !unbound_variables.contains(&variable) {
+                        unbound_variables.push(variable);
+                    }
+                }
+                if let Some((lower, upper)) = &integral.bounds {
+                    lower.collect_unbound_variables(values, unbound_variables);
+                    upper.collect_unbound_variables(values, unbound_variables);
+                }
+            }
+            Equation::Limit(limit) => {
+                let mut expression_variables = vec![];
+                limit
+                    .expression
+                    .collect_unbound_variables(values, &mut expression_variables);
+                for variable in expression_variables {
+                    if variable != limit.variable && !unbound_variables.contains(&variable) {
+                        unbound_variables.push(variable);
+                    }
+                }
+                limit
+                    .point
+                    .collect_unbound_variables(values, unbound_variables);
+            }
+            Equation::Derivative(derivative) => derivative
+                .expression
+                .collect_unbound_variables(values, unbound_variables),
+        }
+    }
+
+    pub(super) fn checked_calculate(
+        &self,
+        values: &BTreeMap<Variable, f64>,
+    ) -> Result<f64, EvalError> {
+        match self {
+            Equation::Variable(Variable::Constant(constant)) => Ok(match constant {
+                Constant::PI => std::f64::consts::PI,
+                Constant::E => std::f64::consts::E,
+                Constant::Infinity => f64::INFINITY,
+            }),
+            Equation::Variable(variable @ (Variable::Letter(_) | Variable::Vector(_))) => values
+                .get(variable)
+                .copied()
+                .ok_or_else(|| EvalError::UnboundVariables(vec![variable.clone()])),
+            Equation::Variable(_) => Ok(self.calculate(values)),
+            Equation::Negative(negative) => Ok(-negative.checked_calculate(values)?),
+            Equation::Addition(addition) => {
+                addition.iter().map(|x| x.checked_calculate(values)).sum()
+            }
+            Equation::Multiplication(multiplication) => multiplication
+                .iter()
+                .map(|x| x.checked_calculate(values))
+                .product(),
+            Equation::Division(division) => {
+                let numerator = division.0.checked_calculate(values)?;
+                let denominator = division.1.checked_calculate(values)?;
+                if denominator == 0. {
+                    return Err(EvalError::DivisionByZero(division.1.clone()));
+                }
+                Ok(numerator / denominator)
+            }
+            Equation::Power(power) => {
+                let base = power.0.checked_calculate(values)?;
+                let exponent = power.1.checked_calculate(values)?;
+                if base == 0. && exponent < 0. {
+                    return Err(EvalError::DivisionByZero(power.0.clone()));
+                }
+                if base < 0. && exponent.fract() != 0. {
+                    return Err(EvalError::NegativeRoot(power.0.clone(), base));
+                }
+                Ok(base.powf(exponent))
+            }
+            Equation::Ln(ln) => {
+                let argument = ln.checked_calculate(values)?;
+                if argument <= 0. {
+                    return Err(EvalError::NonPositiveLogarithm((**ln).clone(), argument));
+                }
+                Ok(argument.ln())
+            }
+            Equation::Exp(exp) => Ok(exp.checked_calculate(values)?.exp()),
+            Equation::Sin(sin) => Ok(sin.checked_calculate(values)?.sin()),
+            Equation::Cos(cos) => Ok(cos.checked_calculate(values)?.cos()),
+            Equation::Abs(abs) => Ok(abs.checked_calculate(values)?.abs()),
+            Equation::Arcsin(arcsin) => Ok(inverse_trig_argument(arcsin, values)?.asin()),
+            Equation::Arccos(arccos) => Ok(inverse_trig_argument(arccos, values)?.acos()),
+            Equation::Tan(tan) => Ok(tan.checked_calculate(values)?.tan()),
+            Equation::Arctan(arctan) => Ok(arctan.checked_calculate(values)?.atan()),
+            Equation::Sinh(sinh) => Ok(sinh.checked_calculate(values)?.sinh()),
+            Equation::Cosh(cosh) => Ok(cosh.checked_calculate(values)?.cosh()),
+            Equation::Tanh(tanh) => Ok(tanh.checked_calculate(values)?.tanh()),
+            Equation::Integral(integral) => {
+                let value = integral.try_calculate(values)?;
+                if !value.is_finite() {
+                    return Err(EvalError::Divergent(self.clone()));
+                }
+                Ok(value)
+            }
+            Equation::Limit(limit) => match limit.evaluate() {
+                Some(value) => value.checked_calculate(values),
+                None => Err(EvalError::NoLimit(self.clone())),
+            },
+            Equation::Derivative(derivative) => derivative.evaluate().checked_calculate(values),
+            //Vectors are unbound here, so the products are of scalars
+            Equation::Dot(pair) | Equation::Cross(pair) => {
+                Err(EvalError::NotAVector(pair.0.clone()))
+            }
+            Equation::Matrix(_) => Err(EvalError::NotAScalar(self.clone())),
+            Equation::Equals(_) => Err(EvalError::Equals),
+        }
+    }
+}
+
+fn inverse_trig_argument(
+    argument: &Equation,
+    values: &BTreeMap<Variable, f64>,
+) -> Result<f64, EvalError> {
+    let value = argument.checked_calculate(values)?;
+    if !(-1. ..=1.).contains(&value) {
+        return Err(EvalError::OutsideDomain(argument.clone(), value));
+    }
+    Ok(value)
+}
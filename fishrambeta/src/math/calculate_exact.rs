@@ -15,11 +15,12 @@ impl Equation {
                 }
                 None
             }
+            Equation::Negative(negative) => Some(-negative.calculate_exact()?),
             Equation::Addition(addition) => {
-                return addition.iter().map(|x| x.calculate_exact()).sum()
+                addition.iter().map(|x| x.calculate_exact()).sum()
             }
             Equation::Multiplication(multiplication) => {
-                return multiplication.iter().map(|x| x.calculate_exact()).product()
+                multiplication.iter().map(|x| x.calculate_exact()).product()
             }
             Equation::Division(division) => {
                 let numerator = division.0.calculate_exact();
@@ -46,14 +47,8 @@ impl Equation {
                     Ok(x) => x,
                     Err(_) => return None,
                 };
-                let numerator = match checked_pow(*base.numer(), exponent_num) {
-                    Some(x) => x,
-                    None => return None,
-                };
-                let denominator = match checked_pow(*base.denom(), exponent_num) {
-                    Some(x) => x,
-                    None => return None,
-                };
+                let numerator = checked_pow(*base.numer(), exponent_num)?;
+                let denominator = checked_pow(*base.denom(), exponent_num)?;
                 Some(Rational64::new(numerator, denominator))
             }
             Equation::Abs(abs) => {
@@ -65,6 +60,32 @@ impl Equation {
                 if **ln == Equation::Variable(Variable::Constant(Constant::E)) {
                     return Some(1.into());
                 }
+                if ln.calculate_exact()? == 1.into() {
+                    return Some(0.into());
+                }
+                None
+            }
+            Equation::Exp(exp) => {
+                if exp.calculate_exact()? == 0.into() {
+                    return Some(1.into());
+                }
+                None
+            }
+            Equation::Sin(x)
+            | Equation::Tan(x)
+            | Equation::Arcsin(x)
+            | Equation::Arctan(x)
+            | Equation::Sinh(x)
+            | Equation::Tanh(x) => {
+                if x.calculate_exact()? == 0.into() {
+                    return Some(0.into());
+                }
+                None
+            }
+            Equation::Cos(x) | Equation::Cosh(x) => {
+                if x.calculate_exact()? == 0.into() {
+                    return Some(1.into());
+                }
                 None
             }
             _ => None,
@@ -1,122 +1,160 @@
+use super::{Derivative, Equation, Integral, Limit, Variable};
+use num_rational::Rational64;
 
-
-
-/*
-impl PartialEq for Equation {
-    fn eq(&self, other: &Self) -> bool {
+impl Equation {
+    ///Rewrites the equation into a form where equations that only differ in the order of commutative operands, the nesting of sums and products, or the placement of signs are identical
+    pub fn canonical(&self) -> Equation {
         match self {
-            Equation::Variable(v1) => {
-                if let Equation::Variable(v2) = other {
-                    return v1 == v2;
-                } else {
-                    return false;
+            Equation::Variable(variable) => Equation::Variable(canonical_number(variable)),
+            Equation::Negative(x) => negate(x.canonical()),
+            Equation::Addition(terms) => {
+                let mut canonical_terms = vec![];
+                for term in terms {
+                    match term.canonical() {
+                        Equation::Addition(inner) => canonical_terms.extend(inner),
+                        term => canonical_terms.push(term),
+                    }
                 }
-            }
-            Equation::Addition(a1) => {
-                if let Equation::Addition(a2) = other {
-                    return a1.iter().zip(a2).all(|(a, b)| a == b);
-                } else {
-                    return false;
+                canonical_terms.retain(|term| !is_number(term, 0));
+                canonical_terms.sort();
+                match canonical_terms.len() {
+                    0 => Equation::Variable(Variable::Integer(0)),
+                    1 => canonical_terms.pop().unwrap(),
+                    _ => Equation::Addition(canonical_terms),
                 }
             }
-            Equation::Multiplication(m1) => {
-                if let Equation::Multiplication(m2) = other {
-                    return m1.iter().zip(m2).all(|(a, b)| a == b);
-                } else {
-                    return false;
+            Equation::Multiplication(factors) => {
+                let mut canonical_factors = vec![];
+                let mut negative = false;
+                for factor in factors {
+                    let mut factor = factor.canonical();
+                    if let Some(positive) = split_sign(&factor) {
+                        factor = positive;
+                        negative = !negative;
+                    }
+                    match factor {
+                        Equation::Multiplication(inner) => canonical_factors.extend(inner),
+                        factor => canonical_factors.push(factor),
+                    }
                 }
-            }
-            Equation::Power(p1) => {
-                if let Equation::Power(p2) = other {
-                    return p1.0 == p2.0 && p1.1 == p2.1;
+                canonical_factors.retain(|factor| !is_number(factor, 1));
+                canonical_factors.sort();
+                let product = match canonical_factors.len() {
+                    0 => Equation::Variable(Variable::Integer(1)),
+                    1 => canonical_factors.pop().unwrap(),
+                    _ => Equation::Multiplication(canonical_factors),
+                };
+                if negative {
+                    negate(product)
                 } else {
-                    return false;
+                    product
                 }
             }
-            Equation::Division(d1) => {
-                if let Equation::Division(d2) = other {
-                    return d1.0 == d2.0 && d1.1 == d2.1;
-                } else {
-                    return false;
+            Equation::Division(division) => {
+                let mut numerator = division.0.canonical();
+                let mut denominator = division.1.canonical();
+                let mut negative = false;
+                for part in [&mut numerator, &mut denominator] {
+                    if let Some(positive) = split_sign(part) {
+                        *part = positive;
+                        negative = !negative;
+                    }
                 }
-            }
-            Equation::Negative(n1) => {
-                if let Equation::Negative(n2) = other {
-                    return n1 == n2;
+                let quotient = Equation::Division(Box::new((numerator, denominator)));
+                if negative {
+                    Equation::Negative(Box::new(quotient))
                 } else {
-                    return false;
+                    quotient
                 }
             }
-            Equation::Sin(s1) => {
-                if let Equation::Sin(s2) = other {
-                    return s1 == s2;
-                } else {
-                    return false;
-                }
+            Equation::Power(power) => {
+                Equation::Power(Box::new((power.0.canonical(), power.1.canonical())))
             }
-            Equation::Cos(c1) => {
-                if let Equation::Cos(c2) = other {
-                    return c1 == c2;
-                } else {
-                    return false;
-                }
+            Equation::Dot(pair) => {
+                let (left, right) = (pair.0.canonical(), pair.1.canonical());
+                Equation::Dot(Box::new((left.clone().min(right.clone()), left.max(right))))
             }
-            Equation::Ln(l1) => {
-                if let Equation::Ln(l2) = other {
-                    return l1 == l2;
-                } else {
-                    return false;
-                }
+            Equation::Cross(pair) => {
+                Equation::Cross(Box::new((pair.0.canonical(), pair.1.canonical())))
             }
-            Equation::Equals(e1) => {
-                if let Equation::Equals(e2) = other {
-                    return e1 == e2;
-                } else {
-                    return false;
-                }
+            Equation::Matrix(_) => self.map_entries(|entry| entry.canonical()),
+            Equation::Equals(sides) => {
+                let (left, right) = (sides.0.canonical(), sides.1.canonical());
+                Equation::Equals(Box::new((left.clone().min(right.clone()), left.max(right))))
             }
+            Equation::Ln(x) => Equation::Ln(Box::new(x.canonical())),
+            Equation::Exp(x) => Equation::Exp(Box::new(x.canonical())),
+            Equation::Sin(x) => Equation::Sin(Box::new(x.canonical())),
+            Equation::Cos(x) => Equation::Cos(Box::new(x.canonical())),
+            Equation::Abs(x) => Equation::Abs(Box::new(x.canonical())),
+            Equation::Tan(x) => Equation::Tan(Box::new(x.canonical())),
+            Equation::Arcsin(x) => Equation::Arcsin(Box::new(x.canonical())),
+            Equation::Arccos(x) => Equation::Arccos(Box::new(x.canonical())),
+            Equation::Arctan(x) => Equation::Arctan(Box::new(x.canonical())),
+            Equation::Sinh(x) => Equation::Sinh(Box::new(x.canonical())),
+            Equation::Cosh(x) => Equation::Cosh(Box::new(x.canonical())),
+            Equation::Tanh(x) => Equation::Tanh(Box::new(x.canonical())),
+            Equation::Integral(integral) => Equation::Integral(Box::new(Integral {
+                integrand: integral.integrand.canonical(),
+                variable: canonical_number(&integral.variable),
+                bounds: integral
+                    .bounds
+                    .as_ref()
+                    .map(|(lower, upper)| (lower.canonical(), upper.canonical())),
+            })),
+            Equation::Limit(limit) => Equation::Limit(Box::new(Limit {
+                expression: limit.expression.canonical(),
+                variable: canonical_number(&limit.variable),
+                point: limit.point.canonical(),
+                direction: limit.direction,
+            })),
+            Equation::Derivative(derivative) => Equation::Derivative(Box::new(Derivative {
+                expression: derivative.expression.canonical(),
+                variables: derivative.variables.iter().map(canonical_number).collect(),
+            })),
         }
     }
+
+    ///Checks whether both equations have the same canonical form, this does not simplify so `x+x` and `2*x` are not equivalent
+    pub fn equivalent(&self, other: &Equation) -> bool {
+        self == other || self.canonical() == other.canonical()
+    }
 }
 
-impl PartialEq for Variable {
-    fn eq(&self, other: &Self) -> bool {
-        match self {
-            Variable::Letter(l1) => {
-                if let Variable::Letter(l2) = other {
-                    return l1 == l2;
-                } else {
-                    return false;
-                }
-            }
-            Variable::Vector(v1) => {
-                if let Variable::Vector(v2) = other {
-                    return v1 == v2;
-                } else {
-                    return false;
-                }
-            }
-            Variable::Integer(i1) => {
-                if let Some(i2) = other.get_number_or_none() {
-                    return Rational64::new(*i1, 1) == i2;
-                } else {
-                    return false;
-                }
-            }
-            Variable::Rational(r1) => {
-                if let Some(r2) = other.get_number_or_none() {
-                    return Rational64::new((*r1).0, (*r1).1) == r2;
-                } else {
-                    return false;
-                }
-            }
-            Variable::Constant(c1) => {
-                if let Variable::Constant(c2) = other {
-                    return c1 == c2;
-                } else {
-                    return false;
-                }
-            }
+///Rationals with a denominator of one are stored as integers
+fn canonical_number(variable: &Variable) -> Variable {
+    match variable {
+        Variable::Rational(r) if r.is_integer() => Variable::Integer(r.to_integer()),
+        _ => variable.clone(),
+    }
+}
+
+fn is_number(equation: &Equation, number: i64) -> bool {
+    equation.get_number_or_none() == Some(number.into())
+}
+
+///Negates a canonical equation, numbers carry their own sign and every other equation is wrapped in a single negation
+fn negate(equation: Equation) -> Equation {
+    match equation {
+        Equation::Negative(x) => *x,
+        Equation::Variable(Variable::Integer(n)) if n.checked_neg().is_some() => {
+            Equation::Variable(Variable::Integer(-n))
+        }
+        Equation::Variable(Variable::Rational(r)) if r.numer().checked_neg().is_some() => {
+            Equation::Variable(Variable::Rational(-r))
         }
+        equation => Equation::Negative(Box::new(equation)),
     }
-}*/
+}
+
+///The positive part of a canonical equation if it is negative
+pub(super) fn split_sign(equation: &Equation) -> Option<Equation> {
+    match equation {
+        Equation::Negative(x) => Some((**x).clone()),
+        Equation::Variable(Variable::Integer(n)) if *n < 0 => Some(negate(equation.clone())),
+        Equation::Variable(Variable::Rational(r)) if *r < Rational64::from(0) => {
+            Some(negate(equation.clone()))
+        }
+        _ => None,
+    }
+}
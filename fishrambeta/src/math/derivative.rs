@@ -0,0 +1,60 @@
+use super::{Derivative, Equation, Variable};
+
+impl Derivative {
+    ///Differentiates the expression to every variable in turn and simplifies the result
+    pub fn evaluate(&self) -> Equation {
+        self.expression.partial_derivative(&self.variables)
+    }
+}
+
+impl Equation {
+    ///The n-th derivative to the variable, simplified after every step so higher derivatives stay small
+    pub fn nth_derivative(&self, variable: &Variable, n: usize) -> Equation {
+        self.partial_derivative(&vec![variable.clone(); n])
+    }
+
+    ///The mixed partial derivative to each of the variables in turn, so [x, y] gives d^2/dxdy
+    pub fn partial_derivative(&self, variables: &[Variable]) -> Equation {
+        let mut derivative = self.clone().simplify_until_complete();
+        for variable in variables {
+            derivative = derivative.differentiate(variable).simplify_until_complete();
+        }
+        derivative
+    }
+
+    ///The partial derivatives to each of the variables
+    pub fn gradient(&self, variables: &[Variable]) -> Vec<Equation> {
+        variables
+            .iter()
+            .map(|variable| self.partial_derivative(std::slice::from_ref(variable)))
+            .collect()
+    }
+
+    ///The matrix of second partial derivatives to each pair of the variables, the mixed partials are computed once as the matrix is symmetric
+    pub fn hessian(&self, variables: &[Variable]) -> Vec<Vec<Equation>> {
+        let gradient = self.gradient(variables);
+        let mut hessian: Vec<Vec<Equation>> = vec![vec![]; variables.len()];
+        for (i, first) in gradient.iter().enumerate() {
+            for (j, second) in variables.iter().enumerate() {
+                let entry = if j < i {
+                    hessian[j][i].clone()
+                } else {
+                    first.partial_derivative(std::slice::from_ref(second))
+                };
+                hessian[i].push(entry);
+            }
+        }
+        hessian
+    }
+
+    ///The letters in the equation in sorted order, which are the variables of the gradient and Hessian
+    pub fn letters(&self) -> Vec<Variable> {
+        let mut letters = self
+            .free_variables()
+            .into_iter()
+            .filter(|variable| matches!(variable, Variable::Letter(_)))
+            .collect::<Vec<_>>();
+        letters.sort();
+        letters
+    }
+}
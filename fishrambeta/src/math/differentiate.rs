@@ -1,4 +1,5 @@
-use crate::math::{Equation, Variable};
+use crate::math::{Equation, Integral, Limit, Variable};
+use num_rational::Rational64;
 
 impl Equation {
     pub fn differentiate(self: &Equation, differentiate_to: &Variable) -> Equation {
@@ -14,7 +15,7 @@ impl Equation {
                 Equation::Negative(Box::new(negative.differentiate(differentiate_to)))
             }
             Equation::Addition(addition) => {
-                return Equation::Addition(
+                Equation::Addition(
                     addition
                         .iter()
                         .map(|x| x.differentiate(differentiate_to))
@@ -22,7 +23,7 @@ impl Equation {
                 )
             }
             Equation::Multiplication(multiplication) => {
-                return Equation::Addition(
+                Equation::Addition(
                     multiplication
                         .iter()
                         .map(|x| {
@@ -67,6 +68,12 @@ impl Equation {
                     (**ln).clone(),
                 )))
             }
+            Equation::Exp(exp) => {
+                Equation::Multiplication(vec![
+                    exp.differentiate(differentiate_to),
+                    Equation::Exp(exp.clone()),
+                ])
+            }
             Equation::Sin(sin) => {
                 Equation::Multiplication(vec![
                     sin.differentiate(differentiate_to),
@@ -85,12 +92,103 @@ impl Equation {
                     equals.1.differentiate(differentiate_to),
                 )))
             }
+            Equation::Integral(integral) => {
+                differentiate_integral(integral, differentiate_to)
+            }
+            Equation::Limit(limit) => {
+                differentiate_limit(limit, differentiate_to)
+            }
+            Equation::Derivative(derivative) => {
+                derivative.evaluate().differentiate(differentiate_to)
+            }
+            Equation::Abs(abs) if abs.is_vector() => Equation::Division(Box::new((
+                Equation::Dot(Box::new((
+                    *abs.clone(),
+                    abs.differentiate(differentiate_to),
+                ))),
+                Equation::Abs(abs.clone()),
+            ))),
+            Equation::Dot(dot) => Equation::Addition(vec![
+                Equation::Dot(Box::new((
+                    dot.0.differentiate(differentiate_to),
+                    dot.1.clone(),
+                ))),
+                Equation::Dot(Box::new((
+                    dot.0.clone(),
+                    dot.1.differentiate(differentiate_to),
+                ))),
+            ]),
+            Equation::Matrix(_) => self.map_entries(|entry| entry.differentiate(differentiate_to)),
+            Equation::Cross(cross) => Equation::Addition(vec![
+                Equation::Cross(Box::new((
+                    cross.0.differentiate(differentiate_to),
+                    cross.1.clone(),
+                ))),
+                Equation::Cross(Box::new((
+                    cross.0.clone(),
+                    cross.1.differentiate(differentiate_to),
+                ))),
+            ]),
             Equation::Abs(abs) => {
                 Equation::Division(Box::new((
                     Equation::Multiplication(vec![*abs.clone(), abs.differentiate(differentiate_to)]),
                     Equation::Abs(abs.clone())
                 )))
             }
+            Equation::Arcsin(arcsin) => {
+                Equation::Division(Box::new((
+                    arcsin.differentiate(differentiate_to),
+                    one_minus_square_root(arcsin),
+                )))
+            }
+            Equation::Tan(tan) => {
+                Equation::Division(Box::new((
+                    tan.differentiate(differentiate_to),
+                    Equation::Power(Box::new((
+                        Equation::Cos(tan.clone()),
+                        Equation::Variable(Variable::Integer(2)),
+                    ))),
+                )))
+            }
+            Equation::Arctan(arctan) => {
+                Equation::Division(Box::new((
+                    arctan.differentiate(differentiate_to),
+                    Equation::Addition(vec![
+                        Equation::Variable(Variable::Integer(1)),
+                        Equation::Power(Box::new((
+                            (**arctan).clone(),
+                            Equation::Variable(Variable::Integer(2)),
+                        ))),
+                    ]),
+                )))
+            }
+            Equation::Sinh(sinh) => {
+                Equation::Multiplication(vec![
+                    sinh.differentiate(differentiate_to),
+                    Equation::Cosh(sinh.clone()),
+                ])
+            }
+            Equation::Cosh(cosh) => {
+                Equation::Multiplication(vec![
+                    cosh.differentiate(differentiate_to),
+                    Equation::Sinh(cosh.clone()),
+                ])
+            }
+            Equation::Tanh(tanh) => {
+                Equation::Division(Box::new((
+                    tanh.differentiate(differentiate_to),
+                    Equation::Power(Box::new((
+                        Equation::Cosh(tanh.clone()),
+                        Equation::Variable(Variable::Integer(2)),
+                    ))),
+                )))
+            }
+            Equation::Arccos(arccos) => {
+                Equation::Negative(Box::new(Equation::Division(Box::new((
+                    arccos.differentiate(differentiate_to),
+                    one_minus_square_root(arccos),
+                )))))
+            }
         }
     }
 }
@@ -115,3 +213,65 @@ fn differentiate_power(power: &(Equation, Equation), differentiate_to: &Variable
     let second_term = Equation::Addition(vec![g_f_accent, f_log_g_accent]);
     Equation::Multiplication(vec![first_term, second_term])
 }
+
+///Returns sqrt(1-x^2), the denominator in the derivatives of arcsin and arccos and a term of their primitives
+pub(super) fn one_minus_square_root(x: &Equation) -> Equation {
+    Equation::Power(Box::new((
+        Equation::Addition(vec![
+            Equation::Variable(Variable::Integer(1)),
+            Equation::Negative(Box::new(Equation::Power(Box::new((
+                x.clone(),
+                Equation::Variable(Variable::Integer(2)),
+            ))))),
+        ]),
+        Equation::Variable(Variable::Rational(Rational64::new(1, 2))),
+    )))
+}
+
+///Differentiates an integral with Leibniz' rule, the bounds contribute f(b)b' - f(a)a'
+fn differentiate_integral(integral: &Integral, differentiate_to: &Variable) -> Equation {
+    let inner = || {
+        Equation::Integral(Box::new(Integral {
+            integrand: integral.integrand.differentiate(differentiate_to),
+            variable: integral.variable.clone(),
+            bounds: integral.bounds.clone(),
+        }))
+    };
+    let Some((lower, upper)) = &integral.bounds else {
+        if &integral.variable == differentiate_to {
+            return integral.integrand.clone();
+        }
+        return inner();
+    };
+    let variable = Equation::Variable(integral.variable.clone());
+    let mut terms = vec![
+        Equation::Multiplication(vec![
+            integral.integrand.substitute(&variable, upper),
+            upper.differentiate(differentiate_to),
+        ]),
+        Equation::Negative(Box::new(Equation::Multiplication(vec![
+            integral.integrand.substitute(&variable, lower),
+            lower.differentiate(differentiate_to),
+        ]))),
+    ];
+    if &integral.variable != differentiate_to {
+        terms.push(inner());
+    }
+    Equation::Addition(terms)
+}
+
+///Differentiates the value of the limit, or the expression inside the limit if it has no known value
+fn differentiate_limit(limit: &Limit, differentiate_to: &Variable) -> Equation {
+    if !Equation::Limit(Box::new(limit.clone())).contains_variable(differentiate_to) {
+        return Equation::Variable(Variable::Integer(0));
+    }
+    match limit.evaluate() {
+        Some(value) => value.differentiate(differentiate_to),
+        None => Equation::Limit(Box::new(Limit {
+            expression: limit.expression.differentiate(differentiate_to),
+            variable: limit.variable.clone(),
+            point: limit.point.clone(),
+            direction: limit.direction,
+        })),
+    }
+}
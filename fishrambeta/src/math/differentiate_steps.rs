@@ -0,0 +1,107 @@
+use crate::math::{Equation, Variable};
+
+///A rule applied while differentiating, with the subexpression it was applied to and its derivative as LaTeX
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DifferentiationStep {
+    pub rule: &'static str,
+    pub expression: String,
+    pub derivative: String,
+}
+
+impl Equation {
+    ///Differentiates the equation, also returning every rule that was applied, outer expressions before the subexpressions they need
+    pub fn differentiate_with_steps(
+        &self,
+        differentiate_to: &Variable,
+    ) -> (Equation, Vec<DifferentiationStep>) {
+        let mut steps = vec![];
+        self.collect_steps(differentiate_to, &mut steps);
+        let derivative = self
+            .differentiate(differentiate_to)
+            .simplify_until_complete();
+        (derivative, steps)
+    }
+
+    fn collect_steps(&self, differentiate_to: &Variable, steps: &mut Vec<DifferentiationStep>) {
+        let depends = |equation: &Equation| equation.contains_variable(differentiate_to);
+        let (rule, parts): (&'static str, Vec<&Equation>) = match self {
+            _ if !depends(self) => ("constant rule", vec![]),
+            Equation::Variable(_) => ("variable rule", vec![]),
+            Equation::Negative(negative) => ("constant multiple rule", vec![negative]),
+            Equation::Addition(terms) => ("sum rule", terms.iter().collect()),
+            Equation::Multiplication(factors) => {
+                let parts = factors
+                    .iter()
+                    .filter(|factor| depends(factor))
+                    .collect::<Vec<_>>();
+                if parts.len() == 1 {
+                    ("constant multiple rule", parts)
+                } else {
+                    ("product rule", parts)
+                }
+            }
+            Equation::Division(division) if !depends(&division.1) => {
+                ("constant multiple rule", vec![&division.0])
+            }
+            Equation::Division(division) => (
+                "quotient rule",
+                [&division.0, &division.1]
+                    .into_iter()
+                    .filter(|part| depends(part))
+                    .collect(),
+            ),
+            Equation::Power(power) if !depends(&power.1) => {
+                chain_rule("power rule", &power.0, differentiate_to)
+            }
+            Equation::Power(power) if !depends(&power.0) => {
+                chain_rule("exponential rule", &power.1, differentiate_to)
+            }
+            Equation::Power(power) => ("generalized power rule", vec![&power.0, &power.1]),
+            Equation::Exp(x) => chain_rule("exponential rule", x, differentiate_to),
+            Equation::Ln(x) => chain_rule("logarithm rule", x, differentiate_to),
+            Equation::Sin(x) => chain_rule("derivative of sine", x, differentiate_to),
+            Equation::Cos(x) => chain_rule("derivative of cosine", x, differentiate_to),
+            Equation::Tan(x) => chain_rule("derivative of tangent", x, differentiate_to),
+            Equation::Arcsin(x) => chain_rule("derivative of arcsine", x, differentiate_to),
+            Equation::Arccos(x) => chain_rule("derivative of arccosine", x, differentiate_to),
+            Equation::Arctan(x) => chain_rule("derivative of arctangent", x, differentiate_to),
+            Equation::Sinh(x) => chain_rule("derivative of sinh", x, differentiate_to),
+            Equation::Cosh(x) => chain_rule("derivative of cosh", x, differentiate_to),
+            Equation::Tanh(x) => chain_rule("derivative of tanh", x, differentiate_to),
+            Equation::Abs(x) => chain_rule("derivative of the absolute value", x, differentiate_to),
+            Equation::Integral(_) => ("Leibniz integral rule", vec![]),
+            Equation::Limit(_) => ("evaluate the limit", vec![]),
+            Equation::Derivative(_) => ("evaluate the inner derivative", vec![]),
+            Equation::Dot(pair) => ("product rule for the dot product", vec![&pair.0, &pair.1]),
+            Equation::Cross(pair) => ("product rule for the cross product", vec![&pair.0, &pair.1]),
+            Equation::Matrix(rows) => {
+                ("differentiate every entry", rows.iter().flatten().collect())
+            }
+            Equation::Equals(sides) => ("differentiate both sides", vec![&sides.0, &sides.1]),
+        };
+        steps.push(DifferentiationStep {
+            rule,
+            expression: self.to_latex(),
+            derivative: self
+                .differentiate(differentiate_to)
+                .simplify_until_complete()
+                .to_latex(),
+        });
+        for part in parts {
+            part.collect_steps(differentiate_to, steps);
+        }
+    }
+}
+
+///A rule for an outer function, which becomes the chain rule if the inner function is not just the variable
+fn chain_rule<'a>(
+    rule: &'static str,
+    inner: &'a Equation,
+    differentiate_to: &Variable,
+) -> (&'static str, Vec<&'a Equation>) {
+    if *inner == Equation::Variable(differentiate_to.clone()) {
+        (rule, vec![])
+    } else {
+        ("chain rule", vec![inner])
+    }
+}
@@ -0,0 +1,111 @@
+use super::{Equation, Variable};
+use std::collections::BTreeMap;
+
+///Points at which both equations have to be defined before they are considered probably equal
+const SAMPLES: usize = 32;
+///Points that are tried before giving up, points where either equation is undefined are skipped
+const ATTEMPTS: usize = 512;
+const RELATIVE_TOLERANCE: f64 = 1e-8;
+
+///The values a variable is sampled from when comparing equations numerically
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DomainHint {
+    ///Any real number, sampled with magnitudes between 0.1 and 10
+    Real,
+    ///Positive numbers, for variables like lengths and masses that make roots and logarithms defined
+    Positive,
+    ///Numbers in the interval
+    Interval(f64, f64),
+}
+
+///The result of comparing two equations at random points
+#[derive(Clone, Debug, PartialEq)]
+pub enum Equivalence {
+    ///Both equations had the same value at every sampled point
+    ProbablyEqual { samples: usize },
+    ///A point where the equations have different values, with both values
+    Different {
+        point: BTreeMap<Variable, f64>,
+        values: (f64, f64),
+    },
+    ///There were too few points where both equations could be calculated
+    Undecided { samples: usize },
+}
+
+impl Equation {
+    ///Compares the equations numerically at pseudo random points, variables without a hint are taken to be real, points near poles and outside the domain of either equation are skipped
+    pub fn probably_equals(
+        &self,
+        other: &Equation,
+        domain_hints: &BTreeMap<Variable, DomainHint>,
+    ) -> Equivalence {
+        let mut variables = self.free_variables();
+        for variable in other.free_variables() {
+            if !variables.contains(&variable) {
+                variables.push(variable);
+            }
+        }
+
+        let mut random = SplitMix64(0x5eed);
+        let mut samples = 0;
+        for _ in 0..ATTEMPTS {
+            let point = variables
+                .iter()
+                .map(|variable| {
+                    let hint = domain_hints.get(variable).unwrap_or(&DomainHint::Real);
+                    (variable.clone(), random.sample(*hint))
+                })
+                .collect::<BTreeMap<_, _>>();
+            let (Ok(a), Ok(b)) = (self.try_calculate(&point), other.try_calculate(&point)) else {
+                continue;
+            };
+            if !a.is_finite() || !b.is_finite() {
+                continue;
+            }
+            if (a - b).abs() > RELATIVE_TOLERANCE * a.abs().max(b.abs()).max(1.) {
+                return Equivalence::Different {
+                    point,
+                    values: (a, b),
+                };
+            }
+            samples += 1;
+            if samples == SAMPLES || variables.is_empty() {
+                return Equivalence::ProbablyEqual { samples };
+            }
+        }
+        Equivalence::Undecided { samples }
+    }
+}
+
+///A small deterministic generator, so comparisons give the same result every run
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    ///A number uniformly distributed in [0, 1)
+    fn uniform(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn sample(&mut self, hint: DomainHint) -> f64 {
+        match hint {
+            DomainHint::Real => {
+                let magnitude = self.sample(DomainHint::Positive);
+                if self.next() & 1 == 0 {
+                    magnitude
+                } else {
+                    -magnitude
+                }
+            }
+            DomainHint::Positive => 10f64.powf(2. * self.uniform() - 1.),
+            DomainHint::Interval(low, high) => low + (high - low) * self.uniform(),
+        }
+    }
+}
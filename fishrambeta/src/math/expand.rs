@@ -0,0 +1,173 @@
+use super::{Derivative, Equation, Integral, Limit, Variable};
+
+impl Equation {
+    ///Multiplies out every product of sums and every integer power of a sum, then collects like terms
+    pub fn expand(&self) -> Equation {
+        self.expand_terms().simplify_until_complete()
+    }
+
+    ///Writes the equation as a sum of products that contain no sums, without collecting terms
+    fn expand_terms(&self) -> Equation {
+        match self {
+            Equation::Variable(_) => self.clone(),
+            Equation::Negative(negative) => Equation::Addition(
+                terms(negative.expand_terms())
+                    .into_iter()
+                    .map(|term| Equation::Negative(Box::new(term)))
+                    .collect(),
+            ),
+            Equation::Addition(addition) => Equation::Addition(
+                addition
+                    .iter()
+                    .flat_map(|term| terms(term.expand_terms()))
+                    .collect(),
+            ),
+            Equation::Multiplication(multiplication) => Equation::Addition(
+                multiplication
+                    .iter()
+                    .map(|factor| terms(factor.expand_terms()))
+                    .fold(vec![Equation::Variable(Variable::Integer(1))], |a, b| {
+                        distribute(&a, &b)
+                    }),
+            ),
+            Equation::Division(division) => {
+                let denominator = division.1.expand_terms();
+                Equation::Addition(
+                    terms(division.0.expand_terms())
+                        .into_iter()
+                        .map(|term| Equation::Division(Box::new((term, denominator.clone()))))
+                        .collect(),
+                )
+            }
+            Equation::Power(power) => {
+                let base = power.0.expand_terms();
+                let exponent = power.1.expand_terms();
+                let expanded = exponent.calculate_exact().and_then(|n| {
+                    let base_terms = terms(base.clone());
+                    if !n.is_integer() || n == 0.into() || base_terms.len() < 2 {
+                        return None;
+                    }
+                    Some((n, power_terms(&base_terms, n.to_integer().unsigned_abs())?))
+                });
+                match expanded {
+                    Some((n, expanded)) if n > 0.into() => Equation::Addition(expanded),
+                    Some((_, expanded)) => Equation::Division(Box::new((
+                        Equation::Variable(Variable::Integer(1)),
+                        Equation::Addition(expanded),
+                    ))),
+                    None => Equation::Power(Box::new((base, exponent))),
+                }
+            }
+            Equation::Dot(pair) => products(pair, Equation::Dot),
+            Equation::Cross(pair) => products(pair, Equation::Cross),
+            Equation::Matrix(_) => self.map_entries(|entry| entry.expand_terms()),
+            Equation::Equals(sides) => {
+                Equation::Equals(Box::new((sides.0.expand_terms(), sides.1.expand_terms())))
+            }
+            Equation::Ln(x) => Equation::Ln(Box::new(x.expand_terms())),
+            Equation::Exp(x) => Equation::Exp(Box::new(x.expand_terms())),
+            Equation::Sin(x) => Equation::Sin(Box::new(x.expand_terms())),
+            Equation::Cos(x) => Equation::Cos(Box::new(x.expand_terms())),
+            Equation::Abs(x) => Equation::Abs(Box::new(x.expand_terms())),
+            Equation::Tan(x) => Equation::Tan(Box::new(x.expand_terms())),
+            Equation::Arcsin(x) => Equation::Arcsin(Box::new(x.expand_terms())),
+            Equation::Arccos(x) => Equation::Arccos(Box::new(x.expand_terms())),
+            Equation::Arctan(x) => Equation::Arctan(Box::new(x.expand_terms())),
+            Equation::Sinh(x) => Equation::Sinh(Box::new(x.expand_terms())),
+            Equation::Cosh(x) => Equation::Cosh(Box::new(x.expand_terms())),
+            Equation::Tanh(x) => Equation::Tanh(Box::new(x.expand_terms())),
+            Equation::Integral(integral) => Equation::Integral(Box::new(Integral {
+                integrand: integral.integrand.expand_terms(),
+                variable: integral.variable.clone(),
+                bounds: integral
+                    .bounds
+                    .as_ref()
+                    .map(|(lower, upper)| (lower.expand_terms(), upper.expand_terms())),
+            })),
+            Equation::Limit(limit) => Equation::Limit(Box::new(Limit {
+                expression: limit.expression.expand_terms(),
+                variable: limit.variable.clone(),
+                point: limit.point.expand_terms(),
+                direction: limit.direction,
+            })),
+            Equation::Derivative(derivative) => Equation::Derivative(Box::new(Derivative {
+                expression: derivative.expression.expand_terms(),
+                variables: derivative.variables.clone(),
+            })),
+        }
+    }
+}
+
+///The terms of a sum, or the equation itself if it is not a sum
+fn terms(equation: Equation) -> Vec<Equation> {
+    match equation {
+        Equation::Addition(terms) => terms,
+        equation => vec![equation],
+    }
+}
+
+///Every product of a term of a and a term of b
+fn distribute(a: &[Equation], b: &[Equation]) -> Vec<Equation> {
+    a.iter()
+        .flat_map(|x| {
+            b.iter()
+                .map(move |y| Equation::Multiplication(vec![x.clone(), y.clone()]))
+        })
+        .collect()
+}
+
+///The terms of (t_1 + ... + t_k)^n by the binomial theorem on t_1 + (t_2 + ... + t_k), so every
+///product of powers occurs once. Returns None if a binomial coefficient does not fit in an i64
+fn power_terms(terms: &[Equation], n: u64) -> Option<Vec<Equation>> {
+    let Some((first, rest)) = terms.split_first() else {
+        return Some(if n == 0 {
+            vec![Equation::Variable(Variable::Integer(1))]
+        } else {
+            vec![]
+        });
+    };
+    if rest.is_empty() {
+        return Some(vec![power(first, n)?]);
+    }
+    let mut expanded = vec![];
+    let mut coefficient: i64 = 1;
+    for i in 0..=n {
+        for term in power_terms(rest, n - i)? {
+            expanded.push(Equation::Multiplication(vec![
+                Equation::Variable(Variable::Integer(coefficient)),
+                power(first, i)?,
+                term,
+            ]));
+        }
+        coefficient = (i128::from(coefficient) * i128::from(n - i) / i128::from(i + 1))
+            .try_into()
+            .ok()?;
+    }
+    Some(expanded)
+}
+
+///The equation to the power n
+fn power(equation: &Equation, n: u64) -> Option<Equation> {
+    Some(Equation::Power(Box::new((
+        equation.clone(),
+        Equation::Variable(Variable::Integer(n.try_into().ok()?)),
+    ))))
+}
+
+///Distributes a dot or cross product over the terms of both sides, keeping the order of the sides
+fn products(
+    pair: &(Equation, Equation),
+    product: fn(Box<(Equation, Equation)>) -> Equation,
+) -> Equation {
+    let right = terms(pair.1.expand_terms());
+    Equation::Addition(
+        terms(pair.0.expand_terms())
+            .into_iter()
+            .flat_map(|x| {
+                right
+                    .iter()
+                    .map(move |y| product(Box::new((x.clone(), y.clone()))))
+            })
+            .collect(),
+    )
+}
@@ -1,6 +1,9 @@
+use num::Signed;
 use num_integer::Integer;
 
-use crate::math::{Equation, Variable};
+use crate::math::compare::split_sign;
+use crate::math::solve::exact_square_root;
+use crate::math::{Equation, Polynomial, Variable};
 
 impl Equation {
     pub fn has_factor(self: &Equation, factor: &Equation) -> bool {
@@ -8,7 +11,7 @@ impl Equation {
             return true;
         }
         if let Some(n) = factor.get_integer_or_none() {
-            if n != 0 && self.gcd() % n == 0 {
+            if n.abs() > 1 && self.gcd() % n == 0 {
                 return true;
             }
         }
@@ -45,7 +48,7 @@ impl Equation {
                 gcd
             }
             Equation::Multiplication(multiplication) => {
-                return multiplication.iter().map(|x| x.gcd()).product()
+                multiplication.iter().map(|x| x.gcd()).product()
             }
             Equation::Division(division) => division.0.gcd().gcd(&division.1.gcd()),
             _ => 1,
@@ -108,22 +111,25 @@ impl Equation {
             Equation::Negative(negative) => {
                 return Equation::Negative(Box::new(negative.remove_factor(factor)))
             }
-            Equation::Multiplication(multiplication) => {
-                let new: Vec<_> = multiplication
-                    .iter()
-                    .map(|x| {
-                        if x.has_factor(factor) {
-                            x.clone().remove_factor(factor)
-                        } else {
-                            x.clone()
-                        }
-                    })
-                    .collect();
-                return if !new.is_empty() {
-                    Equation::Multiplication(new)
-                } else {
-                    Equation::Variable(Variable::Integer(1))
+            Equation::Multiplication(mut multiplication) => {
+                //Only one of the factors is divided, x*x/x is x
+                if let Some(index) = multiplication.iter().position(|x| x.has_factor(factor)) {
+                    let removed = multiplication[index].clone().remove_factor(factor);
+                    multiplication[index] = removed;
+                    return Equation::Multiplication(multiplication);
+                }
+                //An integer can be spread over several factors, as 6 in 2*3*x
+                let Some(mut remaining) = factor.get_integer_or_none() else {
+                    return Equation::Multiplication(multiplication);
                 };
+                for x in multiplication.iter_mut() {
+                    let shared = x.gcd().gcd(&remaining);
+                    if shared.abs() > 1 {
+                        *x = x.clone().remove_factor(&Equation::Variable(Variable::Integer(shared)));
+                        remaining /= shared;
+                    }
+                }
+                return Equation::Multiplication(multiplication);
             }
             Equation::Power(power) => {
                 return Equation::Power(Box::new((
@@ -150,4 +156,156 @@ impl Equation {
 
         self
     }
+
+    ///Writes the equation as a product, pulling common factors out of sums, factoring polynomials in one variable over the rationals and splitting differences of squares
+    pub fn factor(&self) -> Equation {
+        match self {
+            Equation::Addition(_) => factor_sum(self.clone().simplify_until_complete()),
+            Equation::Negative(negative) => Equation::Negative(Box::new(negative.factor())),
+            Equation::Multiplication(multiplication) => {
+                Equation::Multiplication(multiplication.iter().map(|x| x.factor()).collect())
+            }
+            Equation::Division(division) => {
+                Equation::Division(Box::new((division.0.factor(), division.1.factor())))
+            }
+            Equation::Power(power) => {
+                Equation::Power(Box::new((power.0.factor(), power.1.clone())))
+            }
+            Equation::Equals(sides) => {
+                Equation::Equals(Box::new((sides.0.factor(), sides.1.factor())))
+            }
+            _ => self.clone(),
+        }
+    }
+}
+
+fn factor_sum(sum: Equation) -> Equation {
+    let Equation::Addition(ref terms) = sum else {
+        return sum.factor();
+    };
+    if let [variable] = &sum.free_variables()[..] {
+        if let Some(factored) = Polynomial::from_equation(&sum, variable)
+            .and_then(|polynomial| factor_polynomial(&polynomial, variable))
+        {
+            return factored;
+        }
+    }
+
+    let mut common = vec![];
+    let mut rest = sum.clone();
+    let gcd = Equation::Variable(Variable::Integer(sum.gcd()));
+    if gcd.get_integer_or_none() > Some(1) {
+        rest = rest.remove_factor(&gcd).simplify_until_complete();
+        common.push(gcd);
+    }
+    for candidate in terms[0].get_all_factors() {
+        if candidate.get_number_or_none().is_none() && rest.has_factor(&candidate) {
+            rest = rest.remove_factor(&candidate).simplify_until_complete();
+            common.push(candidate);
+        }
+    }
+    if !common.is_empty() {
+        match rest.factor() {
+            Equation::Multiplication(mut factors) => common.append(&mut factors),
+            factor => common.push(factor),
+        }
+        return Equation::Multiplication(collect_powers(common));
+    }
+
+    if let [a, b] = &terms[..] {
+        if let Some(factored) = difference_of_squares(a, b) {
+            return factored;
+        }
+    }
+    sum
+}
+
+///Writes repeated factors as a single power, keeping the order of first occurrence
+fn collect_powers(factors: Vec<Equation>) -> Vec<Equation> {
+    let mut counted: Vec<(Equation, i64)> = vec![];
+    for factor in factors {
+        match counted.iter_mut().find(|(f, _)| *f == factor) {
+            Some((_, count)) => *count += 1,
+            None => counted.push((factor, 1)),
+        }
+    }
+    counted
+        .into_iter()
+        .map(|(factor, count)| match count {
+            1 => factor,
+            _ => Equation::Power(Box::new((factor, Equation::Variable(Variable::Integer(count))))),
+        })
+        .collect()
+}
+
+///a - b = (sqrt(a) - sqrt(b)) * (sqrt(a) + sqrt(b)) when one of the terms is negative and both are squares
+fn difference_of_squares(a: &Equation, b: &Equation) -> Option<Equation> {
+    let (a, b) = (a.canonical(), b.canonical());
+    let (positive, negative) = match (split_sign(&a), split_sign(&b)) {
+        (None, Some(negative)) => (a, negative),
+        (Some(negative), None) => (b, negative),
+        _ => return None,
+    };
+    let (a, b) = (square_root(&positive)?, square_root(&negative)?);
+    Some(Equation::Multiplication(vec![
+        Equation::Addition(vec![a.clone(), Equation::Negative(Box::new(b.clone()))]).factor(),
+        Equation::Addition(vec![a, b]).factor(),
+    ]))
+}
+
+fn square_root(term: &Equation) -> Option<Equation> {
+    match term {
+        Equation::Variable(Variable::Integer(_) | Variable::Rational(_)) => {
+            let root = exact_square_root(term.get_number_or_none()?)?;
+            Some(Equation::Variable(Variable::Rational(root)).simplify_until_complete())
+        }
+        Equation::Power(power) => {
+            let exponent = power.1.get_integer_or_none()?;
+            if exponent <= 0 || exponent % 2 != 0 {
+                return None;
+            }
+            if exponent == 2 {
+                return Some(power.0.clone());
+            }
+            Some(Equation::Power(Box::new((
+                power.0.clone(),
+                Equation::Variable(Variable::Integer(exponent / 2)),
+            ))))
+        }
+        Equation::Multiplication(multiplication) => Some(Equation::Multiplication(
+            multiplication
+                .iter()
+                .map(square_root)
+                .collect::<Option<Vec<_>>>()?,
+        )),
+        _ => None,
+    }
+}
+
+fn factor_polynomial(polynomial: &Polynomial, variable: &Variable) -> Option<Equation> {
+    let (constant, factors) = polynomial.factor()?;
+    let mut product = vec![];
+    if constant.abs() != 1.into() || factors.is_empty() {
+        product
+            .push(Equation::Variable(Variable::Rational(constant.abs())).simplify_until_complete());
+    }
+    for (factor, power) in factors {
+        let factor = factor.to_equation(variable);
+        product.push(match power {
+            1 => factor,
+            _ => Equation::Power(Box::new((
+                factor,
+                Equation::Variable(Variable::Integer(power as i64)),
+            ))),
+        });
+    }
+    let product = match product.len() {
+        1 => product.pop().unwrap(),
+        _ => Equation::Multiplication(product),
+    };
+    if constant.is_negative() {
+        Some(Equation::Negative(Box::new(product)))
+    } else {
+        Some(product)
+    }
 }
@@ -0,0 +1,78 @@
+use super::{Equation, Variable};
+
+impl Equation {
+    ///Integrates a product by parts, differentiating the factor that comes first in LIATE order
+    pub(super) fn integrate_by_parts(
+        &self,
+        integrate_to: &Variable,
+        depth: u32,
+    ) -> Option<Equation> {
+        let Equation::Multiplication(factors) = self else {
+            return None;
+        };
+        let (index, u) = factors
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, factor)| factor.liate_rank(integrate_to))?;
+        let mut rest = factors.clone();
+        rest.remove(index);
+        let dv = if rest.len() == 1 {
+            rest.remove(0)
+        } else {
+            Equation::Multiplication(rest)
+        };
+        match (u.liate_rank(integrate_to), dv.liate_rank(integrate_to)) {
+            (None, _) | (Some(Liate::Algebraic), Some(Liate::Algebraic)) => return None,
+            _ => {}
+        }
+
+        let v = dv.integrate_with_depth(integrate_to, depth + 1)?;
+        let v_du = Equation::Multiplication(vec![v.clone(), u.differentiate(integrate_to)])
+            .simplify_until_complete();
+        Some(Equation::Addition(vec![
+            Equation::Multiplication(vec![u.clone(), v]),
+            Equation::Negative(Box::new(
+                v_du.integrate_with_depth(integrate_to, depth + 1)?,
+            )),
+        ]))
+    }
+
+    ///How preferable the factor is to differentiate when integrating by parts, None if it is not a known kind of factor
+    fn liate_rank(&self, variable: &Variable) -> Option<Liate> {
+        match self {
+            Equation::Ln(_) => Some(Liate::Logarithmic),
+            Equation::Arcsin(_) | Equation::Arccos(_) | Equation::Arctan(_) => {
+                Some(Liate::InverseTrigonometric)
+            }
+            Equation::Variable(_) => Some(Liate::Algebraic),
+            Equation::Power(power) if power.1.term_is_constant(variable) => Some(Liate::Algebraic),
+            Equation::Addition(terms)
+                if terms
+                    .iter()
+                    .all(|term| term.liate_rank(variable) == Some(Liate::Algebraic)) =>
+            {
+                Some(Liate::Algebraic)
+            }
+            Equation::Sin(_)
+            | Equation::Cos(_)
+            | Equation::Tan(_)
+            | Equation::Sinh(_)
+            | Equation::Cosh(_)
+            | Equation::Tanh(_) => Some(Liate::Trigonometric),
+            Equation::Exp(_) => Some(Liate::Exponential),
+            Equation::Power(power) if power.0.term_is_constant(variable) => {
+                Some(Liate::Exponential)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Liate {
+    Exponential,
+    Trigonometric,
+    Algebraic,
+    InverseTrigonometric,
+    Logarithmic,
+}
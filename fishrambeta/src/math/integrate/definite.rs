@@ -0,0 +1,143 @@
+use super::{Equation, Variable};
+use crate::math::{EvalError, Integral, QuadratureOptions, RootOptions};
+use std::collections::BTreeMap;
+
+impl Integral {
+    ///Integrates symbolically, giving F(upper) - F(lower) for a definite integral and F for an indefinite one.
+    ///Returns None for a definite integral over a pole of the integrand, where F(upper) - F(lower) is not the integral
+    pub fn evaluate_symbolic(&self) -> Option<Equation> {
+        if let Some((lower, upper)) = &self.bounds {
+            let (lower, upper) = (
+                lower.try_calculate(&BTreeMap::new()),
+                upper.try_calculate(&BTreeMap::new()),
+            );
+            if let (Ok(lower), Ok(upper)) = (lower, upper) {
+                if self.has_pole_between(lower, upper, &BTreeMap::new()) {
+                    return None;
+                }
+            }
+        }
+        let primitive = self.integrand.integrate(&self.variable)?;
+        let variable = Equation::Variable(self.variable.clone());
+        let result = match &self.bounds {
+            Some((lower, upper)) => Equation::Addition(vec![
+                primitive.substitute(&variable, upper),
+                Equation::Negative(Box::new(primitive.substitute(&variable, lower))),
+            ]),
+            None => primitive,
+        };
+        Some(result.simplify_until_complete())
+    }
+
+    ///Calculates a definite integral like try_calculate, but returns NaN instead of an error
+    pub fn calculate(&self, values: &BTreeMap<Variable, f64>) -> f64 {
+        self.try_calculate(values).unwrap_or(f64::NAN)
+    }
+
+    ///Calculates a definite integral from the antiderivative if there is one and the integrand has no poles between the bounds, otherwise numerically
+    pub fn try_calculate(&self, values: &BTreeMap<Variable, f64>) -> Result<f64, EvalError> {
+        let Some((lower, upper)) = &self.bounds else {
+            return Err(EvalError::IndefiniteIntegral(Equation::Integral(Box::new(
+                self.clone(),
+            ))));
+        };
+        let (lower, upper) = (
+            lower.checked_calculate(values)?,
+            upper.checked_calculate(values)?,
+        );
+        if !self.has_pole_between(lower, upper, values) {
+            if let Some(primitive) = self.integrand.integrate(&self.variable) {
+                let mut values = values.clone();
+                let mut at = |x: f64| {
+                    values.insert(self.variable.clone(), x);
+                    primitive.checked_calculate(&values)
+                };
+                if let (Ok(upper), Ok(lower)) = (at(upper), at(lower)) {
+                    if (upper - lower).is_finite() {
+                        return Ok(upper - lower);
+                    }
+                }
+            }
+        }
+        let options = QuadratureOptions {
+            values: values.clone(),
+            ..Default::default()
+        };
+        match self
+            .integrand
+            .integrate_numeric(&self.variable, lower, upper, &options)
+        {
+            Ok(quadrature) => Ok(quadrature.value),
+            Err(EvalError::NotConverged(_, _)) => Err(EvalError::Divergent(Equation::Integral(
+                Box::new(self.clone()),
+            ))),
+            Err(error) => Err(error),
+        }
+    }
+
+    ///Whether the integrand divides by something with a root strictly between the bounds
+    fn has_pole_between(&self, lower: f64, upper: f64, values: &BTreeMap<Variable, f64>) -> bool {
+        let (a, b) = (lower.min(upper), lower.max(upper));
+        let options = RootOptions {
+            values: values.clone(),
+            ..Default::default()
+        };
+        //Roots on the bounds are improper integrals, which the antiderivative handles if it is finite there
+        let margin = options.tolerance.sqrt() * (b - a).max(1.);
+        let mut denominators = vec![];
+        collect_denominators(&self.integrand, &mut denominators);
+        denominators.iter().any(|denominator| {
+            denominator
+                .find_roots(&self.variable, (a, b), &options)
+                .is_ok_and(|roots| {
+                    roots
+                        .iter()
+                        .any(|root| a + margin < *root && *root < b - margin)
+                })
+        })
+    }
+}
+
+///Collects every expression the equation divides by, which are the places where it can have poles
+fn collect_denominators(equation: &Equation, denominators: &mut Vec<Equation>) {
+    match equation {
+        Equation::Addition(terms) | Equation::Multiplication(terms) => {
+            for term in terms {
+                collect_denominators(term, denominators);
+            }
+        }
+        Equation::Division(division) => {
+            denominators.push(division.1.clone());
+            collect_denominators(&division.0, denominators);
+            collect_denominators(&division.1, denominators);
+        }
+        Equation::Power(power) => {
+            if power
+                .1
+                .calculate_exact()
+                .is_some_and(|exponent| exponent < 0.into())
+            {
+                denominators.push(power.0.clone());
+            }
+            collect_denominators(&power.0, denominators);
+            collect_denominators(&power.1, denominators);
+        }
+        Equation::Tan(tan) => {
+            denominators.push(Equation::Cos(tan.clone()));
+            collect_denominators(tan, denominators);
+        }
+        Equation::Negative(x)
+        | Equation::Ln(x)
+        | Equation::Exp(x)
+        | Equation::Sin(x)
+        | Equation::Cos(x)
+        | Equation::Abs(x)
+        | Equation::Arcsin(x)
+        | Equation::Arccos(x)
+        | Equation::Arctan(x)
+        | Equation::Sinh(x)
+        | Equation::Cosh(x)
+        | Equation::Tanh(x) => collect_denominators(x, denominators),
+        _ => {}
+    }
+}
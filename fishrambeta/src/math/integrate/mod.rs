@@ -1,52 +1,89 @@
-use super::{Equation, Variable};
+use super::differentiate::one_minus_square_root;
+use super::{Equation, Polynomial, Variable};
+use num::{CheckedAdd, CheckedDiv, CheckedMul, Signed, Zero};
 use num_rational::Rational64;
 
-mod bogointegrate;
+mod by_parts;
+mod definite;
+mod rational;
+mod substitution;
+
+///The number of nested substitutions and integrations by parts before giving up
+const MAX_DEPTH: u32 = 6;
 
 impl Equation {
-    pub fn integrate(&self, integrate_to: &Variable) -> Equation {
-        let mut equation_to_integrate: Equation = (*self).clone().simplify();
-        let fixed_terms = equation_to_integrate.get_factors();
-        let mut integrated_equation = Vec::new();
+    ///Finds an antiderivative of the equation, returns None if none of the integration rules apply
+    pub fn integrate(&self, integrate_to: &Variable) -> Option<Equation> {
+        let integrated = self
+            .clone()
+            .simplify_until_complete()
+            .integrate_with_depth(integrate_to, 0)?;
+        Some(integrated.simplify_until_complete())
+    }
 
-        for fixed_term in fixed_terms.into_iter() {
-            println!(
-                "{} has factor? {} is constant? {}",
-                fixed_term,
-                equation_to_integrate.has_factor(&fixed_term),
-                fixed_term.term_is_constant(integrate_to)
-            );
-            if equation_to_integrate.has_factor(&fixed_term)
-                && fixed_term.term_is_constant(integrate_to)
-            {
-                equation_to_integrate = equation_to_integrate.remove_factor(&fixed_term).simplify();
-                integrated_equation.push(fixed_term);
-            }
+    fn integrate_with_depth(&self, integrate_to: &Variable, depth: u32) -> Option<Equation> {
+        if depth > MAX_DEPTH || matches!(self, Equation::Equals(_)) {
+            return None;
+        }
+        if self.term_is_constant(integrate_to) {
+            return Some(Equation::Multiplication(vec![
+                self.clone(),
+                Equation::Variable(integrate_to.clone()),
+            ]));
         }
 
-        let integrated_term = match equation_to_integrate.standard_integrals(integrate_to) {
-            Some(x) => x,
-            None => equation_to_integrate.bogointegrate(integrate_to),
-        };
-        integrated_equation.push(integrated_term);
+        match self {
+            Equation::Addition(addition) => {
+                return addition
+                    .iter()
+                    .map(|x| x.integrate_with_depth(integrate_to, depth))
+                    .collect::<Option<Vec<_>>>()
+                    .map(Equation::Addition);
+            }
+            Equation::Negative(negative) => {
+                return negative
+                    .integrate_with_depth(integrate_to, depth)
+                    .map(|x| Equation::Negative(Box::new(x)));
+            }
+            Equation::Multiplication(multiplication) => {
+                let (mut constant, variable): (Vec<_>, Vec<_>) = multiplication
+                    .iter()
+                    .cloned()
+                    .partition(|x| x.term_is_constant(integrate_to));
+                if !constant.is_empty() {
+                    let rest = if variable.len() == 1 {
+                        variable.into_iter().next().unwrap()
+                    } else {
+                        Equation::Multiplication(variable)
+                    };
+                    constant.push(rest.integrate_with_depth(integrate_to, depth)?);
+                    return Some(Equation::Multiplication(constant));
+                }
+            }
+            Equation::Division(division) if division.1.term_is_constant(integrate_to) => {
+                return Some(Equation::Division(Box::new((
+                    division.0.integrate_with_depth(integrate_to, depth)?,
+                    division.1.clone(),
+                ))));
+            }
+            _ => {}
+        }
 
-        Equation::Multiplication(integrated_equation)
+        self.standard_integral(integrate_to)
+            .or_else(|| self.integrate_rational(integrate_to))
+            .or_else(|| self.integrate_by_substitution(integrate_to, depth))
+            .or_else(|| self.integrate_by_parts(integrate_to, depth))
     }
 
-    fn standard_integrals(&self, integrate_to: &Variable) -> Option<Equation> {
-        println!("Attempting standard integral for: {}", self);
-        return match self {
-            Equation::Addition(addition) => Some(Equation::Addition(
-                addition.iter().map(|x| x.integrate(integrate_to)).collect(),
-            )),
-            Equation::Variable(Variable::Integer(i)) => Some(Equation::Multiplication(vec![
-                Equation::Variable(Variable::Integer(*i)),
-                Equation::Variable(integrate_to.clone()),
-            ])),
-            Equation::Variable(Variable::Rational(r)) => Some(Equation::Multiplication(vec![
-                Equation::Variable(Variable::Rational(*r)),
-                Equation::Variable(integrate_to.clone()),
-            ])),
+    ///Looks the equation up in a table of standard integrals, the argument of each entry may be linear in the variable
+    fn standard_integral(&self, integrate_to: &Variable) -> Option<Equation> {
+        let over_slope = |primitive: Equation, argument: &Equation| {
+            Some(Equation::Division(Box::new((
+                primitive,
+                argument.linear_slope(integrate_to)?,
+            ))))
+        };
+        match self {
             Equation::Variable(v) if v == integrate_to => Some(Equation::Multiplication(vec![
                 Equation::Variable(Variable::Rational(Rational64::new(1, 2))),
                 Equation::Power(Box::new((
@@ -54,42 +91,147 @@ impl Equation {
                     Equation::Variable(Variable::Integer(2)),
                 ))),
             ])),
-            Equation::Power(box (b, n)) if *b == Equation::Variable(integrate_to.clone()) => {
-                Some(Equation::Multiplication(vec![
+            Equation::Power(box (base, exponent)) if exponent.term_is_constant(integrate_to) => {
+                if exponent.calculate_exact() == Some((-1).into()) {
+                    return over_slope(
+                        Equation::Ln(Box::new(Equation::Abs(Box::new(base.clone())))),
+                        base,
+                    );
+                }
+                let new_exponent = Equation::Addition(vec![
+                    exponent.clone(),
+                    Equation::Variable(Variable::Integer(1)),
+                ]);
+                over_slope(
                     Equation::Division(Box::new((
-                        Equation::Variable(Variable::Integer(1)),
-                        Equation::Addition(vec![
-                            n.clone(),
-                            Equation::Variable(Variable::Integer(1)),
-                        ]),
+                        Equation::Power(Box::new((base.clone(), new_exponent.clone()))),
+                        new_exponent,
                     ))),
-                    Equation::Power(Box::new((
-                        Equation::Variable(integrate_to.clone()),
-                        Equation::Addition(vec![
-                            n.clone(),
-                            Equation::Variable(Variable::Integer(1)),
-                        ]),
+                    base,
+                )
+            }
+            Equation::Power(box (base, exponent)) if base.term_is_constant(integrate_to) => {
+                over_slope(
+                    Equation::Division(Box::new((
+                        self.clone(),
+                        Equation::Ln(Box::new(base.clone())),
                     ))),
-                ]))
+                    exponent,
+                )
             }
-            Equation::Sin(box x) if *x == Equation::Variable(integrate_to.clone()) => {
-                Some(Equation::Negative(Box::new(Equation::Cos(Box::new(
-                    Equation::Variable(integrate_to.clone()),
-                )))))
+            Equation::Division(box (numerator, denominator))
+                if numerator.term_is_constant(integrate_to) =>
+            {
+                if let Some(primitive) = arcsin_primitive(denominator, integrate_to) {
+                    return Some(Equation::Multiplication(vec![numerator.clone(), primitive]));
+                }
+                let reciprocal = match denominator {
+                    Equation::Power(box (Equation::Cos(argument), two))
+                        if two.get_integer_or_none() == Some(2) =>
+                    {
+                        over_slope(Equation::Tan(argument.clone()), argument)?
+                    }
+                    Equation::Power(box (Equation::Cosh(argument), two))
+                        if two.get_integer_or_none() == Some(2) =>
+                    {
+                        over_slope(Equation::Tanh(argument.clone()), argument)?
+                    }
+                    Equation::Power(box (base, exponent))
+                        if exponent.term_is_constant(integrate_to) =>
+                    {
+                        Equation::Power(Box::new((
+                            base.clone(),
+                            Equation::Multiplication(vec![
+                                Equation::Variable(Variable::Integer(-1)),
+                                exponent.clone(),
+                            ]),
+                        )))
+                        .standard_integral(integrate_to)?
+                    }
+                    denominator => over_slope(
+                        Equation::Ln(Box::new(Equation::Abs(Box::new(denominator.clone())))),
+                        denominator,
+                    )?,
+                };
+                Some(Equation::Multiplication(vec![
+                    numerator.clone(),
+                    reciprocal,
+                ]))
             }
-            Equation::Cos(box x) if *x == Equation::Variable(integrate_to.clone()) => Some(
-                Equation::Sin(Box::new(Equation::Variable(integrate_to.clone()))),
+            Equation::Exp(argument) => over_slope(self.clone(), argument),
+            Equation::Sin(argument) => over_slope(
+                Equation::Negative(Box::new(Equation::Cos(argument.clone()))),
+                argument,
+            ),
+            Equation::Cos(argument) => over_slope(Equation::Sin(argument.clone()), argument),
+            Equation::Tan(argument) => over_slope(
+                Equation::Negative(Box::new(Equation::Ln(Box::new(Equation::Abs(Box::new(
+                    Equation::Cos(argument.clone()),
+                )))))),
+                argument,
+            ),
+            Equation::Sinh(argument) => over_slope(Equation::Cosh(argument.clone()), argument),
+            Equation::Cosh(argument) => over_slope(Equation::Sinh(argument.clone()), argument),
+            Equation::Tanh(argument) => over_slope(
+                Equation::Ln(Box::new(Equation::Cosh(argument.clone()))),
+                argument,
+            ),
+            Equation::Ln(argument) => over_slope(
+                Equation::Addition(vec![
+                    Equation::Multiplication(vec![(**argument).clone(), self.clone()]),
+                    Equation::Negative(argument.clone()),
+                ]),
+                argument,
+            ),
+            Equation::Arcsin(argument) => over_slope(
+                Equation::Addition(vec![
+                    Equation::Multiplication(vec![(**argument).clone(), self.clone()]),
+                    one_minus_square_root(argument),
+                ]),
+                argument,
+            ),
+            Equation::Arccos(argument) => over_slope(
+                Equation::Addition(vec![
+                    Equation::Multiplication(vec![(**argument).clone(), self.clone()]),
+                    Equation::Negative(Box::new(one_minus_square_root(argument))),
+                ]),
+                argument,
+            ),
+            Equation::Arctan(argument) => over_slope(
+                Equation::Addition(vec![
+                    Equation::Multiplication(vec![(**argument).clone(), self.clone()]),
+                    Equation::Negative(Box::new(Equation::Division(Box::new((
+                        Equation::Ln(Box::new(Equation::Addition(vec![
+                            Equation::Variable(Variable::Integer(1)),
+                            Equation::Power(Box::new((
+                                (**argument).clone(),
+                                Equation::Variable(Variable::Integer(2)),
+                            ))),
+                        ]))),
+                        Equation::Variable(Variable::Integer(2)),
+                    ))))),
+                ]),
+                argument,
             ),
             _ => None,
-        };
+        }
+    }
+
+    ///Returns the derivative of the equation if the equation is linear in the variable
+    fn linear_slope(&self, integrate_to: &Variable) -> Option<Equation> {
+        let slope = self.differentiate(integrate_to).simplify_until_complete();
+        if slope.term_is_constant(integrate_to) && slope != Equation::Variable(Variable::Integer(0))
+        {
+            Some(slope)
+        } else {
+            None
+        }
     }
 
     fn term_is_constant(&self, integrate_to: &Variable) -> bool {
         match self {
             Equation::Addition(a) => a.iter().all(|x| x.term_is_constant(integrate_to)),
-            Equation::Multiplication(m) => {
-                m.iter().all(|x| x.term_is_constant(integrate_to))
-            }
+            Equation::Multiplication(m) => m.iter().all(|x| x.term_is_constant(integrate_to)),
             Equation::Negative(n) => n.term_is_constant(integrate_to),
             Equation::Division(d) => {
                 d.0.term_is_constant(integrate_to) && d.1.term_is_constant(integrate_to)
@@ -100,9 +242,66 @@ impl Equation {
             Equation::Sin(t) => t.term_is_constant(integrate_to),
             Equation::Cos(t) => t.term_is_constant(integrate_to),
             Equation::Ln(t) => t.term_is_constant(integrate_to),
-            Equation::Equals(_) => panic!("Equation containing = cannot be integrated"),
+            Equation::Exp(t) => t.term_is_constant(integrate_to),
+            //Not constant, so integrating it fails instead of treating it as a factor
+            Equation::Equals(_) => false,
+            Equation::Integral(_)
+            | Equation::Limit(_)
+            | Equation::Derivative(_)
+            | Equation::Dot(_)
+            | Equation::Cross(_)
+            | Equation::Matrix(_) => !self.contains_variable(integrate_to),
             Equation::Variable(v) => v != integrate_to,
             Equation::Abs(a) => a.term_is_constant(integrate_to),
+            Equation::Arcsin(t) => t.term_is_constant(integrate_to),
+            Equation::Arccos(t) => t.term_is_constant(integrate_to),
+            Equation::Tan(t) => t.term_is_constant(integrate_to),
+            Equation::Arctan(t) => t.term_is_constant(integrate_to),
+            Equation::Sinh(t) => t.term_is_constant(integrate_to),
+            Equation::Cosh(t) => t.term_is_constant(integrate_to),
+            Equation::Tanh(t) => t.term_is_constant(integrate_to),
         }
     }
 }
+
+///The primitive of 1/sqrt(c + b*x - a*x^2) with a > 0, which is arcsin(sqrt(a/d) * (x - b/2a)) / sqrt(a) with d = c + b^2/4a after completing the square
+fn arcsin_primitive(denominator: &Equation, integrate_to: &Variable) -> Option<Equation> {
+    let Equation::Power(box (base, half)) = denominator else {
+        return None;
+    };
+    if half.calculate_exact()? != Rational64::new(1, 2) {
+        return None;
+    }
+    let quadratic = Polynomial::from_equation(base, integrate_to)?;
+    if quadratic.degree()? != 2 || !quadratic.leading_coefficient().is_negative() {
+        return None;
+    }
+    let a = -quadratic.coefficient(2);
+    let shift = quadratic
+        .coefficient(1)
+        .checked_div(&a.checked_mul(&2.into())?)?;
+    let d = quadratic
+        .coefficient(0)
+        .checked_add(&a.checked_mul(&shift)?.checked_mul(&shift)?)?;
+    let ratio = a.checked_div(&d)?;
+    if !d.is_positive() {
+        return None;
+    }
+    let square_root = |rational: Rational64| {
+        Equation::Power(Box::new((
+            Equation::Variable(Variable::Rational(rational)),
+            Equation::Variable(Variable::Rational(Rational64::new(1, 2))),
+        )))
+    };
+    let mut argument = vec![Equation::Variable(integrate_to.clone())];
+    if !shift.is_zero() {
+        argument.push(Equation::Variable(Variable::Rational(-shift)));
+    }
+    Some(Equation::Division(Box::new((
+        Equation::Arcsin(Box::new(Equation::Multiplication(vec![
+            square_root(ratio),
+            Equation::Addition(argument),
+        ]))),
+        square_root(a),
+    ))))
+}
@@ -0,0 +1,217 @@
+use super::{Equation, Variable};
+use crate::math::solve::exact_square_root;
+use crate::math::{solve_linear_system, Polynomial};
+use num::Signed;
+use num_rational::Rational64;
+
+///The rational roots with their multiplicity and the remaining irreducible quadratic
+type Factorization = (Vec<(Rational64, i64)>, Option<Polynomial>);
+
+impl Equation {
+    ///Integrates a quotient of two polynomials with rational coefficients using partial fractions
+    pub(super) fn integrate_rational(&self, integrate_to: &Variable) -> Option<Equation> {
+        let (numerator, denominator) = self.rational_function(integrate_to)?;
+        let (quotient, remainder) = numerator.div_rem(&denominator)?;
+        let mut terms = vec![];
+        if !quotient.is_zero() {
+            terms.push(integrate_polynomial(&quotient, integrate_to));
+        }
+        if !remainder.is_zero() {
+            terms.append(&mut integrate_partial_fractions(
+                &remainder,
+                &denominator,
+                integrate_to,
+            )?);
+        }
+        Some(Equation::Addition(terms))
+    }
+
+    ///Writes the equation as a numerator and denominator polynomial in the variable
+    pub(in crate::math) fn rational_function(
+        &self,
+        variable: &Variable,
+    ) -> Option<(Polynomial, Polynomial)> {
+        let one = Polynomial::constant(1.into());
+        match self {
+            Equation::Variable(v) if v == variable => {
+                Some((Polynomial::monomial(1.into(), 1), one))
+            }
+            Equation::Variable(_) => Some((Polynomial::constant(self.calculate_exact()?), one)),
+            Equation::Negative(negative) => {
+                let (numerator, denominator) = negative.rational_function(variable)?;
+                Some((numerator.checked_neg()?, denominator))
+            }
+            Equation::Addition(addition) => {
+                addition
+                    .iter()
+                    .try_fold((Polynomial::zero(), one), |(n1, d1), term| {
+                        let (n2, d2) = term.rational_function(variable)?;
+                        let numerator = n1.checked_mul(&d2)?.checked_add(&n2.checked_mul(&d1)?)?;
+                        Some((numerator, d1.checked_mul(&d2)?))
+                    })
+            }
+            Equation::Multiplication(multiplication) => {
+                multiplication
+                    .iter()
+                    .try_fold((one.clone(), one), |(n1, d1), factor| {
+                        let (n2, d2) = factor.rational_function(variable)?;
+                        Some((n1.checked_mul(&n2)?, d1.checked_mul(&d2)?))
+                    })
+            }
+            Equation::Division(division) => {
+                let (n1, d1) = division.0.rational_function(variable)?;
+                let (n2, d2) = division.1.rational_function(variable)?;
+                if n2.is_zero() {
+                    return None;
+                }
+                Some((n1.checked_mul(&d2)?, d1.checked_mul(&n2)?))
+            }
+            Equation::Power(power) => {
+                let exponent = power.1.calculate_exact()?;
+                if !exponent.is_integer() {
+                    return None;
+                }
+                let (mut numerator, mut denominator) = power.0.rational_function(variable)?;
+                if exponent.is_negative() {
+                    if numerator.is_zero() {
+                        return None;
+                    }
+                    std::mem::swap(&mut numerator, &mut denominator);
+                }
+                let exponent = u32::try_from(exponent.to_integer().abs()).ok()?;
+                Some((numerator.pow(exponent)?, denominator.pow(exponent)?))
+            }
+            _ => None,
+        }
+    }
+}
+
+///Integrates remainder/denominator, where the remainder has a lower degree than the denominator
+fn integrate_partial_fractions(
+    remainder: &Polynomial,
+    denominator: &Polynomial,
+    variable: &Variable,
+) -> Option<Vec<Equation>> {
+    let leading = denominator.leading_coefficient();
+    let remainder = remainder.scale(leading.recip())?;
+    let denominator = denominator.monic()?;
+    let (roots, quadratic) = roots_and_quadratic(&denominator)?;
+
+    //Every partial fraction has a numerator polynomial it contributes after multiplying by the denominator
+    let mut basis = vec![];
+    for (root, multiplicity) in &roots {
+        for power in 1..=*multiplicity {
+            let factor = Polynomial::new(vec![-root, 1.into()]).pow(power as u32)?;
+            basis.push(denominator.div_rem(&factor)?.0);
+        }
+    }
+    if let Some(quadratic) = &quadratic {
+        let rest = denominator.div_rem(quadratic)?.0;
+        basis.push(rest.checked_mul(&Polynomial::monomial(1.into(), 1))?);
+        basis.push(rest);
+    }
+    //Row i equates the coefficients of x^i
+    let size = denominator.degree()?;
+    let matrix = (0..size)
+        .map(|row| basis.iter().map(|b| b.coefficient(row)).collect())
+        .collect::<Vec<_>>();
+    let target = (0..size)
+        .map(|row| vec![remainder.coefficient(row)])
+        .collect::<Vec<_>>();
+    let mut coefficients = solve_linear_system(&matrix, &target)?
+        .into_iter()
+        .map(|row| row[0]);
+
+    let x = Equation::Variable(variable.clone());
+    let mut terms = vec![];
+    for (root, multiplicity) in roots {
+        let linear = Equation::Addition(vec![x.clone(), rational(-root)]);
+        for power in 1..=multiplicity {
+            let coefficient = coefficients.next()?;
+            terms.push(if power == 1 {
+                Equation::Multiplication(vec![
+                    rational(coefficient),
+                    Equation::Ln(Box::new(Equation::Abs(Box::new(linear.clone())))),
+                ])
+            } else {
+                Equation::Division(Box::new((
+                    rational(-coefficient / Rational64::from(power - 1)),
+                    Equation::Power(Box::new((
+                        linear.clone(),
+                        Equation::Variable(Variable::Integer(power - 1)),
+                    ))),
+                )))
+            });
+        }
+    }
+    if let Some(quadratic) = quadratic {
+        //(bx+c)/(x^2+px+q) = b/2 * (2x+p)/(x^2+px+q) + (c-bp/2)/((x+p/2)^2+k)
+        let (b, c) = (coefficients.next()?, coefficients.next()?);
+        let (q, p) = (quadratic.coefficient(0), quadratic.coefficient(1));
+        let half_p = p / Rational64::from(2);
+        let k = q - half_p * half_p;
+        let square_root_k = match exact_square_root(k) {
+            Some(root) => rational(root),
+            None => Equation::Power(Box::new((
+                rational(k),
+                Equation::Variable(Variable::Rational(Rational64::new(1, 2))),
+            ))),
+        };
+        terms.push(Equation::Multiplication(vec![
+            rational(b / Rational64::from(2)),
+            Equation::Ln(Box::new(quadratic.to_equation(variable))),
+        ]));
+        terms.push(Equation::Multiplication(vec![
+            Equation::Division(Box::new((rational(c - b * half_p), square_root_k.clone()))),
+            Equation::Arctan(Box::new(Equation::Division(Box::new((
+                Equation::Addition(vec![x, rational(half_p)]),
+                square_root_k,
+            ))))),
+        ]));
+    }
+    Some(terms)
+}
+
+///The rational roots of the polynomial with their multiplicity and at most one irreducible quadratic
+///factor, None if it has any other factors
+fn roots_and_quadratic(polynomial: &Polynomial) -> Option<Factorization> {
+    let (_, factors) = polynomial.factor()?;
+    let mut roots = vec![];
+    let mut quadratic = None;
+    for (factor, power) in factors {
+        let factor = factor.monic()?;
+        let (q, p) = (factor.coefficient(0), factor.coefficient(1));
+        match factor.degree()? {
+            0 => {}
+            1 => roots.push((-q, i64::from(power))),
+            2 if power == 1 && quadratic.is_none() && p * p < Rational64::from(4) * q => {
+                quadratic = Some(factor)
+            }
+            _ => return None,
+        }
+    }
+    Some((roots, quadratic))
+}
+
+fn integrate_polynomial(polynomial: &Polynomial, variable: &Variable) -> Equation {
+    Equation::Addition(
+        polynomial
+            .coefficients()
+            .iter()
+            .enumerate()
+            .map(|(degree, coefficient)| {
+                Equation::Multiplication(vec![
+                    rational(coefficient / Rational64::from(degree as i64 + 1)),
+                    Equation::Power(Box::new((
+                        Equation::Variable(variable.clone()),
+                        Equation::Variable(Variable::Integer(degree as i64 + 1)),
+                    ))),
+                ])
+            })
+            .collect(),
+    )
+}
+
+fn rational(rational: Rational64) -> Equation {
+    Equation::Variable(Variable::Rational(rational))
+}
@@ -0,0 +1,88 @@
+use super::{Equation, Variable};
+
+impl Equation {
+    ///Tries every inner function g(x) as substitution u = g(x), which works when the integrand divided by g'(x) only depends on u
+    pub(super) fn integrate_by_substitution(
+        &self,
+        integrate_to: &Variable,
+        depth: u32,
+    ) -> Option<Equation> {
+        let substitute_variable = ["u", "v", "w", "t"]
+            .into_iter()
+            .map(|name| Variable::Letter(name.to_string()))
+            .find(|variable| !self.contains_variable(variable))?;
+        let u = Equation::Variable(substitute_variable.clone());
+
+        let mut candidates = vec![];
+        self.inner_functions(integrate_to, &mut candidates);
+        candidates.into_iter().find_map(|inner| {
+            let derivative = inner.differentiate(integrate_to).simplify_until_complete();
+            if derivative == Equation::Variable(Variable::Integer(0)) {
+                return None;
+            }
+            let substituted = Equation::Division(Box::new((self.clone(), derivative)))
+                .simplify_until_complete()
+                .substitute(&inner, &u);
+            if substituted.contains_variable(integrate_to) {
+                return None;
+            }
+            let integrated = substituted.integrate_with_depth(&substitute_variable, depth + 1)?;
+            Some(integrated.substitute(&u, &inner))
+        })
+    }
+
+    ///Collects the arguments of functions, powers and denominators that depend on the variable
+    pub(in crate::math) fn inner_functions(
+        &self,
+        variable: &Variable,
+        candidates: &mut Vec<Equation>,
+    ) {
+        let mut push = |candidate: &Equation| {
+            if candidate.contains_variable(variable)
+                && *candidate != Equation::Variable(variable.clone())
+                && !candidates.contains(candidate)
+            {
+                candidates.push(candidate.clone());
+            }
+        };
+        match self {
+            Equation::Variable(_)
+            | Equation::Integral(_)
+            | Equation::Limit(_)
+            | Equation::Derivative(_)
+            | Equation::Matrix(_) => {}
+            Equation::Addition(terms) | Equation::Multiplication(terms) => {
+                for term in terms {
+                    term.inner_functions(variable, candidates);
+                }
+            }
+            Equation::Division(pair)
+            | Equation::Power(pair)
+            | Equation::Equals(pair)
+            | Equation::Dot(pair)
+            | Equation::Cross(pair) => {
+                push(&pair.0);
+                push(&pair.1);
+                pair.0.inner_functions(variable, candidates);
+                pair.1.inner_functions(variable, candidates);
+            }
+            Equation::Negative(x) => x.inner_functions(variable, candidates),
+            Equation::Ln(x)
+            | Equation::Exp(x)
+            | Equation::Sin(x)
+            | Equation::Cos(x)
+            | Equation::Abs(x)
+            | Equation::Tan(x)
+            | Equation::Arcsin(x)
+            | Equation::Arccos(x)
+            | Equation::Arctan(x)
+            | Equation::Sinh(x)
+            | Equation::Cosh(x)
+            | Equation::Tanh(x) => {
+                push(x);
+                push(self);
+                x.inner_functions(variable, candidates);
+            }
+        }
+    }
+}
@@ -0,0 +1,673 @@
+use super::{Constant, Equation, Limit, LimitDirection, Polynomial, Variable};
+use num::{CheckedDiv, Signed, Zero};
+use num_rational::Rational64;
+use std::collections::BTreeMap;
+
+///How often L'Hôpital's rule or a rewrite of an indeterminate form can be applied within one limit
+const MAX_DEPTH: usize = 12;
+///Values closer to zero than this are taken to be zero when comparing numerically
+const TOLERANCE: f64 = 1e-12;
+
+impl Limit {
+    ///The value of the limit, see Equation::limit
+    pub fn evaluate(&self) -> Option<Equation> {
+        self.expression
+            .limit(&self.variable, &self.point, self.direction)
+    }
+}
+
+impl Equation {
+    ///The limit as the variable approaches the point, which can be \infty or -\infty, infinite limits are returned as one of those, None if the limit does not exist or could not be found
+    pub fn limit(
+        &self,
+        variable: &Variable,
+        point: &Equation,
+        direction: LimitDirection,
+    ) -> Option<Equation> {
+        let point = point.clone().simplify_until_complete();
+        let approach = |side| {
+            let point = Point::new(&point, side);
+            self.without_absolute_values(variable, &point)
+                .approach(variable, &point, 0)
+        };
+        let value = match direction {
+            _ if infinity_sign(&point).is_some() => approach(Side::Above)?,
+            LimitDirection::Left => approach(Side::Below)?,
+            LimitDirection::Right => approach(Side::Above)?,
+            LimitDirection::Both => {
+                let (below, above) = (approach(Side::Below)?, approach(Side::Above)?);
+                if !below.same(&above) {
+                    return None;
+                }
+                above
+            }
+        };
+        Some(value.into_equation())
+    }
+
+    ///Replaces absolute values by their argument or its negation if the sign of the argument close to the point is known
+    fn without_absolute_values(&self, variable: &Variable, point: &Point) -> Equation {
+        let mut candidates = vec![];
+        self.inner_functions(variable, &mut candidates);
+        candidates
+            .into_iter()
+            .fold(self.clone(), |equation, candidate| {
+                let Equation::Abs(argument) = &candidate else {
+                    return equation;
+                };
+                match probe_sign(argument, variable, point) {
+                    Some(1) => equation.substitute(&candidate, argument),
+                    Some(_) => {
+                        equation.substitute(&candidate, &Equation::Negative(argument.clone()))
+                    }
+                    None => equation,
+                }
+            })
+    }
+
+    ///The value the equation approaches, finite values may still contain other variables
+    fn approach(&self, variable: &Variable, point: &Point, depth: usize) -> Option<Value> {
+        if !self.contains_variable(variable) {
+            return Some(Value::Finite(self.clone()));
+        }
+        if let Point::Finite(value, _) = point {
+            //Substitution gives the limit wherever the equation is continuous, which needs it to be defined on the side of the point
+            let substituted = self.substitute(&Equation::Variable(variable.clone()), value);
+            if number(&substituted).is_some() {
+                return probe(self, variable, point)
+                    .all(|value| value.is_some_and(f64::is_finite))
+                    .then_some(Value::Finite(substituted));
+            }
+        }
+        if let Some(value) = self.rational_limit(variable, point) {
+            return Some(value);
+        }
+        let approach = |equation: &Equation| equation.approach(variable, point, depth);
+        match self {
+            Equation::Variable(_) => Some(point.value()),
+            Equation::Negative(negative) => Some(approach(negative)?.negate()),
+            Equation::Addition(terms) => approach_sum(terms, variable, point, depth),
+            Equation::Multiplication(factors) => approach_product(factors, variable, point, depth),
+            Equation::Division(division) => {
+                approach_quotient(&division.0, &division.1, variable, point, depth)
+            }
+            Equation::Power(power) => approach_power(&power.0, &power.1, variable, point, depth),
+            Equation::Exp(exp) => Some(match approach(exp)? {
+                Value::Finite(value) => Value::Finite(Equation::Exp(Box::new(value))),
+                Value::PlusInfinity => Value::PlusInfinity,
+                Value::MinusInfinity => Value::zero(),
+            }),
+            Equation::Ln(ln) => match approach(ln)? {
+                Value::Finite(value) => match sign(&value) {
+                    Some(0) if probe_sign(ln, variable, point) == Some(1) => {
+                        Some(Value::MinusInfinity)
+                    }
+                    Some(0) => None,
+                    _ => continuous(Equation::Ln(Box::new(value))),
+                },
+                Value::PlusInfinity => Some(Value::PlusInfinity),
+                Value::MinusInfinity => None,
+            },
+            Equation::Abs(abs) => match approach(abs)? {
+                Value::Finite(value) => continuous(Equation::Abs(Box::new(value))),
+                _ => Some(Value::PlusInfinity),
+            },
+            Equation::Arctan(arctan) => match approach(arctan)? {
+                Value::Finite(value) => continuous(Equation::Arctan(Box::new(value))),
+                infinity => {
+                    let half_pi = Equation::Division(Box::new((
+                        Equation::Variable(Variable::Constant(Constant::PI)),
+                        Equation::Variable(Variable::Integer(2)),
+                    )));
+                    Some(Value::Finite(half_pi).with_sign_of(&infinity))
+                }
+            },
+            Equation::Tanh(tanh) => match approach(tanh)? {
+                Value::Finite(value) => continuous(Equation::Tanh(Box::new(value))),
+                infinity => Some(Value::one().with_sign_of(&infinity)),
+            },
+            Equation::Sinh(sinh) => match approach(sinh)? {
+                Value::Finite(value) => continuous(Equation::Sinh(Box::new(value))),
+                infinity => Some(infinity),
+            },
+            Equation::Cosh(cosh) => match approach(cosh)? {
+                Value::Finite(value) => continuous(Equation::Cosh(Box::new(value))),
+                _ => Some(Value::PlusInfinity),
+            },
+            Equation::Sin(x) => continuous(Equation::Sin(Box::new(approach(x)?.finite()?))),
+            Equation::Cos(x) => continuous(Equation::Cos(Box::new(approach(x)?.finite()?))),
+            Equation::Tan(x) => continuous(Equation::Tan(Box::new(approach(x)?.finite()?))),
+            Equation::Arcsin(x) => continuous(Equation::Arcsin(Box::new(approach(x)?.finite()?))),
+            Equation::Arccos(x) => continuous(Equation::Arccos(Box::new(approach(x)?.finite()?))),
+            Equation::Derivative(derivative) => approach(&derivative.evaluate()),
+            Equation::Equals(_)
+            | Equation::Integral(_)
+            | Equation::Limit(_)
+            | Equation::Dot(_)
+            | Equation::Cross(_)
+            | Equation::Matrix(_) => None,
+        }
+    }
+
+    ///Uses the dominant terms at infinity, or cancels common factors at a point, if the equation is a quotient of polynomials
+    fn rational_limit(&self, variable: &Variable, point: &Point) -> Option<Value> {
+        let (numerator, denominator) = self.rational_function(variable)?;
+        if numerator.is_zero() {
+            return Some(Value::zero());
+        }
+        match point {
+            Point::Finite(value, side) => {
+                let point = value.calculate_exact()?;
+                let gcd = numerator.gcd(&denominator)?;
+                let numerator = numerator.div_rem(&gcd)?.0;
+                let denominator = denominator.div_rem(&gcd)?.0;
+                if !denominator.evaluate(point)?.is_zero() {
+                    return Some(rational(
+                        numerator
+                            .evaluate(point)?
+                            .checked_div(&denominator.evaluate(point)?)?,
+                    ));
+                }
+                //The denominator is (x-a)^multiplicity * rest and the numerator is not zero at a after cancelling
+                let linear = Polynomial::new(vec![-point, 1.into()]);
+                let (mut rest, mut multiplicity) = (denominator, 0);
+                while rest.evaluate(point)?.is_zero() {
+                    rest = rest.div_rem(&linear)?.0;
+                    multiplicity += 1;
+                }
+                let positive = numerator
+                    .evaluate(point)?
+                    .checked_div(&rest.evaluate(point)?)?
+                    .is_positive();
+                let side_positive = *side == Side::Above || multiplicity % 2 == 0;
+                Some(Value::infinity(positive == side_positive))
+            }
+            Point::PlusInfinity | Point::MinusInfinity => {
+                let (n, d) = (numerator.degree()?, denominator.degree()?);
+                let leading = numerator.leading_coefficient() / denominator.leading_coefficient();
+                if n < d {
+                    Some(Value::zero())
+                } else if n == d {
+                    Some(rational(leading))
+                } else {
+                    let flipped = *point == Point::MinusInfinity && (n - d) % 2 == 1;
+                    Some(Value::infinity(leading.is_positive() != flipped))
+                }
+            }
+        }
+    }
+}
+
+fn approach_sum(
+    terms: &[Equation],
+    variable: &Variable,
+    point: &Point,
+    depth: usize,
+) -> Option<Value> {
+    let (mut finite, mut infinities) = (vec![], vec![]);
+    for term in terms {
+        match term.approach(variable, point, depth)? {
+            Value::Finite(value) => finite.push(value),
+            infinity => infinities.push((term, infinity)),
+        }
+    }
+    let Some((dominant, infinity)) = infinities.first() else {
+        return Some(Value::Finite(Equation::Addition(finite)));
+    };
+    if infinities.iter().all(|(_, other)| other.same(infinity)) {
+        return Some(infinity.clone());
+    }
+    if depth == MAX_DEPTH {
+        return None;
+    }
+    //Infinity minus infinity, written as a single fraction or as f(1 + g/f) for the first infinite term f
+    let rewritten = match combine_fractions(terms) {
+        Some(fraction) => fraction,
+        None => Equation::Multiplication(vec![
+            (*dominant).clone(),
+            Equation::Addition(
+                terms
+                    .iter()
+                    .map(|term| {
+                        Equation::Division(Box::new((term.clone(), (*dominant).clone())))
+                            .simplify_until_complete()
+                    })
+                    .collect(),
+            ),
+        ]),
+    };
+    rewritten.approach(variable, point, depth + 1)
+}
+
+fn approach_product(
+    factors: &[Equation],
+    variable: &Variable,
+    point: &Point,
+    depth: usize,
+) -> Option<Value> {
+    let (mut finite, mut infinite) = (vec![], vec![]);
+    let mut product = Value::one();
+    for factor in factors {
+        match factor.approach(variable, point, depth)? {
+            Value::Finite(value) => {
+                finite.push(factor.clone());
+                product = match product {
+                    Value::Finite(product) => {
+                        Value::Finite(Equation::Multiplication(vec![product, value]))
+                    }
+                    infinity => match sign(&value)? {
+                        0 => return zero_times_infinity(factors, variable, point, depth),
+                        1 => infinity,
+                        _ => infinity.negate(),
+                    },
+                };
+            }
+            infinity => {
+                infinite.push(factor.clone());
+                product = match product {
+                    Value::Finite(product) => match sign(&product)? {
+                        0 => return zero_times_infinity(factors, variable, point, depth),
+                        1 => infinity,
+                        _ => infinity.negate(),
+                    },
+                    product if product.same(&infinity) => Value::PlusInfinity,
+                    _ => Value::MinusInfinity,
+                };
+            }
+        }
+    }
+    Some(product)
+}
+
+///Rewrites a product that approaches zero times infinity as infinite/(1/zero) or zero/(1/infinite), which L'Hôpital's rule can be applied to
+fn zero_times_infinity(
+    factors: &[Equation],
+    variable: &Variable,
+    point: &Point,
+    depth: usize,
+) -> Option<Value> {
+    if depth == MAX_DEPTH {
+        return None;
+    }
+    let (mut infinite, mut finite) = (vec![], vec![]);
+    for factor in factors {
+        match factor.approach(variable, point, depth)? {
+            Value::Finite(_) => finite.push(factor.clone()),
+            _ => infinite.push(factor.clone()),
+        }
+    }
+    //Whichever of the two quotients L'Hôpital's rule can be applied to more easily is not known in advance
+    let (infinite, finite) = (
+        Equation::Multiplication(infinite),
+        Equation::Multiplication(finite),
+    );
+    let quotients = [(infinite.clone(), finite.clone()), (finite, infinite)];
+    quotients.into_iter().find_map(|(numerator, inverted)| {
+        approach_quotient(
+            &numerator,
+            &reciprocal(inverted),
+            variable,
+            point,
+            depth + 1,
+        )
+    })
+}
+
+///1/equation, with exponentials and powers written as e^{-u} and b^{-u} so L'Hôpital's rule does not differentiate a quotient of them
+fn reciprocal(equation: Equation) -> Equation {
+    fn invert(equation: Equation) -> Equation {
+        match equation {
+            Equation::Exp(exponent) => Equation::Exp(Box::new(Equation::Negative(exponent))),
+            Equation::Power(power) => {
+                Equation::Power(Box::new((power.0, Equation::Negative(Box::new(power.1)))))
+            }
+            Equation::Multiplication(factors) => {
+                Equation::Multiplication(factors.into_iter().map(invert).collect())
+            }
+            equation => Equation::Division(Box::new((
+                Equation::Variable(Variable::Integer(1)),
+                equation,
+            ))),
+        }
+    }
+    invert(equation).simplify_until_complete()
+}
+
+fn approach_quotient(
+    numerator: &Equation,
+    denominator: &Equation,
+    variable: &Variable,
+    point: &Point,
+    depth: usize,
+) -> Option<Value> {
+    let quotient = || Equation::Division(Box::new((numerator.clone(), denominator.clone())));
+    let numerator_value = numerator.approach(variable, point, depth)?;
+    let denominator_value = denominator.approach(variable, point, depth)?;
+    match (numerator_value, denominator_value) {
+        (Value::Finite(a), Value::Finite(b)) => match (sign(&a), sign(&b)) {
+            (Some(0), Some(0)) => lhopital(numerator, denominator, variable, point, depth),
+            (_, Some(0)) => {
+                let positive = probe_sign(&quotient(), variable, point)? == 1;
+                Some(Value::infinity(positive))
+            }
+            _ => Some(Value::Finite(Equation::Division(Box::new((a, b))))),
+        },
+        (Value::Finite(_), _) => Some(Value::zero()),
+        (infinity, Value::Finite(b)) => match sign(&b) {
+            Some(0) => {
+                let positive = probe_sign(&quotient(), variable, point)? == 1;
+                Some(Value::infinity(positive))
+            }
+            Some(1) => Some(infinity),
+            Some(_) => Some(infinity.negate()),
+            None => None,
+        },
+        _ => lhopital(numerator, denominator, variable, point, depth),
+    }
+}
+
+///L'Hôpital's rule for 0/0 and infinity/infinity
+fn lhopital(
+    numerator: &Equation,
+    denominator: &Equation,
+    variable: &Variable,
+    point: &Point,
+    depth: usize,
+) -> Option<Value> {
+    if depth == MAX_DEPTH {
+        return None;
+    }
+    Equation::Division(Box::new((
+        numerator.differentiate(variable),
+        denominator.differentiate(variable),
+    )))
+    .simplify_until_complete()
+    .approach(variable, point, depth + 1)
+}
+
+fn approach_power(
+    base: &Equation,
+    exponent: &Equation,
+    variable: &Variable,
+    point: &Point,
+    depth: usize,
+) -> Option<Value> {
+    //A power with a varying exponent, or a base that might be negative, is written as exp(exponent*ln(base))
+    let general = || {
+        Equation::Exp(Box::new(Equation::Multiplication(vec![
+            exponent.clone(),
+            Equation::Ln(Box::new(base.clone())),
+        ])))
+        .approach(variable, point, depth)
+    };
+    if exponent.contains_variable(variable) {
+        return general();
+    }
+    let Some(exponent_value) = exponent.calculate_exact() else {
+        return match base.approach(variable, point, depth)? {
+            Value::Finite(value) if sign(&value) == Some(1) => {
+                continuous(Equation::Power(Box::new((value, exponent.clone()))))
+            }
+            _ => general(),
+        };
+    };
+    match base.approach(variable, point, depth)? {
+        Value::Finite(value) => {
+            if sign(&value) == Some(0) && exponent_value.is_negative() {
+                let reciprocal = Equation::Power(Box::new((
+                    base.clone(),
+                    Equation::Variable(Variable::Rational(-exponent_value)),
+                )));
+                return approach_quotient(
+                    &Equation::Variable(Variable::Integer(1)),
+                    &reciprocal,
+                    variable,
+                    point,
+                    depth,
+                );
+            }
+            continuous(Equation::Power(Box::new((value, exponent.clone()))))
+        }
+        _ if exponent_value.is_zero() => Some(Value::one()),
+        _ if exponent_value.is_negative() => Some(Value::zero()),
+        Value::PlusInfinity => Some(Value::PlusInfinity),
+        Value::MinusInfinity if exponent_value.is_integer() => {
+            Some(Value::infinity(exponent_value.to_integer() % 2 == 0))
+        }
+        Value::MinusInfinity => None,
+    }
+}
+
+///Writes a sum containing fractions as a single fraction
+fn combine_fractions(terms: &[Equation]) -> Option<Equation> {
+    let parts = terms
+        .iter()
+        .map(|term| match term {
+            Equation::Division(division) => (division.0.clone(), division.1.clone()),
+            Equation::Negative(negative) => match &**negative {
+                Equation::Division(division) => (
+                    Equation::Negative(Box::new(division.0.clone())),
+                    division.1.clone(),
+                ),
+                _ => (term.clone(), Equation::Variable(Variable::Integer(1))),
+            },
+            _ => (term.clone(), Equation::Variable(Variable::Integer(1))),
+        })
+        .collect::<Vec<_>>();
+    if parts
+        .iter()
+        .all(|(_, denominator)| *denominator == Equation::Variable(Variable::Integer(1)))
+    {
+        return None;
+    }
+    let numerator = (0..parts.len())
+        .map(|i| {
+            let mut product = vec![parts[i].0.clone()];
+            for (j, (_, denominator)) in parts.iter().enumerate() {
+                if i != j {
+                    product.push(denominator.clone());
+                }
+            }
+            Equation::Multiplication(product)
+        })
+        .collect();
+    let denominator = parts
+        .into_iter()
+        .map(|(_, denominator)| denominator)
+        .collect();
+    Some(
+        Equation::Division(Box::new((
+            Equation::Addition(numerator),
+            Equation::Multiplication(denominator),
+        )))
+        .simplify_until_complete(),
+    )
+}
+
+///A function applied to a finite limit, None if the value is outside the domain of the function
+fn continuous(value: Equation) -> Option<Value> {
+    if !value.free_variables().is_empty() || number(&value).is_some() {
+        Some(Value::Finite(value))
+    } else {
+        None
+    }
+}
+
+fn rational(value: Rational64) -> Value {
+    Value::Finite(Equation::Variable(Variable::Rational(value)))
+}
+
+///The value of an equation without variables, None if it is not a finite number
+fn number(equation: &Equation) -> Option<f64> {
+    equation
+        .try_calculate(&BTreeMap::new())
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+///The sign of an equation without variables as -1, 0 or 1
+fn sign(equation: &Equation) -> Option<i8> {
+    if let Some(exact) = equation.calculate_exact() {
+        return Some(exact.signum().to_integer() as i8);
+    }
+    //Simplifying finds zeros like a-a that contain other variables
+    let simplified = equation.clone().simplify_until_complete();
+    if let Some(exact) = simplified.calculate_exact() {
+        return Some(exact.signum().to_integer() as i8);
+    }
+    let value = number(&simplified)?;
+    Some(if value.abs() < TOLERANCE {
+        0
+    } else if value > 0. {
+        1
+    } else {
+        -1
+    })
+}
+
+///The values of the equation at points that get closer to the point, None where it can not be calculated
+fn probe<'a>(
+    equation: &'a Equation,
+    variable: &'a Variable,
+    point: &'a Point,
+) -> impl Iterator<Item = Option<f64>> + 'a {
+    [1e-3, 1e-5, 1e-7].into_iter().map(|offset: f64| {
+        let x = match point {
+            Point::Finite(value, Side::Below) => number(value)? - offset,
+            Point::Finite(value, Side::Above) => number(value)? + offset,
+            Point::PlusInfinity => offset.recip(),
+            Point::MinusInfinity => -offset.recip(),
+        };
+        let values = BTreeMap::from([(variable.clone(), x)]);
+        equation.try_calculate(&values).ok()
+    })
+}
+
+///The sign of the equation close to the point, found by calculating it at points that get closer
+fn probe_sign(equation: &Equation, variable: &Variable, point: &Point) -> Option<i8> {
+    let mut signs = probe(equation, variable, point).map(|value| {
+        value
+            .filter(|value| *value != 0. && !value.is_nan())
+            .map(|value| value.signum() as i8)
+    });
+    let first = signs.next()??;
+    signs.all(|sign| sign == Some(first)).then_some(first)
+}
+
+///1 for \infty and -1 for -\infty
+fn infinity_sign(equation: &Equation) -> Option<i8> {
+    match equation {
+        Equation::Variable(Variable::Constant(Constant::Infinity)) => Some(1),
+        Equation::Negative(negative) => Some(-infinity_sign(negative)?),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Below,
+    Above,
+}
+
+///The point a limit approaches, with the side it is approached from if it is finite
+#[derive(Clone, Debug, PartialEq)]
+enum Point {
+    Finite(Equation, Side),
+    PlusInfinity,
+    MinusInfinity,
+}
+
+impl Point {
+    fn new(point: &Equation, side: Side) -> Self {
+        match infinity_sign(point) {
+            Some(1) => Point::PlusInfinity,
+            Some(_) => Point::MinusInfinity,
+            None => Point::Finite(point.clone(), side),
+        }
+    }
+
+    ///The value of the variable itself
+    fn value(&self) -> Value {
+        match self {
+            Point::Finite(value, _) => Value::Finite(value.clone()),
+            Point::PlusInfinity => Value::PlusInfinity,
+            Point::MinusInfinity => Value::MinusInfinity,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Finite(Equation),
+    PlusInfinity,
+    MinusInfinity,
+}
+
+impl Value {
+    fn zero() -> Self {
+        Value::Finite(Equation::Variable(Variable::Integer(0)))
+    }
+
+    fn one() -> Self {
+        Value::Finite(Equation::Variable(Variable::Integer(1)))
+    }
+
+    fn infinity(positive: bool) -> Self {
+        if positive {
+            Value::PlusInfinity
+        } else {
+            Value::MinusInfinity
+        }
+    }
+
+    fn finite(self) -> Option<Equation> {
+        match self {
+            Value::Finite(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn negate(self) -> Self {
+        match self {
+            Value::Finite(value) => Value::Finite(Equation::Negative(Box::new(value))),
+            Value::PlusInfinity => Value::MinusInfinity,
+            Value::MinusInfinity => Value::PlusInfinity,
+        }
+    }
+
+    ///Negates the value if the infinity is negative
+    fn with_sign_of(self, infinity: &Value) -> Self {
+        match infinity {
+            Value::MinusInfinity => self.negate(),
+            _ => self,
+        }
+    }
+
+    fn same(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Finite(a), Value::Finite(b)) => {
+                let (a, b) = (
+                    a.clone().simplify_until_complete(),
+                    b.clone().simplify_until_complete(),
+                );
+                a == b
+                    || number(&Equation::Addition(vec![
+                        a,
+                        Equation::Negative(Box::new(b)),
+                    ]))
+                    .is_some_and(|difference| difference.abs() < TOLERANCE)
+            }
+            _ => self == other,
+        }
+    }
+
+    fn into_equation(self) -> Equation {
+        match self {
+            Value::Finite(value) => value.simplify_until_complete(),
+            Value::PlusInfinity => Equation::Variable(Variable::Constant(Constant::Infinity)),
+            Value::MinusInfinity => Equation::Negative(Box::new(Equation::Variable(
+                Variable::Constant(Constant::Infinity),
+            ))),
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use super::polynomial::number;
+use super::{Equation, Variable};
+use num::{CheckedDiv, CheckedMul, CheckedSub, Zero};
+use num_rational::Rational64;
+
+impl Equation {
+    ///The rows of the matrix, None if the equation is not a matrix
+    pub fn rows(&self) -> Option<&Vec<Vec<Equation>>> {
+        match self {
+            Equation::Matrix(rows) => Some(rows),
+            _ => None,
+        }
+    }
+
+    ///The number of rows and columns of the matrix
+    pub fn dimensions(&self) -> Option<(usize, usize)> {
+        let rows = self.rows()?;
+        Some((rows.len(), rows.first().map_or(0, |row| row.len())))
+    }
+
+    ///Applies the function to every entry of the matrix, any other equation is passed to it as a whole
+    pub fn map_entries(&self, mut function: impl FnMut(&Equation) -> Equation) -> Equation {
+        match self {
+            Equation::Matrix(rows) => Equation::Matrix(
+                rows.iter()
+                    .map(|row| row.iter().map(&mut function).collect())
+                    .collect(),
+            ),
+            equation => function(equation),
+        }
+    }
+
+    ///The matrix with its rows as columns
+    pub fn transpose(&self) -> Option<Equation> {
+        let rows = self.rows()?;
+        let (_, columns) = self.dimensions()?;
+        Some(Equation::Matrix(
+            (0..columns)
+                .map(|j| rows.iter().map(|row| row[j].clone()).collect())
+                .collect(),
+        ))
+    }
+
+    ///The product of the matrices, None if the number of columns of the first is not the number of rows of the second
+    pub fn matrix_product(&self, other: &Equation) -> Option<Equation> {
+        let ((rows, inner), (other_rows, columns)) = (self.dimensions()?, other.dimensions()?);
+        if inner != other_rows {
+            return None;
+        }
+        let (a, b) = (self.rows()?, other.rows()?);
+        Some(Equation::Matrix(
+            (0..rows)
+                .map(|i| {
+                    (0..columns)
+                        .map(|j| {
+                            Equation::Addition(
+                                (0..inner)
+                                    .map(|k| {
+                                        Equation::Multiplication(vec![
+                                            a[i][k].clone(),
+                                            b[k][j].clone(),
+                                        ])
+                                    })
+                                    .collect(),
+                            )
+                        })
+                        .collect()
+                })
+                .collect(),
+        ))
+    }
+
+    ///The determinant of a square matrix, by Gaussian elimination if every entry is a number and by expansion along the first row otherwise
+    pub fn determinant(&self) -> Option<Equation> {
+        let (rows, columns) = self.dimensions()?;
+        if rows != columns {
+            return None;
+        }
+        if let Some(exact) = self.exact_entries() {
+            return exact_determinant(exact).map(number);
+        }
+        Some(cofactor_determinant(self.rows()?))
+    }
+
+    ///The inverse of a square matrix, None if it is singular
+    pub fn inverse(&self) -> Option<Equation> {
+        let (size, columns) = self.dimensions()?;
+        if size != columns {
+            return None;
+        }
+        if let Some(exact) = self.exact_entries() {
+            let identity = (0..size)
+                .map(|i| {
+                    (0..size)
+                        .map(|j| Rational64::from((i == j) as i64))
+                        .collect()
+                })
+                .collect::<Vec<_>>();
+            return solve_linear_system(&exact, &identity).map(numbers);
+        }
+        //The inverse is the adjugate divided by the determinant, the adjugate being the transposed matrix of cofactors
+        let rows = self.rows()?;
+        let determinant = cofactor_determinant(rows).simplify_until_complete();
+        if determinant == Equation::Variable(Variable::Integer(0)) {
+            return None;
+        }
+        Some(Equation::Matrix(
+            (0..size)
+                .map(|i| {
+                    (0..size)
+                        .map(|j| {
+                            let cofactor = cofactor_determinant(&minor(rows, j, i));
+                            let cofactor = if (i + j) % 2 == 0 {
+                                cofactor
+                            } else {
+                                Equation::Negative(Box::new(cofactor))
+                            };
+                            Equation::Division(Box::new((cofactor, determinant.clone())))
+                                .simplify_until_complete()
+                        })
+                        .collect()
+                })
+                .collect(),
+        ))
+    }
+
+    ///Solves the linear system self * x = constants for x, where the constants are a matrix with as many rows as self, None if self is singular
+    pub fn solve_linear(&self, constants: &Equation) -> Option<Equation> {
+        let (size, columns) = self.dimensions()?;
+        if size != columns || constants.dimensions()?.0 != size {
+            return None;
+        }
+        if let (Some(coefficients), Some(exact_constants)) =
+            (self.exact_entries(), constants.exact_entries())
+        {
+            return solve_linear_system(&coefficients, &exact_constants).map(numbers);
+        }
+        self.inverse()?.matrix_product(constants)
+    }
+
+    ///The entries of the matrix as exact numbers, None if any entry is not a number
+    fn exact_entries(&self) -> Option<Vec<Vec<Rational64>>> {
+        self.rows()?
+            .iter()
+            .map(|row| row.iter().map(|entry| entry.calculate_exact()).collect())
+            .collect()
+    }
+}
+
+///Solves coefficients * x = constants for every column of the constants by Gauss-Jordan elimination with exact arithmetic, None if the coefficients are singular or a number overflows
+pub fn solve_linear_system(
+    coefficients: &[Vec<Rational64>],
+    constants: &[Vec<Rational64>],
+) -> Option<Vec<Vec<Rational64>>> {
+    let size = coefficients.len();
+    if constants.len() != size || coefficients.iter().any(|row| row.len() != size) {
+        return None;
+    }
+    let mut augmented = coefficients
+        .iter()
+        .zip(constants)
+        .map(|(row, constant)| row.iter().chain(constant).copied().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    for column in 0..size {
+        let pivot = (column..size).find(|row| !augmented[*row][column].is_zero())?;
+        augmented.swap(column, pivot);
+        let pivot = augmented[column][column];
+        for entry in augmented[column].iter_mut() {
+            *entry = entry.checked_div(&pivot)?;
+        }
+        for row in 0..size {
+            let factor = augmented[row][column];
+            if row == column || factor.is_zero() {
+                continue;
+            }
+            for j in column..augmented[row].len() {
+                let eliminated = factor.checked_mul(&augmented[column][j])?;
+                augmented[row][j] = augmented[row][j].checked_sub(&eliminated)?;
+            }
+        }
+    }
+    Some(
+        augmented
+            .into_iter()
+            .map(|row| row[size..].to_vec())
+            .collect(),
+    )
+}
+
+///The determinant as the product of the pivots of Gaussian elimination, with the sign flipped for every swap of rows
+fn exact_determinant(mut rows: Vec<Vec<Rational64>>) -> Option<Rational64> {
+    let size = rows.len();
+    let mut determinant = Rational64::from(1);
+    for column in 0..size {
+        let Some(pivot) = (column..size).find(|row| !rows[*row][column].is_zero()) else {
+            return Some(Rational64::zero());
+        };
+        if pivot != column {
+            rows.swap(column, pivot);
+            determinant = -determinant;
+        }
+        let pivot = rows[column][column];
+        determinant = determinant.checked_mul(&pivot)?;
+        let (above, below) = rows.split_at_mut(column + 1);
+        for row in below {
+            let factor = row[column].checked_div(&pivot)?;
+            for (entry, pivot_entry) in row.iter_mut().zip(&above[column]).skip(column) {
+                *entry = entry.checked_sub(&factor.checked_mul(pivot_entry)?)?;
+            }
+        }
+    }
+    Some(determinant)
+}
+
+///The determinant by expansion along the first row, skipping entries that are zero
+fn cofactor_determinant(rows: &[Vec<Equation>]) -> Equation {
+    match rows.len() {
+        0 => return Equation::Variable(Variable::Integer(1)),
+        1 => return rows[0][0].clone(),
+        _ => {}
+    }
+    let terms = rows[0]
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| **entry != Equation::Variable(Variable::Integer(0)))
+        .map(|(j, entry)| {
+            let term = Equation::Multiplication(vec![
+                entry.clone(),
+                cofactor_determinant(&minor(rows, 0, j)),
+            ]);
+            if j % 2 == 0 {
+                term
+            } else {
+                Equation::Negative(Box::new(term))
+            }
+        })
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        Equation::Variable(Variable::Integer(0))
+    } else {
+        Equation::Addition(terms)
+    }
+}
+
+///The matrix without the row and the column
+fn minor(rows: &[Vec<Equation>], row: usize, column: usize) -> Vec<Vec<Equation>> {
+    rows.iter()
+        .enumerate()
+        .filter(|(i, _)| *i != row)
+        .map(|(_, entries)| {
+            entries
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != column)
+                .map(|(_, entry)| entry.clone())
+                .collect()
+        })
+        .collect()
+}
+
+fn numbers(rows: Vec<Vec<Rational64>>) -> Equation {
+    Equation::Matrix(
+        rows.into_iter()
+            .map(|row| row.into_iter().map(number).collect())
+            .collect(),
+    )
+}
@@ -3,12 +3,41 @@ use num_rational::Rational64;
 mod calculate;
 mod calculate_exact;
 mod compare;
+mod derivative;
 mod differentiate;
+mod differentiate_steps;
+mod equivalence;
+mod expand;
 mod factors;
 mod integrate;
+mod limit;
+mod matrix;
 mod multiply_by;
+mod polynomial;
+mod quadrature;
+mod roots;
+mod series;
 mod simplify;
+mod solve;
+mod system;
 mod to_latex;
+mod uncertainty;
+mod units;
+mod vector;
+
+pub use calculate::EvalError;
+pub use differentiate_steps::DifferentiationStep;
+pub use equivalence::{DomainHint, Equivalence};
+pub use matrix::solve_linear_system;
+pub use polynomial::Polynomial;
+pub use quadrature::{Quadrature, QuadratureMethod, QuadratureOptions};
+pub use roots::RootOptions;
+pub use series::Series;
+pub use simplify::{Rewrite, Simplification, SimplifyOptions};
+pub use system::System;
+pub use uncertainty::Measurement;
+pub use units::{Dimension, Quantity, Unit};
+pub use vector::VectorValue;
 
 ///Represents a generic math object
 #[derive(Eq, PartialEq, Hash, Clone, Debug, Ord, PartialOrd)]
@@ -20,10 +49,55 @@ pub enum Equation {
     Division(Box<(Equation, Equation)>),
     Power(Box<(Equation, Equation)>),
     Ln(Box<Equation>),
+    Exp(Box<Equation>),
     Equals(Box<(Equation, Equation)>),
     Sin(Box<Equation>),
     Cos(Box<Equation>),
     Abs(Box<Equation>),
+    Tan(Box<Equation>),
+    Arcsin(Box<Equation>),
+    Arccos(Box<Equation>),
+    Arctan(Box<Equation>),
+    Sinh(Box<Equation>),
+    Cosh(Box<Equation>),
+    Tanh(Box<Equation>),
+    Integral(Box<Integral>),
+    Limit(Box<Limit>),
+    Derivative(Box<Derivative>),
+    Dot(Box<(Equation, Equation)>),
+    Cross(Box<(Equation, Equation)>),
+    ///The rows of a matrix, which all have the same number of entries
+    Matrix(Vec<Vec<Equation>>),
+}
+///An integral of the integrand over the variable, which is definite if it has lower and upper bounds
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Ord, PartialOrd)]
+pub struct Integral {
+    pub integrand: Equation,
+    pub variable: Variable,
+    pub bounds: Option<(Equation, Equation)>,
+}
+///The limit of the expression as the variable approaches the point, which can be infinite
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Ord, PartialOrd)]
+pub struct Limit {
+    pub expression: Equation,
+    pub variable: Variable,
+    pub point: Equation,
+    pub direction: LimitDirection,
+}
+///The derivative of the expression to each of the variables in turn, a variable occurring n times gives the n-th derivative
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Ord, PartialOrd)]
+pub struct Derivative {
+    pub expression: Equation,
+    pub variables: Vec<Variable>,
+}
+///The side from which the variable approaches the point of a limit
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug, Ord, PartialOrd)]
+pub enum LimitDirection {
+    Both,
+    ///From below, written as a^-
+    Left,
+    ///From above, written as a^+
+    Right,
 }
 ///Represents a single number
 #[derive(Eq, PartialEq, Hash, Clone, Debug, Ord, PartialOrd)]
@@ -39,6 +113,7 @@ pub enum Variable {
 pub enum Constant {
     PI,
     E,
+    Infinity,
 }
 
 impl Equation {
@@ -55,20 +130,135 @@ impl Equation {
             _ => None,
         }
     }
-}
-
-impl Variable {
-    fn get_number_or_none(&self) -> Option<Rational64> {
+    ///Checks whether the variable occurs anywhere in the equation
+    pub fn contains_variable(&self, variable: &Variable) -> bool {
         match self {
-            Variable::Integer(n) => Some((*n).into()),
-            Variable::Rational(r) => Some(*r),
-            _ => None,
+            Equation::Variable(v) => v == variable,
+            Equation::Addition(terms) | Equation::Multiplication(terms) => {
+                terms.iter().any(|x| x.contains_variable(variable))
+            }
+            Equation::Division(pair)
+            | Equation::Power(pair)
+            | Equation::Equals(pair)
+            | Equation::Dot(pair)
+            | Equation::Cross(pair) => {
+                pair.0.contains_variable(variable) || pair.1.contains_variable(variable)
+            }
+            Equation::Negative(x)
+            | Equation::Ln(x)
+            | Equation::Exp(x)
+            | Equation::Sin(x)
+            | Equation::Cos(x)
+            | Equation::Abs(x)
+            | Equation::Tan(x)
+            | Equation::Arcsin(x)
+            | Equation::Arccos(x)
+            | Equation::Arctan(x)
+            | Equation::Sinh(x)
+            | Equation::Cosh(x)
+            | Equation::Tanh(x) => x.contains_variable(variable),
+            Equation::Limit(limit) => {
+                limit.point.contains_variable(variable)
+                    || (limit.variable != *variable && limit.expression.contains_variable(variable))
+            }
+            Equation::Derivative(derivative) => derivative.expression.contains_variable(variable),
+            Equation::Matrix(rows) => rows
+                .iter()
+                .flatten()
+                .any(|entry| entry.contains_variable(variable)),
+            Equation::Integral(integral) => match &integral.bounds {
+                Some((lower, upper)) => {
+                    lower.contains_variable(variable)
+                        || upper.contains_variable(variable)
+                        || (integral.variable != *variable
+                            && integral.integrand.contains_variable(variable))
+                }
+                None => {
+                    integral.variable == *variable || integral.integrand.contains_variable(variable)
+                }
+            },
         }
     }
-    fn get_integer_or_none(&self) -> Option<i64> {
+    ///Replaces every occurrence of `from` in the equation by `to`
+    pub fn substitute(&self, from: &Equation, to: &Equation) -> Equation {
+        if self == from {
+            return to.clone();
+        }
+        let substitute = |x: &Equation| Box::new(x.substitute(from, to));
+        let substitute_pair = |pair: &(Equation, Equation)| {
+            Box::new((pair.0.substitute(from, to), pair.1.substitute(from, to)))
+        };
         match self {
-            Variable::Integer(n) => Some(*n),
-            _ => None,
+            Equation::Variable(_) => self.clone(),
+            Equation::Addition(terms) => {
+                Equation::Addition(terms.iter().map(|x| x.substitute(from, to)).collect())
+            }
+            Equation::Multiplication(terms) => {
+                Equation::Multiplication(terms.iter().map(|x| x.substitute(from, to)).collect())
+            }
+            Equation::Division(pair) => Equation::Division(substitute_pair(pair)),
+            Equation::Power(pair) => Equation::Power(substitute_pair(pair)),
+            Equation::Equals(pair) => Equation::Equals(substitute_pair(pair)),
+            Equation::Dot(pair) => Equation::Dot(substitute_pair(pair)),
+            Equation::Cross(pair) => Equation::Cross(substitute_pair(pair)),
+            Equation::Matrix(_) => self.map_entries(|entry| entry.substitute(from, to)),
+            Equation::Negative(x) => Equation::Negative(substitute(x)),
+            Equation::Ln(x) => Equation::Ln(substitute(x)),
+            Equation::Exp(x) => Equation::Exp(substitute(x)),
+            Equation::Sin(x) => Equation::Sin(substitute(x)),
+            Equation::Cos(x) => Equation::Cos(substitute(x)),
+            Equation::Abs(x) => Equation::Abs(substitute(x)),
+            Equation::Tan(x) => Equation::Tan(substitute(x)),
+            Equation::Arcsin(x) => Equation::Arcsin(substitute(x)),
+            Equation::Arccos(x) => Equation::Arccos(substitute(x)),
+            Equation::Arctan(x) => Equation::Arctan(substitute(x)),
+            Equation::Sinh(x) => Equation::Sinh(substitute(x)),
+            Equation::Cosh(x) => Equation::Cosh(substitute(x)),
+            Equation::Tanh(x) => Equation::Tanh(substitute(x)),
+            Equation::Integral(integral) => {
+                //The integration variable is bound inside the integrand
+                let integrand = if from.contains_variable(&integral.variable) {
+                    integral.integrand.clone()
+                } else {
+                    integral.integrand.substitute(from, to)
+                };
+                Equation::Integral(Box::new(Integral {
+                    integrand,
+                    variable: integral.variable.clone(),
+                    bounds: integral.bounds.as_ref().map(|(lower, upper)| {
+                        (lower.substitute(from, to), upper.substitute(from, to))
+                    }),
+                }))
+            }
+            Equation::Limit(limit) => {
+                //The limit variable is bound inside the expression
+                let expression = if from.contains_variable(&limit.variable) {
+                    limit.expression.clone()
+                } else {
+                    limit.expression.substitute(from, to)
+                };
+                Equation::Limit(Box::new(Limit {
+                    expression,
+                    variable: limit.variable.clone(),
+                    point: limit.point.substitute(from, to),
+                    direction: limit.direction,
+                }))
+            }
+            Equation::Derivative(derivative) => {
+                //Substituting a variable that is differentiated to only makes sense afterwards
+                if derivative
+                    .variables
+                    .iter()
+                    .any(|variable| from.contains_variable(variable))
+                {
+                    derivative.evaluate().substitute(from, to)
+                } else {
+                    Equation::Derivative(Box::new(Derivative {
+                        expression: derivative.expression.substitute(from, to),
+                        variables: derivative.variables.clone(),
+                    }))
+                }
+            }
         }
     }
 }
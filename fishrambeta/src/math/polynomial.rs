@@ -0,0 +1,412 @@
+use super::solve::exact_square_root;
+use super::{Equation, Variable};
+use num::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Integer, Signed, Zero};
+use num_rational::Rational64;
+
+///A polynomial in a single variable with rational coefficients.
+///The arithmetic returns None when a coefficient overflows or the degree gets above MAX_DEGREE
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Polynomial {
+    ///Indexed by degree, without zeros at the highest degrees so the zero polynomial is empty
+    coefficients: Vec<Rational64>,
+}
+
+impl Polynomial {
+    ///The highest degree a polynomial can have, higher powers take too long to factor and their coefficients overflow
+    pub const MAX_DEGREE: usize = 256;
+
+    ///Creates the polynomial from its coefficients, starting at degree zero
+    pub fn new(mut coefficients: Vec<Rational64>) -> Self {
+        while coefficients.last().is_some_and(|c| c.is_zero()) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    pub fn zero() -> Self {
+        Polynomial::default()
+    }
+
+    pub fn constant(constant: Rational64) -> Self {
+        Polynomial::new(vec![constant])
+    }
+
+    ///The polynomial coefficient * x^degree
+    pub fn monomial(coefficient: Rational64, degree: usize) -> Self {
+        let mut coefficients = vec![Rational64::zero(); degree + 1];
+        coefficients[degree] = coefficient;
+        Polynomial::new(coefficients)
+    }
+
+    ///Expands the equation into a polynomial in the variable, None if it is not a polynomial with rational coefficients or its degree is above MAX_DEGREE
+    pub fn from_equation(equation: &Equation, variable: &Variable) -> Option<Self> {
+        let constant = |equation: &Equation| {
+            let polynomial = Polynomial::from_equation(equation, variable)?;
+            (polynomial.degree().unwrap_or(0) == 0).then(|| polynomial.coefficient(0))
+        };
+        match equation {
+            Equation::Variable(v) if v == variable => Some(Polynomial::monomial(1.into(), 1)),
+            Equation::Negative(negative) => {
+                Polynomial::from_equation(negative, variable)?.checked_neg()
+            }
+            Equation::Addition(terms) => terms.iter().try_fold(Polynomial::zero(), |sum, term| {
+                sum.checked_add(&Polynomial::from_equation(term, variable)?)
+            }),
+            Equation::Multiplication(factors) => {
+                factors
+                    .iter()
+                    .try_fold(Polynomial::constant(1.into()), |product, factor| {
+                        product.checked_mul(&Polynomial::from_equation(factor, variable)?)
+                    })
+            }
+            Equation::Division(division) => {
+                let denominator = constant(&division.1)?;
+                if denominator.is_zero() {
+                    return None;
+                }
+                Polynomial::from_equation(&division.0, variable)?.scale(denominator.recip())
+            }
+            Equation::Power(power) => {
+                let exponent = constant(&power.1)?;
+                if !exponent.is_integer() || exponent.is_negative() {
+                    return None;
+                }
+                let exponent = u32::try_from(exponent.to_integer()).ok()?;
+                Polynomial::from_equation(&power.0, variable)?.pow(exponent)
+            }
+            _ if !equation.contains_variable(variable) => {
+                Some(Polynomial::constant(equation.calculate_exact()?))
+            }
+            _ => None,
+        }
+    }
+
+    ///Writes the polynomial as a sum of terms in the variable, starting at the highest degree
+    pub fn to_equation(&self, variable: &Variable) -> Equation {
+        let x = Equation::Variable(variable.clone());
+        let mut terms = vec![];
+        for (degree, coefficient) in self.coefficients.iter().enumerate().rev() {
+            if coefficient.is_zero() {
+                continue;
+            }
+            let power = match degree {
+                0 => None,
+                1 => Some(x.clone()),
+                _ => Some(Equation::Power(Box::new((
+                    x.clone(),
+                    Equation::Variable(Variable::Integer(degree as i64)),
+                )))),
+            };
+            let term = match power {
+                None => number(coefficient.abs()),
+                Some(power) if coefficient.abs() == 1.into() => power,
+                Some(power) => Equation::Multiplication(vec![number(coefficient.abs()), power]),
+            };
+            terms.push(if coefficient.is_negative() {
+                Equation::Negative(Box::new(term))
+            } else {
+                term
+            });
+        }
+        match terms.len() {
+            0 => Equation::Variable(Variable::Integer(0)),
+            1 => terms.pop().unwrap(),
+            _ => Equation::Addition(terms),
+        }
+    }
+
+    ///The coefficients starting at degree zero, empty for the zero polynomial
+    pub fn coefficients(&self) -> &[Rational64] {
+        &self.coefficients
+    }
+
+    ///The highest power with a nonzero coefficient, None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn coefficient(&self, degree: usize) -> Rational64 {
+        self.coefficients
+            .get(degree)
+            .copied()
+            .unwrap_or_else(Rational64::zero)
+    }
+
+    pub fn leading_coefficient(&self) -> Rational64 {
+        self.coefficients
+            .last()
+            .copied()
+            .unwrap_or_else(Rational64::zero)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    pub fn evaluate(&self, x: Rational64) -> Option<Rational64> {
+        self.coefficients
+            .iter()
+            .rev()
+            .try_fold(Rational64::zero(), |result, coefficient| {
+                result.checked_mul(&x)?.checked_add(coefficient)
+            })
+    }
+
+    pub fn scale(&self, factor: Rational64) -> Option<Self> {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .map(|c| c.checked_mul(&factor))
+            .collect::<Option<_>>()?;
+        Some(Polynomial::new(coefficients))
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        self.scale(Rational64::from(-1))
+    }
+
+    pub fn checked_add(&self, other: &Polynomial) -> Option<Self> {
+        let mut result =
+            vec![Rational64::zero(); self.coefficients.len().max(other.coefficients.len())];
+        for coefficients in [&self.coefficients, &other.coefficients] {
+            for (i, coefficient) in coefficients.iter().enumerate() {
+                result[i] = result[i].checked_add(coefficient)?;
+            }
+        }
+        Some(Polynomial::new(result))
+    }
+
+    pub fn checked_sub(&self, other: &Polynomial) -> Option<Self> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, other: &Polynomial) -> Option<Self> {
+        if self.is_zero() || other.is_zero() {
+            return Some(Polynomial::zero());
+        }
+        let length = self.coefficients.len() + other.coefficients.len() - 1;
+        if length > Polynomial::MAX_DEGREE + 1 {
+            return None;
+        }
+        let mut result = vec![Rational64::zero(); length];
+        for (i, x) in self.coefficients.iter().enumerate() {
+            for (j, y) in other.coefficients.iter().enumerate() {
+                result[i + j] = result[i + j].checked_add(&x.checked_mul(y)?)?;
+            }
+        }
+        Some(Polynomial::new(result))
+    }
+
+    ///Raises the polynomial to a power by repeated squaring
+    pub fn pow(&self, mut exponent: u32) -> Option<Self> {
+        let degree = self.degree().unwrap_or(0);
+        if degree.checked_mul(exponent as usize)? > Polynomial::MAX_DEGREE {
+            return None;
+        }
+        let (mut result, mut square) = (Polynomial::constant(1.into()), self.clone());
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = result.checked_mul(&square)?;
+            }
+            exponent /= 2;
+            if exponent > 0 {
+                square = square.checked_mul(&square)?;
+            }
+        }
+        Some(result)
+    }
+
+    ///Divides by the leading coefficient, so the leading coefficient becomes one
+    pub fn monic(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(self.clone());
+        }
+        self.scale(self.leading_coefficient().recip())
+    }
+
+    pub fn derivative(&self) -> Option<Self> {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(degree, c)| c.checked_mul(&Rational64::from(degree as i64)))
+            .collect::<Option<_>>()?;
+        Some(Polynomial::new(coefficients))
+    }
+
+    ///Polynomial long division, returns the quotient and the remainder. None if the divisor is zero
+    pub fn div_rem(&self, divisor: &Polynomial) -> Option<(Polynomial, Polynomial)> {
+        let degree = divisor.degree()?;
+        let leading = divisor.leading_coefficient();
+        let mut remainder = self.coefficients.clone();
+        if remainder.len() <= degree {
+            return Some((Polynomial::zero(), self.clone()));
+        }
+        let mut quotient = vec![Rational64::zero(); remainder.len() - degree];
+        while remainder.len() > degree {
+            let shift = remainder.len() - 1 - degree;
+            let factor = remainder.last()?.checked_div(&leading)?;
+            quotient[shift] = factor;
+            for (i, coefficient) in divisor.coefficients.iter().enumerate() {
+                remainder[i + shift] =
+                    remainder[i + shift].checked_sub(&coefficient.checked_mul(&factor)?)?;
+            }
+            remainder.pop();
+            remainder = Polynomial::new(remainder).coefficients;
+        }
+        Some((Polynomial::new(quotient), Polynomial::new(remainder)))
+    }
+
+    ///The monic greatest common divisor, found with the Euclidean algorithm
+    pub fn gcd(&self, other: &Polynomial) -> Option<Polynomial> {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let remainder = a.div_rem(&b)?.1;
+            a = b;
+            b = remainder;
+        }
+        a.monic()
+    }
+
+    ///Finds a rational root using the rational root theorem
+    pub fn rational_root(&self) -> Option<Rational64> {
+        if self.degree()? == 0 {
+            return None;
+        }
+        if self.coefficient(0).is_zero() {
+            return Some(Rational64::zero());
+        }
+        let (_, primitive) = self.primitive()?;
+        let constant = primitive.coefficient(0).to_integer().abs();
+        let leading = primitive.leading_coefficient().to_integer().abs();
+        if constant.max(leading) > 100_000 {
+            return None;
+        }
+        for p in (1..=constant).filter(|p| constant % p == 0) {
+            for q in (1..=leading).filter(|q| leading % q == 0) {
+                for candidate in [Rational64::new(p, q), Rational64::new(-p, q)] {
+                    if self
+                        .evaluate(candidate)
+                        .is_some_and(|value| value.is_zero())
+                    {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    ///Splits off the content, leaving a polynomial with coprime integer coefficients and a positive leading coefficient
+    pub fn primitive(&self) -> Option<(Rational64, Polynomial)> {
+        if self.is_zero() {
+            return Some((Rational64::zero(), Polynomial::zero()));
+        }
+        let denominator = self
+            .coefficients
+            .iter()
+            .try_fold(1i64, |lcm, coefficient| {
+                (lcm / lcm.gcd(coefficient.denom())).checked_mul(*coefficient.denom())
+            })?;
+        let numerator = self
+            .coefficients
+            .iter()
+            .try_fold(0, |gcd: i64, coefficient| {
+                Some(gcd.gcd(&coefficient.checked_mul(&denominator.into())?.to_integer()))
+            })?;
+        let mut content = Rational64::new(numerator, denominator);
+        if self.leading_coefficient().is_negative() {
+            content = -content;
+        }
+        Some((content, self.scale(content.recip())?))
+    }
+
+    ///Splits the polynomial into monic factors without repeated roots, every factor with the power it occurs to, using Yun's algorithm
+    pub fn square_free_decomposition(&self) -> Option<Vec<(Polynomial, u32)>> {
+        let mut factors = vec![];
+        if self.degree().unwrap_or(0) == 0 {
+            return Some(factors);
+        }
+        let derivative = self.derivative()?;
+        let repeated = self.gcd(&derivative)?;
+        let mut b = self.div_rem(&repeated)?.0.monic()?;
+        let mut d = derivative
+            .div_rem(&repeated)?
+            .0
+            .scale(self.leading_coefficient().recip())?
+            .checked_sub(&b.derivative()?)?;
+        let mut power = 1;
+        while b.degree().unwrap_or(0) > 0 {
+            let factor = b.gcd(&d)?;
+            b = b.div_rem(&factor)?.0;
+            let c = d.div_rem(&factor)?.0;
+            d = c.checked_sub(&b.derivative()?)?;
+            if factor.degree().unwrap_or(0) > 0 {
+                factors.push((factor, power));
+            }
+            power += 1;
+        }
+        Some(factors)
+    }
+
+    ///Factors over the rationals into a constant and primitive factors with their powers, using rational roots and differences of squares
+    pub fn factor(&self) -> Option<(Rational64, Vec<(Polynomial, u32)>)> {
+        let mut constant = self.leading_coefficient();
+        let mut factors = vec![];
+        for (square_free, power) in self.square_free_decomposition()? {
+            for factor in square_free.split()? {
+                let (content, primitive) = factor.primitive()?;
+                let content = (0..power).try_fold(Rational64::from(1), |product, _| {
+                    product.checked_mul(&content)
+                })?;
+                constant = constant.checked_mul(&content)?;
+                match factors.iter_mut().find(|(f, _)| *f == primitive) {
+                    Some((_, existing)) => *existing += power,
+                    None => factors.push((primitive, power)),
+                }
+            }
+        }
+        Some((constant, factors))
+    }
+
+    ///Splits a monic polynomial into monic factors
+    fn split(&self) -> Option<Vec<Polynomial>> {
+        if let Some(root) = self.rational_root() {
+            let linear = Polynomial::new(vec![-root, 1.into()]);
+            let mut factors = vec![linear.clone()];
+            factors.append(&mut self.div_rem(&linear)?.0.split()?);
+            return Some(factors);
+        }
+        //x^2k - c = (x^k - sqrt(c)) * (x^k + sqrt(c))
+        if let Some(degree) = self
+            .degree()
+            .filter(|degree| degree % 2 == 0 && *degree > 0)
+        {
+            let is_binomial = self.coefficients[1..degree].iter().all(|c| c.is_zero());
+            let square = -self.coefficient(0);
+            if let Some(root) = (is_binomial && square.is_positive())
+                .then(|| exact_square_root(square))
+                .flatten()
+            {
+                let half = Polynomial::monomial(1.into(), degree / 2);
+                let mut factors = half.checked_sub(&Polynomial::constant(root))?.split()?;
+                factors.append(&mut half.checked_add(&Polynomial::constant(root))?.split()?);
+                return Some(factors);
+            }
+        }
+        if self.degree().unwrap_or(0) > 0 {
+            Some(vec![self.clone()])
+        } else {
+            Some(vec![])
+        }
+    }
+}
+
+pub(super) fn number(rational: Rational64) -> Equation {
+    if rational.is_integer() {
+        Equation::Variable(Variable::Integer(rational.to_integer()))
+    } else {
+        Equation::Variable(Variable::Rational(rational))
+    }
+}
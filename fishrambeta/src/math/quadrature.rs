@@ -0,0 +1,199 @@
+use super::{Constant, Equation, EvalError, Variable};
+use std::collections::BTreeMap;
+
+///The rule used on every subinterval when integrating numerically
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuadratureMethod {
+    ///The 15 point Kronrod rule, with the embedded 7 point Gauss rule for the error estimate
+    GaussKronrod,
+    ///Simpson's rule on the interval and on both of its halves
+    Simpson,
+}
+
+///Options for numerical integration
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuadratureOptions {
+    pub method: QuadratureMethod,
+    ///The allowed error, relative to the value if that is larger than 1
+    pub tolerance: f64,
+    ///How often an interval may be split before giving up
+    pub max_subdivisions: usize,
+    ///The values of the other variables in the integrand
+    pub values: BTreeMap<Variable, f64>,
+}
+
+impl Default for QuadratureOptions {
+    fn default() -> Self {
+        QuadratureOptions {
+            method: QuadratureMethod::GaussKronrod,
+            tolerance: 1e-10,
+            max_subdivisions: 500,
+            values: BTreeMap::new(),
+        }
+    }
+}
+
+///A numerically calculated integral
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quadrature {
+    pub value: f64,
+    ///The estimated absolute error of the value
+    pub error: f64,
+    ///The number of times the integrand was calculated
+    pub evaluations: usize,
+}
+
+impl Equation {
+    ///Integrates the equation over the variable from a to b numerically, subdividing the interval with the largest error until the total error is below the tolerance
+    pub fn integrate_numeric(
+        &self,
+        integrate_to: &Variable,
+        a: f64,
+        b: f64,
+        options: &QuadratureOptions,
+    ) -> Result<Quadrature, EvalError> {
+        let mut values = options.values.clone();
+        values
+            .entry(Variable::Constant(Constant::PI))
+            .or_insert(std::f64::consts::PI);
+        values
+            .entry(Variable::Constant(Constant::E))
+            .or_insert(std::f64::consts::E);
+        values.insert(integrate_to.clone(), (a + b) / 2.);
+        //Errors at a single point are left to the sampling, which treats them as NaN
+        match self.try_calculate(&values) {
+            Ok(_)
+            | Err(
+                EvalError::DivisionByZero(_)
+                | EvalError::NonPositiveLogarithm(_, _)
+                | EvalError::NegativeRoot(_, _)
+                | EvalError::OutsideDomain(_, _),
+            ) => {}
+            Err(error) => return Err(error),
+        }
+
+        let mut evaluations = 0;
+        let mut f = |x: f64| {
+            evaluations += 1;
+            values.insert(integrate_to.clone(), x);
+            self.checked_calculate(&values).unwrap_or(f64::NAN)
+        };
+        let singular_endpoint = !f(a).is_finite() || !f(b).is_finite();
+        let (value, error) = if singular_endpoint {
+            //x = a + (b - a)(3t^2 - 2t^3) has dx = 0 at both ends, which removes integrable singularities there
+            let mut g = |t: f64| {
+                let x = a + (b - a) * t * t * (3. - 2. * t);
+                //Points that are rounded to an endpoint have no weight
+                if x == a || x == b {
+                    return 0.;
+                }
+                f(x) * (b - a) * 6. * t * (1. - t)
+            };
+            adaptive(&mut g, 0., 1., options)
+        } else {
+            adaptive(&mut f, a, b, options)
+        };
+        if !value.is_finite() || error > options.tolerance * value.abs().max(1.) {
+            return Err(EvalError::NotConverged(value, error));
+        }
+        Ok(Quadrature {
+            value,
+            error,
+            evaluations,
+        })
+    }
+}
+
+///Integrates f from a to b, splitting the interval with the largest error estimate until the total error is small enough
+fn adaptive(
+    f: &mut impl FnMut(f64) -> f64,
+    a: f64,
+    b: f64,
+    options: &QuadratureOptions,
+) -> (f64, f64) {
+    let rule = |f: &mut dyn FnMut(f64) -> f64, a: f64, b: f64| match options.method {
+        QuadratureMethod::GaussKronrod => gauss_kronrod(f, a, b),
+        QuadratureMethod::Simpson => simpson(f, a, b),
+    };
+    let (value, error) = rule(f, a, b);
+    let mut intervals = vec![(a, b, value, error)];
+    for _ in 0..options.max_subdivisions {
+        let (value, error) = intervals.iter().fold((0., 0.), |(value, error), interval| {
+            (value + interval.2, error + interval.3)
+        });
+        if !error.is_finite() || error <= options.tolerance * f64::max(value.abs(), 1.) {
+            break;
+        }
+        let worst = (0..intervals.len())
+            .max_by(|i, j| intervals[*i].3.total_cmp(&intervals[*j].3))
+            .unwrap();
+        let (a, b, _, _) = intervals.swap_remove(worst);
+        let m = (a + b) / 2.;
+        for (a, b) in [(a, m), (m, b)] {
+            let (value, error) = rule(f, a, b);
+            intervals.push((a, b, value, error));
+        }
+    }
+    intervals.iter().fold((0., 0.), |(value, error), interval| {
+        (value + interval.2, error + interval.3)
+    })
+}
+
+const KRONROD_NODES: [f64; 8] = [
+    0.9914553711208126,
+    0.9491079123427585,
+    0.8648644233597691,
+    0.7415311855993945,
+    0.5860872354676911,
+    0.4058451513773972,
+    0.20778495500789848,
+    0.,
+];
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022935322010529224,
+    0.06309209262997856,
+    0.10479001032225019,
+    0.14065325971552592,
+    0.1690047266392679,
+    0.19035057806478542,
+    0.20443294007529889,
+    0.20948214108472782,
+];
+///The weights of the Gauss rule, whose nodes are every other Kronrod node
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.1294849661688697,
+    0.27970539148927664,
+    0.3818300505051189,
+    0.4179591836734694,
+];
+
+///The 15 point Kronrod estimate of the integral and its difference with the 7 point Gauss estimate
+fn gauss_kronrod(f: &mut dyn FnMut(f64) -> f64, a: f64, b: f64) -> (f64, f64) {
+    let (center, half_length) = ((a + b) / 2., (b - a) / 2.);
+    let (mut kronrod, mut gauss) = (0., 0.);
+    for (i, (node, weight)) in KRONROD_NODES.iter().zip(KRONROD_WEIGHTS).enumerate() {
+        let values = if *node == 0. {
+            f(center)
+        } else {
+            f(center - half_length * node) + f(center + half_length * node)
+        };
+        kronrod += weight * values;
+        if i % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[i / 2] * values;
+        }
+    }
+    (
+        kronrod * half_length,
+        ((kronrod - gauss) * half_length).abs(),
+    )
+}
+
+///Simpson's rule on both halves of the interval with Richardson extrapolation, the error is estimated from the rule on the whole interval
+fn simpson(f: &mut dyn FnMut(f64) -> f64, a: f64, b: f64) -> (f64, f64) {
+    let m = (a + b) / 2.;
+    let (fa, flm, fm, frm, fb) = (f(a), f((a + m) / 2.), f(m), f((m + b) / 2.), f(b));
+    let whole = (b - a) / 6. * (fa + 4. * fm + fb);
+    let halves = (b - a) / 12. * (fa + 4. * flm + 2. * fm + 4. * frm + fb);
+    let difference = halves - whole;
+    (halves + difference / 15., (difference / 15.).abs())
+}
@@ -0,0 +1,203 @@
+use super::{Constant, Equation, EvalError, Variable};
+use std::collections::BTreeMap;
+
+///Options for finding roots numerically
+#[derive(Clone, Debug, PartialEq)]
+pub struct RootOptions {
+    ///The number of equal subintervals that are scanned for sign changes
+    pub subdivisions: usize,
+    ///The allowed absolute error of a root
+    pub tolerance: f64,
+    ///How many steps Brent's and Newton's method may take for one root
+    pub max_iterations: usize,
+    ///The values of the other variables in the equation
+    pub values: BTreeMap<Variable, f64>,
+}
+
+impl Default for RootOptions {
+    fn default() -> Self {
+        RootOptions {
+            subdivisions: 1000,
+            tolerance: 1e-12,
+            max_iterations: 100,
+            values: BTreeMap::new(),
+        }
+    }
+}
+
+impl Equation {
+    ///Finds every root of the equation in the interval in increasing order, where an equation with = has a root where both sides are equal.
+    ///The interval is scanned for sign changes, which are narrowed down with Brent's method, and roots where the sign does not change are found with Newton's method from the smallest values in between
+    pub fn find_roots(
+        &self,
+        variable: &Variable,
+        interval: (f64, f64),
+        options: &RootOptions,
+    ) -> Result<Vec<f64>, EvalError> {
+        let (a, b) = (interval.0.min(interval.1), interval.0.max(interval.1));
+        let difference = match self {
+            Equation::Equals(sides) => Equation::Addition(vec![
+                sides.0.clone(),
+                Equation::Negative(Box::new(sides.1.clone())),
+            ]),
+            equation => equation.clone(),
+        }
+        .simplify_until_complete();
+        let derivative = difference.differentiate(variable).simplify_until_complete();
+
+        let mut values = options.values.clone();
+        values
+            .entry(Variable::Constant(Constant::PI))
+            .or_insert(std::f64::consts::PI);
+        values
+            .entry(Variable::Constant(Constant::E))
+            .or_insert(std::f64::consts::E);
+        values.insert(variable.clone(), (a + b) / 2.);
+        if let Err(error @ (EvalError::UnboundVariables(_) | EvalError::Equals)) =
+            difference.try_calculate(&values)
+        {
+            return Err(error);
+        }
+
+        //Points outside of the domain are NaN, so they never bracket a root
+        let calculate = |equation: &Equation, x: f64| {
+            let mut values = values.clone();
+            values.insert(variable.clone(), x);
+            equation.checked_calculate(&values).unwrap_or(f64::NAN)
+        };
+        let f = |x: f64| calculate(&difference, x);
+        let derivative = |x: f64| calculate(&derivative, x);
+
+        let subdivisions = options.subdivisions.max(1);
+        let points = (0..=subdivisions)
+            .map(|i| {
+                let x = a + (b - a) * i as f64 / subdivisions as f64;
+                (x, f(x))
+            })
+            .collect::<Vec<_>>();
+        let mut roots = vec![];
+        for (i, (x, fx)) in points.iter().enumerate() {
+            if *fx == 0. {
+                roots.push(*x);
+            }
+            if let Some((next, fnext)) = points.get(i + 1) {
+                if fx * fnext < 0. {
+                    let root = brent(&f, (*x, *fx), (*next, *fnext), options);
+                    //A sign change across a pole gets larger instead of smaller near the pole
+                    if f(root).abs() <= fx.abs().min(fnext.abs()) {
+                        roots.push(root);
+                    }
+                }
+            }
+            //A root where the sign does not change is close to a point where the absolute value is smallest
+            let (Some((_, previous)), Some((_, next))) = (
+                i.checked_sub(1).and_then(|i| points.get(i)),
+                points.get(i + 1),
+            ) else {
+                continue;
+            };
+            if fx.abs() < previous.abs()
+                && fx.abs() <= next.abs()
+                && fx * previous > 0.
+                && fx * next > 0.
+            {
+                if let Some(root) = newton(&f, &derivative, *x, options) {
+                    if (a..=b).contains(&root) {
+                        roots.push(root);
+                    }
+                }
+            }
+        }
+
+        roots.sort_by(f64::total_cmp);
+        roots.dedup_by(|x, y| (*x - *y).abs() <= options.tolerance.sqrt() * y.abs().max(1.));
+        Ok(roots)
+    }
+}
+
+///Brent's method on an interval where f changes sign, which uses inverse quadratic interpolation when it converges and bisection otherwise
+fn brent(
+    f: &impl Fn(f64) -> f64,
+    (mut a, mut fa): (f64, f64),
+    (mut b, mut fb): (f64, f64),
+    options: &RootOptions,
+) -> f64 {
+    //The root is always between b and c, b being the best estimate and a the previous one
+    let (mut c, mut fc) = (b, fb);
+    let (mut step, mut previous_step) = (b - a, b - a);
+    for _ in 0..options.max_iterations {
+        if fb.signum() == fc.signum() {
+            (c, fc) = (a, fa);
+            (step, previous_step) = (b - a, b - a);
+        }
+        if fc.abs() < fb.abs() {
+            (a, fa) = (b, fb);
+            (b, fb) = (c, fc);
+            (c, fc) = (a, fa);
+        }
+        let tolerance = 2. * f64::EPSILON * b.abs() + options.tolerance / 2.;
+        let half = (c - b) / 2.;
+        if half.abs() <= tolerance || fb == 0. {
+            return b;
+        }
+        if previous_step.abs() >= tolerance && fa.abs() > fb.abs() {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                //Secant step
+                (2. * half * s, 1. - s)
+            } else {
+                //Inverse quadratic interpolation
+                let (q, r) = (fa / fc, fb / fc);
+                (
+                    s * (2. * half * q * (q - r) - (b - a) * (r - 1.)),
+                    (q - 1.) * (r - 1.) * (s - 1.),
+                )
+            };
+            if p > 0. {
+                q = -q;
+            }
+            p = p.abs();
+            //Only interpolate if the step stays in the interval and decreases fast enough
+            if 2. * p < (3. * half * q - (tolerance * q).abs()).min((previous_step * q).abs()) {
+                previous_step = step;
+                step = p / q;
+            } else {
+                (step, previous_step) = (half, half);
+            }
+        } else {
+            (step, previous_step) = (half, half);
+        }
+        (a, fa) = (b, fb);
+        b += if step.abs() > tolerance {
+            step
+        } else {
+            tolerance.copysign(half)
+        };
+        fb = f(b);
+    }
+    b
+}
+
+///Newton's method from the starting point, None if the steps do not get smaller than the tolerance
+fn newton(
+    f: &impl Fn(f64) -> f64,
+    derivative: &impl Fn(f64) -> f64,
+    mut x: f64,
+    options: &RootOptions,
+) -> Option<f64> {
+    for _ in 0..options.max_iterations {
+        let fx = f(x);
+        if fx == 0. {
+            return Some(x);
+        }
+        let step = fx / derivative(x);
+        if !step.is_finite() {
+            return None;
+        }
+        x -= step;
+        if step.abs() <= options.tolerance * x.abs().max(1.) {
+            return Some(x);
+        }
+    }
+    None
+}
@@ -0,0 +1,133 @@
+use super::{Constant, Equation, LimitDirection, Variable};
+
+///The highest order of a pole that is looked for when the equation is not defined at the point
+const MAX_POLE_ORDER: i64 = 8;
+
+///A truncated Taylor or Laurent series, the sum of coefficient * (x-a)^power plus a term of the order (x-a)^order
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub variable: Variable,
+    pub point: Equation,
+    ///The coefficients of consecutive powers, starting at the lowest power
+    pub coefficients: Vec<Equation>,
+    ///The power of the first coefficient, negative for a Laurent series
+    pub lowest_power: i64,
+    ///The power of the order term, every power below it is included
+    pub order: i64,
+}
+
+impl Equation {
+    ///Expands the equation around the point up to and including the power order, the coefficients are derivatives at the point found with limits, so removable singularities and poles are allowed
+    pub fn series(&self, variable: &Variable, point: &Equation, order: u32) -> Option<Series> {
+        let point = point.clone().simplify_until_complete();
+        if point.contains_variable(variable) {
+            return None;
+        }
+        let offset = displacement(variable, &point);
+
+        //Multiplying by (x-a)^pole removes a pole of that order
+        let (pole, mut derivative) = (0..=MAX_POLE_ORDER).find_map(|pole| {
+            let regular = Equation::Multiplication(vec![power(offset.clone(), pole), self.clone()])
+                .simplify_until_complete();
+            let value = regular.limit(variable, &point, LimitDirection::Right)?;
+            (!is_infinite(&value)).then_some((pole, regular))
+        })?;
+
+        let order = i64::from(order);
+        let mut coefficients = vec![];
+        let mut factorial = 1;
+        for n in 0..=order + pole {
+            if n > 0 {
+                factorial = n.checked_mul(factorial)?;
+                derivative = derivative.differentiate(variable).simplify_until_complete();
+            }
+            let value = derivative.limit(variable, &point, LimitDirection::Both)?;
+            if is_infinite(&value) {
+                return None;
+            }
+            coefficients.push(
+                Equation::Division(Box::new((
+                    value,
+                    Equation::Variable(Variable::Integer(factorial)),
+                )))
+                .simplify_until_complete(),
+            );
+        }
+        Some(Series {
+            variable: variable.clone(),
+            point,
+            coefficients,
+            lowest_power: -pole,
+            order: order + 1,
+        })
+    }
+}
+
+impl Series {
+    ///The truncated series without the order term, in order of increasing power
+    pub fn to_equation(&self) -> Equation {
+        let offset = displacement(&self.variable, &self.point);
+        let terms = self
+            .coefficients
+            .iter()
+            .zip(self.lowest_power..)
+            .filter(|(coefficient, _)| coefficient.calculate_exact() != Some(0.into()))
+            .map(|(coefficient, exponent)| {
+                let power = power(offset.clone(), exponent);
+                match coefficient.calculate_exact() {
+                    _ if exponent == 0 => coefficient.clone(),
+                    Some(one) if one == 1.into() => power,
+                    Some(minus_one) if minus_one == (-1).into() => {
+                        Equation::Negative(Box::new(power))
+                    }
+                    _ => Equation::Multiplication(vec![coefficient.clone(), power]),
+                }
+            })
+            .collect::<Vec<_>>();
+        match terms.len() {
+            0 => Equation::Variable(Variable::Integer(0)),
+            1 => terms.into_iter().next().unwrap(),
+            _ => Equation::Addition(terms),
+        }
+    }
+
+    ///The order term (x-a)^order, which is written as O((x-a)^order)
+    pub fn order_term(&self) -> Equation {
+        power(displacement(&self.variable, &self.point), self.order)
+    }
+}
+
+///x-a, or x if the point is zero
+fn displacement(variable: &Variable, point: &Equation) -> Equation {
+    let x = Equation::Variable(variable.clone());
+    if point.calculate_exact() == Some(0.into()) {
+        x
+    } else {
+        Equation::Addition(vec![x, Equation::Negative(Box::new(point.clone()))])
+    }
+}
+
+///base^exponent, written as a fraction for negative exponents
+fn power(base: Equation, exponent: i64) -> Equation {
+    match exponent {
+        0 => Equation::Variable(Variable::Integer(1)),
+        1 => base,
+        _ if exponent < 0 => Equation::Division(Box::new((
+            Equation::Variable(Variable::Integer(1)),
+            power(base, -exponent),
+        ))),
+        _ => Equation::Power(Box::new((
+            base,
+            Equation::Variable(Variable::Integer(exponent)),
+        ))),
+    }
+}
+
+///Whether the limit is \\infty or -\\infty
+fn is_infinite(limit: &Equation) -> bool {
+    match limit {
+        Equation::Variable(Variable::Constant(Constant::Infinity)) => true,
+        Equation::Negative(negative) => is_infinite(negative),
+        _ => false,
+    }
+}
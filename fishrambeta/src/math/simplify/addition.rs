@@ -1,4 +1,4 @@
-use super::{Equation, Variable};
+use super::{trace, Equation, Variable};
 use num_rational::Rational64;
 use std::collections::BTreeMap;
 
@@ -17,9 +17,16 @@ fn flatten_addition(addition: Vec<Equation>) -> Vec<Equation> {
 
 pub(super) fn simplify_addition(mut addition: Vec<Equation>) -> Equation {
     if addition.len() == 1 {
+        trace::rule("sum of one term");
         return addition.remove(0);
     }
+    //Terms are put in order when nothing else changes
+    trace::rule("sort terms");
+    if addition.iter().any(|term| matches!(term, Equation::Addition(_))) {
+        trace::rule("flatten sums");
+    }
     let addition = flatten_addition(addition);
+    let mut number_terms = 0;
     let mut total_rational_term: Rational64 = 0.into();
     let mut terms: BTreeMap<Equation, Rational64> = BTreeMap::new();
     let mut sin_squares: BTreeMap<Equation, Rational64> = BTreeMap::new();
@@ -27,12 +34,17 @@ pub(super) fn simplify_addition(mut addition: Vec<Equation>) -> Equation {
 
     for equation in addition.into_iter() {
         let (term, count) = match equation.simplify() {
-            Equation::Variable(Variable::Integer(0)) => continue,
+            Equation::Variable(Variable::Integer(0)) => {
+                trace::rule("add numbers");
+                continue;
+            }
             Equation::Variable(Variable::Integer(i)) => {
+                number_terms += 1;
                 total_rational_term += i;
                 continue;
             }
             Equation::Variable(Variable::Rational(r)) => {
+                number_terms += 1;
                 total_rational_term += r;
                 continue;
             }
@@ -51,23 +63,19 @@ pub(super) fn simplify_addition(mut addition: Vec<Equation>) -> Equation {
                     .product();
                 if count == 0.into() {
                     // The multiplication is zero, so we can skip it
+                    trace::rule("add numbers");
                     continue;
                 }
                 if multiplication.len() - number_of_numbers == 0 {
                     // The multiplication is a
                     // constant factor, so we add that factor to the addition
-                    
-                    // I'm sure this is some kind of mistake but I don't understand the code well enough to say what it is
-                    (
-                        Equation::Variable(Variable::Rational(count)),
-                        Equation::Variable(Variable::Integer(1)),
-                    );
+                    trace::rule("add numbers");
+                    total_rational_term += count;
+                    continue;
                 }
                 let term: Vec<Equation> = multiplication
                     .into_iter()
-                    .filter(|x| {
-                        x.get_number_or_none().is_some()
-                    })
+                    .filter(|x| x.get_number_or_none().is_none())
                     .collect();
                 (Equation::Multiplication(term).simplify(), count)
             }
@@ -86,9 +94,15 @@ pub(super) fn simplify_addition(mut addition: Vec<Equation>) -> Equation {
             other => (other, 1.into()),
         };
 
+        if terms.contains_key(&term) {
+            trace::rule("collect like terms");
+        }
         let previous_count = *terms.get(&term).unwrap_or(&0.into());
         terms.insert(term, previous_count + count);
     }
+    if number_terms > 1 {
+        trace::rule("add numbers");
+    }
 
     let mut simplified_addition: Vec<Equation> = Vec::new();
     if total_rational_term != 0.into() {
@@ -114,6 +128,7 @@ pub(super) fn simplify_addition(mut addition: Vec<Equation>) -> Equation {
         sin_count -= number_of_ones;
         cos_count -= number_of_ones;
         if number_of_ones != 0.into() {
+            trace::rule("pythagorean identity");
             simplified_addition
                 .push(Equation::Variable(Variable::Rational(number_of_ones)).simplify());
         }
@@ -1,4 +1,4 @@
-use super::{Equation, Variable};
+use super::{trace, Equation, Variable};
 
 use num_rational::Rational64;
 
@@ -8,6 +8,7 @@ pub(super) fn simplify_division(division: (Equation, Equation)) -> Equation {
 
     match numerator {
         Equation::Division(division) => {
+            trace::rule("nested fraction");
             return Equation::Division(Box::new((
                 division.0,
                 Equation::Multiplication(vec![division.1, denominator]),
@@ -15,6 +16,7 @@ pub(super) fn simplify_division(division: (Equation, Equation)) -> Equation {
             .simplify();
         }
         Equation::Variable(Variable::Rational(rational)) => {
+            trace::rule("clear fractions in fraction");
             return Equation::Division(Box::new((
                 Equation::Variable(Variable::Integer(*rational.numer())),
                 Equation::Multiplication(vec![
@@ -37,6 +39,7 @@ pub(super) fn simplify_division(division: (Equation, Equation)) -> Equation {
                     unreachable!()
                 };
                 multiplication.push(Equation::Variable(Variable::Integer(*rational.numer())));
+                trace::rule("clear fractions in fraction");
                 return Equation::Division(Box::new((
                     Equation::Multiplication(multiplication.clone()),
                     Equation::Multiplication(vec![
@@ -51,6 +54,7 @@ pub(super) fn simplify_division(division: (Equation, Equation)) -> Equation {
     }
     match denominator {
         Equation::Division(division) => {
+            trace::rule("nested fraction");
             return Equation::Division(Box::new((
                 Equation::Multiplication(vec![numerator, division.1]),
                 division.0,
@@ -58,6 +62,7 @@ pub(super) fn simplify_division(division: (Equation, Equation)) -> Equation {
             .simplify();
         }
         Equation::Variable(Variable::Rational(rational)) => {
+            trace::rule("clear fractions in fraction");
             return Equation::Division(Box::new((
                 Equation::Multiplication(vec![
                     numerator,
@@ -67,10 +72,11 @@ pub(super) fn simplify_division(division: (Equation, Equation)) -> Equation {
             )));
         }
         Equation::Variable(Variable::Integer(n)) => {
+            trace::rule("divide by a number");
             return Equation::Multiplication(vec![
                 Equation::Variable(Variable::Rational(Rational64::new(1, n))),
                 numerator,
-            ])
+            ]);
         }
         Equation::Multiplication(ref mut multiplication) => {
             if let Some(index) = multiplication
@@ -85,6 +91,7 @@ pub(super) fn simplify_division(division: (Equation, Equation)) -> Equation {
                     unreachable!()
                 };
                 multiplication.push(Equation::Variable(Variable::Integer(*rational.numer())));
+                trace::rule("clear fractions in fraction");
                 return Equation::Division(Box::new((
                     Equation::Multiplication(vec![
                         numerator,
@@ -99,21 +106,29 @@ pub(super) fn simplify_division(division: (Equation, Equation)) -> Equation {
         _ => {}
     }
 
+    let mut cancelled = false;
     for factor in denominator.shared_factors(&numerator) {
         if numerator.has_factor(&factor) && denominator.has_factor(&factor) {
             numerator = numerator.remove_factor(&factor);
             denominator = denominator.remove_factor(&factor);
+            cancelled = true;
         }
     }
 
     numerator = numerator.simplify();
     denominator = denominator.simplify();
 
-    if numerator == Equation::Variable(Variable::Integer(0)) {
+    let simplified = if numerator == Equation::Variable(Variable::Integer(0)) {
+        trace::rule("zero numerator");
         Equation::Variable(Variable::Integer(0))
     } else if denominator == Equation::Variable(Variable::Integer(1)) {
+        trace::rule("divide by one");
         numerator
     } else {
         Equation::Division(Box::new((numerator, denominator)))
+    };
+    if cancelled {
+        trace::rule("cancel common factors");
     }
+    simplified
 }
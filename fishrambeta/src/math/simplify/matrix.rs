@@ -0,0 +1,109 @@
+use super::{Equation, Variable};
+
+///The highest integer power of a matrix that is multiplied out
+const MAX_MATRIX_POWER: u64 = 16;
+
+///Multiplies neighbouring matrices in order, as matrix multiplication is not commutative, and multiplies the scalars into a single remaining matrix
+pub(super) fn simplify_matrix_product(factors: Vec<Equation>) -> Equation {
+    let mut scalars = vec![];
+    let mut matrices: Vec<Equation> = vec![];
+    for factor in factors.into_iter().map(|factor| factor.simplify()) {
+        if factor.rows().is_none() {
+            scalars.push(factor);
+            continue;
+        }
+        match matrices
+            .last()
+            .and_then(|last| last.matrix_product(&factor))
+        {
+            Some(product) => *matrices.last_mut().unwrap() = product,
+            None => matrices.push(factor),
+        }
+    }
+    match <[Equation; 1]>::try_from(matrices) {
+        Ok([matrix]) if scalars.is_empty() => matrix,
+        Ok([matrix]) => matrix.map_entries(|entry| {
+            let mut product = scalars.clone();
+            product.push(entry.clone());
+            Equation::Multiplication(product)
+        }),
+        Err(mut matrices) => {
+            scalars.append(&mut matrices);
+            Equation::Multiplication(scalars)
+        }
+    }
+}
+
+///Adds matrices of the same dimensions entry by entry
+pub(super) fn simplify_matrix_sum(terms: Vec<Equation>) -> Equation {
+    let mut sum: Vec<Equation> = vec![];
+    for term in terms.into_iter().map(|term| term.simplify()) {
+        let same_dimensions = sum
+            .iter()
+            .position(|other| other.rows().is_some() && other.dimensions() == term.dimensions());
+        match same_dimensions {
+            Some(i) => {
+                let other = sum[i].rows().unwrap().clone();
+                sum[i] = Equation::Matrix(
+                    other
+                        .into_iter()
+                        .zip(term.rows().unwrap())
+                        .map(|(row, term_row)| {
+                            row.into_iter()
+                                .zip(term_row)
+                                .map(|(a, b)| Equation::Addition(vec![a, b.clone()]))
+                                .collect()
+                        })
+                        .collect(),
+                );
+            }
+            None => sum.push(term),
+        }
+    }
+    if sum.len() == 1 {
+        sum.remove(0)
+    } else {
+        Equation::Addition(sum)
+    }
+}
+
+///Takes integer powers of square matrices, with negative powers of the inverse, and transposes a matrix to the power T
+pub(super) fn simplify_matrix_power(matrix: Equation, exponent: Equation) -> Equation {
+    let exponent = exponent.simplify();
+    let power = match &exponent {
+        Equation::Variable(Variable::Letter(letter)) if letter == "T" => matrix.transpose(),
+        Equation::Variable(Variable::Integer(n)) => {
+            let base = if *n < 0 {
+                matrix.inverse()
+            } else {
+                Some(matrix.clone())
+            };
+            base.and_then(|base| repeated_product(base, n.unsigned_abs()))
+        }
+        _ => None,
+    };
+    power.unwrap_or_else(|| Equation::Power(Box::new((matrix.simplify(), exponent))))
+}
+
+///The matrix multiplied by itself n times, None if it is not square or n is too large
+fn repeated_product(matrix: Equation, n: u64) -> Option<Equation> {
+    let (size, columns) = matrix.dimensions()?;
+    if size != columns || n > MAX_MATRIX_POWER {
+        return None;
+    }
+    let mut power = Equation::Matrix(
+        (0..size)
+            .map(|i| {
+                (0..size)
+                    .map(|j| Equation::Variable(Variable::Integer((i == j) as i64)))
+                    .collect()
+            })
+            .collect(),
+    );
+    for _ in 0..n {
+        power = power
+            .matrix_product(&matrix)?
+            .map_entries(|entry| entry.clone().simplify_until_complete());
+    }
+    Some(power)
+}
@@ -1,45 +1,129 @@
-use std::collections::BTreeMap;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashSet};
 
-use crate::math::{Equation, Variable};
+use crate::math::{Equation, Integral, Limit, Variable};
+
+pub use trace::Rewrite;
 
 mod addition;
 mod division;
+mod matrix;
 mod multiplication;
 mod power;
+mod trace;
+mod vector;
+
+///Options for simplify_with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimplifyOptions {
+    ///The maximum number of passes over the whole equation
+    pub max_passes: usize,
+    ///Whether to record every rewrite that was made
+    pub trace: bool,
+    ///Keep products of sums as they are instead of multiplying them out
+    pub keep_factored: bool,
+}
+
+impl Default for SimplifyOptions {
+    fn default() -> Self {
+        SimplifyOptions {
+            max_passes: 32,
+            trace: false,
+            keep_factored: false,
+        }
+    }
+}
+
+thread_local! {
+    ///Whether the simplification that is running keeps products of sums
+    static KEEP_FACTORED: Cell<bool> = const { Cell::new(false) };
+}
+
+///Restores the keep_factored setting of the outer simplification when dropped, also when a rule panics
+struct KeepFactoredGuard(bool);
+
+impl KeepFactoredGuard {
+    fn set(keep_factored: bool) -> Self {
+        KeepFactoredGuard(KEEP_FACTORED.replace(keep_factored))
+    }
+}
+
+impl Drop for KeepFactoredGuard {
+    fn drop(&mut self) {
+        KEEP_FACTORED.set(self.0);
+    }
+}
+
+///The result of simplify_with
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simplification {
+    pub equation: Equation,
+    pub passes: usize,
+    ///False if the passes ran out or started repeating before the equation stopped changing
+    pub converged: bool,
+    ///The rewrites in the order they were made, empty unless the trace option is set
+    pub trace: Vec<Rewrite>,
+}
 
 impl Equation {
-    ///
+    ///Simplifies the equation until it no longer changes. When called by a rule, products of sums
+    ///are kept if the simplification running the rule keeps them
     pub fn simplify_until_complete(self) -> Self {
-        let mut equation = self.clone();
-        let mut previous = equation.to_latex();
-        for _ in 1..5 {
-            equation = equation.simplify();
-            if equation.to_latex() == previous {
-                break;
-            }
-            previous = equation.to_latex();
-        }
-        equation
+        let options = SimplifyOptions {
+            keep_factored: KEEP_FACTORED.get(),
+            ..Default::default()
+        };
+        self.simplify_with(&options).equation
     }
 
+    ///Simplifies the equation until it no longer changes, printing every rewrite
     pub fn simplify_until_complete_with_print(self) -> Self {
-        let mut equation = self.clone();
-        let mut previous = equation.to_latex();
-        for i in 1..5 {
-            equation = equation.simplify();
-            println!("{i}: {equation}");
-            if equation.to_latex() == previous {
-                break;
+        let options = SimplifyOptions {
+            trace: true,
+            ..Default::default()
+        };
+        let simplification = self.simplify_with(&options);
+        for (i, rewrite) in simplification.trace.iter().enumerate() {
+            println!("{}: {}", i + 1, rewrite);
+        }
+        simplification.equation
+    }
+
+    ///Simplifies in passes until the equation is a fixed point, or the budget of passes runs out
+    pub fn simplify_with(self, options: &SimplifyOptions) -> Simplification {
+        let run = || {
+            let mut seen = HashSet::new();
+            let mut equation = self;
+            for pass in 1..=options.max_passes {
+                let simplified = equation.clone().simplify();
+                if simplified == equation {
+                    return (equation, pass, true);
+                }
+                seen.insert(equation);
+                if seen.contains(&simplified) {
+                    return (simplified, pass, false);
+                }
+                equation = simplified;
             }
-            previous = equation.to_latex();
+            (equation, options.max_passes, false)
+        };
+        let _keep_factored = KeepFactoredGuard::set(options.keep_factored);
+        let ((equation, passes, converged), trace) = trace::with_trace(options.trace, run);
+        Simplification {
+            equation,
+            passes,
+            converged,
+            trace,
         }
-        equation
     }
 
     pub(super) fn simplify(self) -> Self {
-        let calculated_wrapped = self.calculate_exact();
-        if calculated_wrapped.is_some() {
-            let calculated = calculated_wrapped.unwrap();
+        trace::record(self, Self::simplify_once)
+    }
+
+    fn simplify_once(self) -> Self {
+        if let Some(calculated) = self.calculate_exact() {
+            trace::rule("calculate exactly");
             if calculated.is_integer() {
                 return Equation::Variable(Variable::Integer(calculated.to_integer()));
             }
@@ -48,6 +132,7 @@ impl Equation {
         match self {
             Equation::Variable(variable) => match variable {
                 Variable::Rational(r) => {
+                    trace::rule("normalize number");
                     if r.is_integer() {
                         Equation::Variable(Variable::Integer(r.to_integer()))
                     } else {
@@ -57,36 +142,156 @@ impl Equation {
                 variable => Equation::Variable(variable),
             },
             Equation::Negative(negative) => match *negative {
-                Equation::Negative(negative) => (*negative).simplify(),
+                Equation::Negative(negative) => {
+                    trace::rule("double negation");
+                    (*negative).simplify()
+                }
                 Equation::Variable(Variable::Integer(0)) => {
+                    trace::rule("negate number");
                     Equation::Variable(Variable::Integer(0))
                 }
                 Equation::Variable(Variable::Integer(integer)) => {
+                    trace::rule("negate number");
                     Equation::Variable(Variable::Integer(-integer))
                 }
                 Equation::Variable(Variable::Rational(rational)) => {
+                    trace::rule("negate number");
                     Equation::Variable(Variable::Rational(-rational))
                 }
+                Equation::Matrix(rows) => {
+                    trace::rule("matrix arithmetic");
+                    Equation::Matrix(rows)
+                        .map_entries(|entry| Equation::Negative(Box::new(entry.clone())))
+                }
 
                 negative => Equation::Negative(Box::new(negative.simplify())),
             },
+            Equation::Addition(addition) if addition.iter().any(is_matrix) => {
+                trace::rule("matrix arithmetic");
+                matrix::simplify_matrix_sum(addition)
+            }
             Equation::Addition(addition) => addition::simplify_addition(addition),
+            Equation::Multiplication(multiplication) if multiplication.iter().any(is_matrix) => {
+                trace::rule("matrix arithmetic");
+                matrix::simplify_matrix_product(multiplication)
+            }
             Equation::Multiplication(multiplication) => {
                 multiplication::simplify_multiplication(multiplication)
             }
+            Equation::Division(division) if is_matrix(&division.0) => {
+                trace::rule("matrix arithmetic");
+                let denominator = division.1.simplify();
+                division.0.map_entries(|entry| {
+                    Equation::Division(Box::new((entry.clone(), denominator.clone())))
+                })
+            }
             Equation::Division(division) => division::simplify_division(*division),
+            Equation::Power(power) if is_matrix(&power.0) => {
+                trace::rule("matrix arithmetic");
+                matrix::simplify_matrix_power(power.0, power.1)
+            }
             Equation::Power(power) => power::simplify_power(*power),
-            Equation::Ln(ln) => Equation::Ln(Box::new(ln.simplify())),
-            Equation::Sin(sin) => Equation::Sin(Box::new(sin.simplify())),
-            Equation::Cos(cos) => Equation::Cos(Box::new(cos.simplify())),
+            Equation::Ln(ln) => match ln.simplify() {
+                Equation::Exp(exp) => {
+                    trace::rule("inverse functions");
+                    *exp
+                }
+                ln => Equation::Ln(Box::new(ln)),
+            },
+            Equation::Exp(exp) => match exp.simplify() {
+                Equation::Ln(ln) => {
+                    trace::rule("inverse functions");
+                    *ln
+                }
+                exp => Equation::Exp(Box::new(exp)),
+            },
+            Equation::Sin(sin) => match sin.simplify() {
+                Equation::Arcsin(arcsin) => {
+                    trace::rule("inverse functions");
+                    *arcsin
+                }
+                sin => Equation::Sin(Box::new(sin)),
+            },
+            Equation::Cos(cos) => match cos.simplify() {
+                Equation::Arccos(arccos) => {
+                    trace::rule("inverse functions");
+                    *arccos
+                }
+                cos => Equation::Cos(Box::new(cos)),
+            },
+            Equation::Tan(tan) => match tan.simplify() {
+                Equation::Arctan(arctan) => {
+                    trace::rule("inverse functions");
+                    *arctan
+                }
+                tan => Equation::Tan(Box::new(tan)),
+            },
             Equation::Abs(abs) => Equation::Abs(Box::new(abs.simplify())),
+            Equation::Arcsin(arcsin) => Equation::Arcsin(Box::new(arcsin.simplify())),
+            Equation::Arccos(arccos) => Equation::Arccos(Box::new(arccos.simplify())),
+            Equation::Arctan(arctan) => Equation::Arctan(Box::new(arctan.simplify())),
+            Equation::Sinh(sinh) => Equation::Sinh(Box::new(sinh.simplify())),
+            Equation::Cosh(cosh) => match cosh.simplify() {
+                Equation::Negative(negative) => {
+                    trace::rule("cosh is even");
+                    Equation::Cosh(negative)
+                }
+                cosh => Equation::Cosh(Box::new(cosh)),
+            },
+            Equation::Tanh(tanh) => Equation::Tanh(Box::new(tanh.simplify())),
+            Equation::Dot(dot) => {
+                trace::rule("vector products");
+                vector::simplify_dot(*dot)
+            }
+            Equation::Cross(cross) => {
+                trace::rule("vector products");
+                vector::simplify_cross(*cross)
+            }
+            Equation::Matrix(rows) => {
+                Equation::Matrix(rows).map_entries(|entry| entry.clone().simplify())
+            }
             Equation::Equals(equation) => {
                 Equation::Equals(Box::new((equation.0.simplify(), equation.1.simplify())))
             }
+            Equation::Integral(integral) => {
+                let integral = Integral {
+                    integrand: integral.integrand.simplify(),
+                    variable: integral.variable,
+                    bounds: integral
+                        .bounds
+                        .map(|(lower, upper)| (lower.simplify(), upper.simplify())),
+                };
+                trace::rule("evaluate integral");
+                match integral.bounds {
+                    Some(_) => integral.evaluate_symbolic(),
+                    None => None,
+                }
+                .unwrap_or_else(|| Equation::Integral(Box::new(integral)))
+            }
+            Equation::Limit(limit) => {
+                let limit = Limit {
+                    expression: limit.expression.simplify(),
+                    variable: limit.variable,
+                    point: limit.point.simplify(),
+                    direction: limit.direction,
+                };
+                trace::rule("evaluate limit");
+                limit
+                    .evaluate()
+                    .unwrap_or_else(|| Equation::Limit(Box::new(limit)))
+            }
+            Equation::Derivative(derivative) => {
+                trace::rule("evaluate derivative");
+                derivative.evaluate()
+            }
         }
     }
 }
 
+fn is_matrix(equation: &Equation) -> bool {
+    matches!(equation, Equation::Matrix(_))
+}
+
 pub struct EquationBTreeMap(BTreeMap<Equation, Vec<Equation>>);
 
 impl EquationBTreeMap {
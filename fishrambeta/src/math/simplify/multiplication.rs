@@ -1,4 +1,4 @@
-use super::{Equation, EquationBTreeMap, Variable};
+use super::{trace, Equation, EquationBTreeMap, Variable};
 use num_rational::Rational64;
 
 
@@ -26,7 +26,13 @@ fn distribute_terms(multiplication: Vec<Equation>, addition: Vec<Equation>) -> E
 }
 
 pub(super) fn simplify_multiplication(multiplication: Vec<Equation>) -> Equation {
+    //Factors are put in order when nothing else changes
+    trace::rule("sort factors");
+    if multiplication.iter().any(|factor| matches!(factor, Equation::Multiplication(_))) {
+        trace::rule("flatten products");
+    }
     let mut multiplication = flatten_multiplication(multiplication);
+    let mut number_factors = 0;
     let mut terms: EquationBTreeMap = EquationBTreeMap::new();
     let mut total_rational_factor: Rational64 = 1.into();
 
@@ -34,25 +40,31 @@ pub(super) fn simplify_multiplication(multiplication: Vec<Equation>) -> Equation
     for (index, equation) in multiplication.iter().enumerate() {
         let (term, count) = match equation.clone().simplify() {
             Equation::Variable(Variable::Integer(0)) => {
+                trace::rule("multiply by zero");
                 return Equation::Variable(Variable::Integer(0));
             }
             Equation::Variable(Variable::Integer(1)) => {
+                trace::rule("multiply by one");
                 continue;
             }
             Equation::Variable(Variable::Integer(n)) => {
+                number_factors += 1;
                 total_rational_factor *= n;
                 continue;
             }
             Equation::Variable(Variable::Rational(r)) => {
+                number_factors += 1;
                 total_rational_factor *= r;
                 continue;
             }
             Equation::Negative(negative) => {
+                trace::rule("collect signs");
                 total_is_negative = !total_is_negative;
                 (*negative, Equation::Variable(Variable::Integer(1)))
             }
             Equation::Power(power) => {
                 if let Equation::Division(division) = power.0 {
+                    trace::rule("power of a fraction");
                     terms.insert_or_push(division.0, power.1.clone());
                     terms.insert_or_push(division.1, Equation::Negative(Box::new(power.1)));
                     continue;
@@ -61,6 +73,7 @@ pub(super) fn simplify_multiplication(multiplication: Vec<Equation>) -> Equation
                 }
             }
             Equation::Division(division) => {
+                trace::rule("multiply fractions");
                 multiplication.remove(index);
                 multiplication.push(division.0);
                 return Equation::Division(Box::new((
@@ -68,14 +81,21 @@ pub(super) fn simplify_multiplication(multiplication: Vec<Equation>) -> Equation
                     division.1,
                 )));
             }
-            Equation::Addition(addition) => {
+            Equation::Addition(addition) if !super::KEEP_FACTORED.get() => {
+                trace::rule("distribute");
                 multiplication.remove(index);
                 return distribute_terms(multiplication, addition);
             }
             term => (term, Equation::Variable(Variable::Integer(1))),
         };
+        if terms.0.contains_key(&term) {
+            trace::rule("collect powers");
+        }
         terms.insert_or_push(term, count)
     }
+    if number_factors > 1 {
+        trace::rule("multiply numbers");
+    }
 
     let mut simplified_multiplication: Vec<Equation> = Vec::new();
 
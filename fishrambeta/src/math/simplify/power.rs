@@ -1,4 +1,5 @@
-use super::{Equation, Variable};
+use super::{trace, Equation, Variable};
+use crate::math::Constant;
 use num::Signed;
 
 
@@ -9,14 +10,25 @@ pub(super) fn simplify_power(power: (Equation, Equation)) -> Equation {
 
     if let Some(n) = exponent.get_number_or_none() {
         if n == 1.into() {
+            trace::rule("power of one");
             return base;
         } else if n == 0.into() {
+            trace::rule("power of zero");
             return Equation::Variable(Variable::Integer(1));
         }
     }
 
     match base {
+        Equation::Variable(Variable::Constant(Constant::E)) => {
+            trace::rule("exponential");
+            return Equation::Exp(Box::new(exponent));
+        }
+        Equation::Exp(exp) => {
+            trace::rule("power of a power");
+            return Equation::Exp(Box::new(Equation::Multiplication(vec![*exp, exponent])));
+        }
         Equation::Multiplication(terms) => {
+            trace::rule("power of a product");
             let mut simplified_power: Vec<Equation> = vec![];
             for term in terms.into_iter() {
                 simplified_power.push(Equation::Power(Box::new((term, exponent.clone()))));
@@ -26,6 +38,7 @@ pub(super) fn simplify_power(power: (Equation, Equation)) -> Equation {
         Equation::Power(ref power) => {
             if let Some(e1) = exponent.get_number_or_none() {
                 if let Some(e2) = power.1.get_number_or_none() {
+                    trace::rule("power of a power");
                     return Equation::Power(Box::new((
                         power.0.clone(),
                         Equation::Variable(Variable::Rational(e1 * e2)),
@@ -35,6 +48,7 @@ pub(super) fn simplify_power(power: (Equation, Equation)) -> Equation {
         }
         Equation::Division(ref division) => {
             if let Some(number) = division.0.get_number_or_none() {
+                trace::rule("power of a fraction");
                 return Equation::Division(Box::new((
                     Equation::Power(Box::new((
                         Equation::Variable(Variable::Rational(number)),
@@ -45,6 +59,7 @@ pub(super) fn simplify_power(power: (Equation, Equation)) -> Equation {
             }
             if let Some(n) = exponent.get_number_or_none() {
                 if n < 0.into() {
+                    trace::rule("negative exponent");
                     return Equation::Power(Box::new((
                         Equation::Division(Box::new((division.1.clone(), division.0.clone()))),
                         Equation::Variable(Variable::Rational(-n)),
@@ -52,6 +67,7 @@ pub(super) fn simplify_power(power: (Equation, Equation)) -> Equation {
                 }
             }
             if let Equation::Negative(ref negative) = exponent {
+                trace::rule("negative exponent");
                 return Equation::Power(Box::new((
                     Equation::Division(Box::new((division.1.clone(), division.0.clone()))),
                     *negative.clone(),
@@ -62,22 +78,24 @@ pub(super) fn simplify_power(power: (Equation, Equation)) -> Equation {
     }
 
     if let Equation::Negative(new_exponent) = exponent {
+        trace::rule("negative exponent");
         return Equation::Division(Box::new((
             Equation::Variable(Variable::Integer(1)),
             Equation::Power(Box::new((base, *new_exponent))),
         )));
     }
 
-    if let Some(n) = exponent.get_number_or_none()
-        && n < 0.into()
-    {
-        return Equation::Division(Box::new((
-            Equation::Variable(Variable::Integer(1)),
-            Equation::Power(Box::new((
-                base,
-                Equation::Variable(Variable::Rational(n.abs())).simplify(),
-            ))),
-        )));
+    if let Some(n) = exponent.get_number_or_none() {
+        if n < 0.into() {
+            trace::rule("negative exponent");
+            return Equation::Division(Box::new((
+                Equation::Variable(Variable::Integer(1)),
+                Equation::Power(Box::new((
+                    base,
+                    Equation::Variable(Variable::Rational(n.abs())).simplify(),
+                ))),
+            )));
+        }
     }
 
     Equation::Power(Box::new((base, exponent)))
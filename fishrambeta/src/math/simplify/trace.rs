@@ -0,0 +1,101 @@
+use super::Equation;
+use std::cell::RefCell;
+use std::fmt;
+
+///A single rewrite made by the simplifier, before is the subexpression as it was when the rule was applied
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rewrite {
+    pub rule: &'static str,
+    pub before: Equation,
+    pub after: Equation,
+}
+
+impl fmt::Display for Rewrite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} = {}", self.rule, self.before, self.after)
+    }
+}
+
+///A subexpression that is being simplified
+#[derive(Default)]
+struct Frame {
+    ///The rule that rewrote the subexpression itself, set by the rule when it applies
+    rule: Option<&'static str>,
+    ///The subexpressions that were simplified while simplifying this one, with their rewrites
+    simplified: Vec<(Equation, Equation, Vec<Rewrite>)>,
+}
+
+thread_local! {
+    ///The subexpressions that are being simplified, innermost last, None when rewrites are not being recorded
+    static TRACE: RefCell<Option<Vec<Frame>>> = const { RefCell::new(None) };
+}
+
+///Runs f with its own trace, which is empty if recording is disabled, so nested simplifications do not end up in the trace of the caller
+pub(super) fn with_trace<T>(enabled: bool, f: impl FnOnce() -> T) -> (T, Vec<Rewrite>) {
+    let outer = TRACE.replace(enabled.then(|| vec![Frame::default()]));
+    let result = f();
+    let trace = TRACE
+        .replace(outer)
+        .and_then(|mut frames| frames.pop())
+        .map(|frame| {
+            frame
+                .simplified
+                .into_iter()
+                .flat_map(|(_, _, rewrites)| rewrites)
+                .collect()
+        })
+        .unwrap_or_default();
+    (result, trace)
+}
+
+///Names the rule that is rewriting the subexpression being simplified, if a trace is being recorded
+pub(super) fn rule(name: &'static str) {
+    TRACE.with_borrow_mut(|frames| {
+        if let Some(frame) = frames.as_mut().and_then(|frames| frames.last_mut()) {
+            frame.rule = Some(name);
+        }
+    });
+}
+
+///Simplifies the equation, recording the rewrite if a trace is being recorded
+pub(super) fn record(equation: Equation, simplify: impl FnOnce(Equation) -> Equation) -> Equation {
+    let recording = TRACE.with_borrow_mut(|frames| match frames.as_mut() {
+        Some(frames) => {
+            frames.push(Frame::default());
+            true
+        }
+        None => false,
+    });
+    if !recording {
+        return simplify(equation);
+    }
+    let input = equation.clone();
+    let after = simplify(equation);
+    TRACE.with_borrow_mut(|frames| {
+        let Some(frames) = frames.as_mut() else {
+            return;
+        };
+        let frame = frames.pop().unwrap_or_default();
+        //Rules also simplify intermediate expressions they build, only the rewrites of subexpressions that occur in the input are kept
+        let mut before = input.clone();
+        let mut rewrites = vec![];
+        for (simplified_before, simplified_after, mut simplified_rewrites) in frame.simplified {
+            let substituted = before.substitute(&simplified_before, &simplified_after);
+            if substituted != before {
+                before = substituted;
+                rewrites.append(&mut simplified_rewrites);
+            }
+        }
+        if before != after {
+            rewrites.push(Rewrite {
+                rule: frame.rule.unwrap_or("simplify"),
+                before,
+                after: after.clone(),
+            });
+        }
+        if let Some(parent) = frames.last_mut().filter(|_| input != after) {
+            parent.simplified.push((input, after.clone(), rewrites));
+        }
+    });
+    after
+}
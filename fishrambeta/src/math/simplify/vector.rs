@@ -0,0 +1,65 @@
+use super::{Equation, Variable};
+
+///Pulls scalar factors out of the dot product, a·a becomes |a|^2 and the sides are sorted as the dot product is commutative
+pub(super) fn simplify_dot(dot: (Equation, Equation)) -> Equation {
+    let (mut scalars, left) = split_scalars(dot.0.simplify());
+    let (mut right_scalars, right) = split_scalars(dot.1.simplify());
+    scalars.append(&mut right_scalars);
+
+    let product = if is_zero(&left) || is_zero(&right) {
+        return Equation::Variable(Variable::Integer(0));
+    } else if left == right {
+        Equation::Power(Box::new((
+            Equation::Abs(Box::new(left)),
+            Equation::Variable(Variable::Integer(2)),
+        )))
+    } else {
+        Equation::Dot(Box::new((left.clone().min(right.clone()), left.max(right))))
+    };
+    with_scalars(scalars, product)
+}
+
+///Pulls scalar factors out of the cross product, the cross product of a vector with itself is zero
+pub(super) fn simplify_cross(cross: (Equation, Equation)) -> Equation {
+    let (mut scalars, left) = split_scalars(cross.0.simplify());
+    let (mut right_scalars, right) = split_scalars(cross.1.simplify());
+    scalars.append(&mut right_scalars);
+
+    if is_zero(&left) || is_zero(&right) || left == right {
+        return Equation::Variable(Variable::Integer(0));
+    }
+    with_scalars(scalars, Equation::Cross(Box::new((left, right))))
+}
+
+///Splits a vector into its scalar factors and the vector that remains
+fn split_scalars(equation: Equation) -> (Vec<Equation>, Equation) {
+    let is_vector = equation.is_vector();
+    match equation {
+        Equation::Negative(negative) => {
+            let (mut scalars, vector) = split_scalars(*negative);
+            scalars.push(Equation::Variable(Variable::Integer(-1)));
+            (scalars, vector)
+        }
+        Equation::Multiplication(factors) if is_vector => {
+            let (vectors, mut scalars): (Vec<_>, Vec<_>) =
+                factors.into_iter().partition(|factor| factor.is_vector());
+            let (mut inner_scalars, vector) = split_scalars(vectors.into_iter().next().unwrap());
+            scalars.append(&mut inner_scalars);
+            (scalars, vector)
+        }
+        _ => (vec![], equation),
+    }
+}
+
+fn with_scalars(mut scalars: Vec<Equation>, product: Equation) -> Equation {
+    if scalars.is_empty() {
+        product
+    } else {
+        scalars.push(product);
+        Equation::Multiplication(scalars)
+    }
+}
+
+fn is_zero(equation: &Equation) -> bool {
+    *equation == Equation::Variable(Variable::Integer(0))
+}
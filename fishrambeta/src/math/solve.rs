@@ -0,0 +1,440 @@
+use super::polynomial::number;
+use crate::math::{Equation, EvalError, Polynomial, Variable};
+use num::integer::Roots;
+use num::{Integer, Zero};
+use num_rational::Rational64;
+use std::collections::BTreeMap;
+
+impl Equation {
+    ///Solves the equation for the given variable, returning every solution in the form `variable = solution`.
+    ///An equation without `=` is solved as if it equals zero. Inverse functions only yield the principal branch, and solutions outside the domain of the equation are left out.
+    ///Polynomials with rational coefficients are factored first, factors of degree above two are only solved if they are of the form a*x^n + c.
+    pub fn solve(&self, solve_for: &Variable) -> Vec<Equation> {
+        let (lhs, rhs) = match self {
+            Equation::Equals(equals) => (equals.0.clone(), equals.1.clone()),
+            equation => (equation.clone(), Equation::Variable(Variable::Integer(0))),
+        };
+        let difference =
+            Equation::Addition(vec![lhs.clone(), Equation::Negative(Box::new(rhs.clone()))])
+                .simplify_until_complete();
+
+        let solutions = match Polynomial::from_equation(&difference, solve_for) {
+            Some(polynomial) => solve_factored(&polynomial),
+            None => match difference.polynomial_coefficients(solve_for) {
+                Some(coefficients) => solve_polynomial(coefficients),
+                None => None,
+            },
+        };
+        let solutions = match solutions {
+            Some(solutions) => solutions,
+            None => {
+                if lhs.contains_variable(solve_for) && !rhs.contains_variable(solve_for) {
+                    isolate(lhs, rhs, solve_for)
+                } else if rhs.contains_variable(solve_for) && !lhs.contains_variable(solve_for) {
+                    isolate(rhs, lhs, solve_for)
+                } else {
+                    isolate(
+                        difference,
+                        Equation::Variable(Variable::Integer(0)),
+                        solve_for,
+                    )
+                }
+            }
+        };
+
+        let mut unique_solutions: Vec<Equation> = vec![];
+        for solution in solutions {
+            let solution = solution.simplify_until_complete();
+            if !is_in_domain(&solution) {
+                continue;
+            }
+            if !unique_solutions.contains(&solution) {
+                unique_solutions.push(solution);
+            }
+        }
+        unique_solutions
+            .into_iter()
+            .map(|solution| {
+                Equation::Equals(Box::new((Equation::Variable(solve_for.clone()), solution)))
+            })
+            .collect()
+    }
+
+    ///Returns the coefficients of the equation as a polynomial in the variable, indexed by degree
+    fn polynomial_coefficients(&self, variable: &Variable) -> Option<BTreeMap<i64, Equation>> {
+        let mut coefficients: BTreeMap<i64, Vec<Equation>> = BTreeMap::new();
+        let terms = match self {
+            Equation::Addition(addition) => addition.clone(),
+            equation => vec![equation.clone()],
+        };
+        for term in terms {
+            let (degree, coefficient) = term.monomial(variable)?;
+            coefficients.entry(degree).or_default().push(coefficient);
+        }
+        Some(
+            coefficients
+                .into_iter()
+                .map(|(degree, coefficient)| {
+                    (
+                        degree,
+                        Equation::Addition(coefficient).simplify_until_complete(),
+                    )
+                })
+                .filter(|(_, coefficient)| *coefficient != Equation::Variable(Variable::Integer(0)))
+                .collect(),
+        )
+    }
+
+    ///Splits a term into its degree in the variable and the coefficient in front of it
+    fn monomial(&self, variable: &Variable) -> Option<(i64, Equation)> {
+        if !self.contains_variable(variable) {
+            return Some((0, self.clone()));
+        }
+        match self {
+            Equation::Variable(v) if v == variable => {
+                Some((1, Equation::Variable(Variable::Integer(1))))
+            }
+            Equation::Power(power) if power.0 == Equation::Variable(variable.clone()) => {
+                match power.1.get_integer_or_none() {
+                    Some(n) if n >= 0 => Some((n, Equation::Variable(Variable::Integer(1)))),
+                    _ => None,
+                }
+            }
+            Equation::Negative(negative) => {
+                let (degree, coefficient) = negative.monomial(variable)?;
+                Some((degree, Equation::Negative(Box::new(coefficient))))
+            }
+            Equation::Multiplication(multiplication) => {
+                let mut degree = 0;
+                let mut coefficient = vec![];
+                for factor in multiplication {
+                    let (factor_degree, factor_coefficient) = factor.monomial(variable)?;
+                    degree += factor_degree;
+                    coefficient.push(factor_coefficient);
+                }
+                Some((degree, Equation::Multiplication(coefficient)))
+            }
+            Equation::Division(division) if !division.1.contains_variable(variable) => {
+                let (degree, coefficient) = division.0.monomial(variable)?;
+                Some((
+                    degree,
+                    Equation::Division(Box::new((coefficient, division.1.clone()))),
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+///Solves every factor of the polynomial that can be solved exactly, None if it cannot be factored
+fn solve_factored(polynomial: &Polynomial) -> Option<Vec<Equation>> {
+    let (_, factors) = polynomial.factor()?;
+    Some(
+        factors
+            .into_iter()
+            .filter_map(|(factor, _)| {
+                let coefficients = factor
+                    .coefficients()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, coefficient)| !coefficient.is_zero())
+                    .map(|(degree, coefficient)| (degree as i64, number(*coefficient)))
+                    .collect();
+                solve_polynomial(coefficients)
+            })
+            .flatten()
+            .collect(),
+    )
+}
+
+///Solves a polynomial given by its coefficients, returns None if the degree is too high to solve exactly
+fn solve_polynomial(coefficients: BTreeMap<i64, Equation>) -> Option<Vec<Equation>> {
+    let zero = Equation::Variable(Variable::Integer(0));
+    let coefficient = |degree: i64| coefficients.get(&degree).cloned().unwrap_or(zero.clone());
+    let degree = *coefficients.keys().max().unwrap_or(&0);
+    match degree {
+        0 => Some(vec![]),
+        1 => Some(vec![Equation::Division(Box::new((
+            Equation::Negative(Box::new(coefficient(0))),
+            coefficient(1),
+        )))]),
+        2 => Some(solve_quadratic(
+            coefficient(2),
+            coefficient(1),
+            coefficient(0),
+        )),
+        n if coefficients
+            .keys()
+            .all(|degree| *degree == 0 || *degree == n) =>
+        {
+            // a*x^n + c = 0, so x^n = -c/a
+            let root = real_root(
+                Equation::Negative(Box::new(Equation::Division(Box::new((
+                    coefficient(0),
+                    coefficient(n),
+                ))))),
+                n,
+            );
+            if n.is_even() {
+                Some(vec![root.clone(), Equation::Negative(Box::new(root))])
+            } else {
+                Some(vec![root])
+            }
+        }
+        _ => None,
+    }
+}
+
+///Solves a*x^2 + b*x + c = 0 using the discriminant, only real solutions are returned
+fn solve_quadratic(a: Equation, b: Equation, c: Equation) -> Vec<Equation> {
+    let discriminant = Equation::Addition(vec![
+        Equation::Power(Box::new((
+            b.clone(),
+            Equation::Variable(Variable::Integer(2)),
+        ))),
+        Equation::Negative(Box::new(Equation::Multiplication(vec![
+            Equation::Variable(Variable::Integer(4)),
+            a.clone(),
+            c,
+        ]))),
+    ])
+    .simplify_until_complete();
+    let square_root = match discriminant.calculate_exact() {
+        Some(d) if d < 0.into() => return vec![],
+        Some(d) => match exact_square_root(d) {
+            Some(root) => Equation::Variable(Variable::Rational(root)),
+            None => square_root(discriminant),
+        },
+        None => square_root(discriminant),
+    };
+    let denominator = Equation::Multiplication(vec![Equation::Variable(Variable::Integer(2)), a]);
+    [
+        square_root.clone(),
+        Equation::Negative(Box::new(square_root)),
+    ]
+    .into_iter()
+    .map(|square_root| {
+        Equation::Division(Box::new((
+            Equation::Addition(vec![Equation::Negative(Box::new(b.clone())), square_root]),
+            denominator.clone(),
+        )))
+    })
+    .collect()
+}
+
+///Isolates the single occurrence of the variable in lhs, undoing one operation at a time
+fn isolate(lhs: Equation, rhs: Equation, variable: &Variable) -> Vec<Equation> {
+    match lhs {
+        Equation::Variable(v) if v == *variable => vec![rhs],
+        Equation::Negative(negative) => {
+            isolate(*negative, Equation::Negative(Box::new(rhs)), variable)
+        }
+        Equation::Addition(addition) => {
+            let (with_variable, without_variable): (Vec<_>, Vec<_>) = addition
+                .into_iter()
+                .partition(|term| term.contains_variable(variable));
+            if with_variable.len() != 1 {
+                return vec![];
+            }
+            let mut new_rhs = vec![rhs];
+            new_rhs.extend(
+                without_variable
+                    .into_iter()
+                    .map(|term| Equation::Negative(Box::new(term))),
+            );
+            isolate(
+                with_variable.into_iter().next().unwrap(),
+                Equation::Addition(new_rhs),
+                variable,
+            )
+        }
+        Equation::Multiplication(multiplication) => {
+            let (with_variable, without_variable): (Vec<_>, Vec<_>) = multiplication
+                .into_iter()
+                .partition(|factor| factor.contains_variable(variable));
+            if with_variable.len() != 1 {
+                return vec![];
+            }
+            isolate(
+                with_variable.into_iter().next().unwrap(),
+                Equation::Division(Box::new((rhs, Equation::Multiplication(without_variable)))),
+                variable,
+            )
+        }
+        Equation::Division(division) => {
+            let (numerator, denominator) = *division;
+            match (
+                numerator.contains_variable(variable),
+                denominator.contains_variable(variable),
+            ) {
+                (true, false) => isolate(
+                    numerator,
+                    Equation::Multiplication(vec![rhs, denominator]),
+                    variable,
+                ),
+                (false, true) => isolate(
+                    denominator,
+                    Equation::Division(Box::new((numerator, rhs))),
+                    variable,
+                ),
+                _ => vec![],
+            }
+        }
+        Equation::Power(power) => {
+            let (base, exponent) = *power;
+            match (
+                base.contains_variable(variable),
+                exponent.contains_variable(variable),
+            ) {
+                (true, false) => {
+                    let roots = match exponent.get_integer_or_none() {
+                        Some(n) if n.is_even() => {
+                            let root = real_root(rhs, n);
+                            vec![root.clone(), Equation::Negative(Box::new(root))]
+                        }
+                        Some(n) if n != 0 => vec![real_root(rhs, n)],
+                        _ => vec![Equation::Power(Box::new((
+                            rhs,
+                            Equation::Division(Box::new((
+                                Equation::Variable(Variable::Integer(1)),
+                                exponent.clone(),
+                            ))),
+                        )))],
+                    };
+                    roots
+                        .into_iter()
+                        .flat_map(|root| isolate(base.clone(), root, variable))
+                        .collect()
+                }
+                (false, true) => isolate(
+                    exponent,
+                    Equation::Division(Box::new((
+                        Equation::Ln(Box::new(rhs)),
+                        Equation::Ln(Box::new(base)),
+                    ))),
+                    variable,
+                ),
+                _ => vec![],
+            }
+        }
+        Equation::Ln(ln) => isolate(*ln, Equation::Exp(Box::new(rhs)), variable),
+        Equation::Exp(exp) => isolate(*exp, Equation::Ln(Box::new(rhs)), variable),
+        Equation::Sin(sin) => isolate(*sin, Equation::Arcsin(Box::new(rhs)), variable),
+        Equation::Cos(cos) => isolate(*cos, Equation::Arccos(Box::new(rhs)), variable),
+        //The inverse functions only reach their principal range
+        Equation::Arcsin(_) | Equation::Arctan(_)
+            if value(&rhs).is_some_and(|rhs| rhs.abs() > std::f64::consts::FRAC_PI_2) =>
+        {
+            vec![]
+        }
+        Equation::Arccos(_)
+            if value(&rhs).is_some_and(|rhs| !(0. ..=std::f64::consts::PI).contains(&rhs)) =>
+        {
+            vec![]
+        }
+        Equation::Arcsin(arcsin) => isolate(*arcsin, Equation::Sin(Box::new(rhs)), variable),
+        Equation::Arccos(arccos) => isolate(*arccos, Equation::Cos(Box::new(rhs)), variable),
+        Equation::Tan(tan) => isolate(*tan, Equation::Arctan(Box::new(rhs)), variable),
+        Equation::Arctan(arctan) => isolate(*arctan, Equation::Tan(Box::new(rhs)), variable),
+        Equation::Sinh(sinh) => {
+            // arsinh(y) = ln(y + sqrt(y^2 + 1))
+            let inverse = Equation::Ln(Box::new(Equation::Addition(vec![
+                rhs.clone(),
+                square_root(Equation::Addition(vec![
+                    Equation::Power(Box::new((rhs, Equation::Variable(Variable::Integer(2))))),
+                    Equation::Variable(Variable::Integer(1)),
+                ])),
+            ])));
+            isolate(*sinh, inverse, variable)
+        }
+        Equation::Cosh(cosh) => {
+            // arcosh(y) = ln(y + sqrt(y^2 - 1)), cosh is even so both signs are solutions
+            let inverse = Equation::Ln(Box::new(Equation::Addition(vec![
+                rhs.clone(),
+                square_root(Equation::Addition(vec![
+                    Equation::Power(Box::new((rhs, Equation::Variable(Variable::Integer(2))))),
+                    Equation::Variable(Variable::Integer(-1)),
+                ])),
+            ])));
+            let mut solutions = isolate((*cosh).clone(), inverse.clone(), variable);
+            solutions.append(&mut isolate(
+                *cosh,
+                Equation::Negative(Box::new(inverse)),
+                variable,
+            ));
+            solutions
+        }
+        Equation::Tanh(tanh) => {
+            // artanh(y) = ln((1 + y) / (1 - y)) / 2
+            let inverse = Equation::Division(Box::new((
+                Equation::Ln(Box::new(Equation::Division(Box::new((
+                    Equation::Addition(vec![Equation::Variable(Variable::Integer(1)), rhs.clone()]),
+                    Equation::Addition(vec![
+                        Equation::Variable(Variable::Integer(1)),
+                        Equation::Negative(Box::new(rhs)),
+                    ]),
+                ))))),
+                Equation::Variable(Variable::Integer(2)),
+            )));
+            isolate(*tanh, inverse, variable)
+        }
+        Equation::Abs(_) if value(&rhs).is_some_and(|rhs| rhs < 0.) => vec![],
+        Equation::Abs(abs) => {
+            let mut solutions = isolate((*abs).clone(), rhs.clone(), variable);
+            solutions.append(&mut isolate(
+                *abs,
+                Equation::Negative(Box::new(rhs)),
+                variable,
+            ));
+            solutions
+        }
+        _ => vec![],
+    }
+}
+
+///The value of an equation without variables, None if it has variables or is undefined
+fn value(equation: &Equation) -> Option<f64> {
+    equation.try_calculate(&BTreeMap::new()).ok()
+}
+
+///Whether the solution has a real value, solutions with variables are assumed to
+fn is_in_domain(solution: &Equation) -> bool {
+    matches!(
+        solution.try_calculate(&BTreeMap::new()),
+        Ok(_) | Err(EvalError::UnboundVariables(_))
+    )
+}
+
+///The real nth root, which is the negative root of the absolute value for negative numbers when n is odd
+fn real_root(radicand: Equation, n: i64) -> Equation {
+    let root = |radicand: Equation| {
+        Equation::Power(Box::new((
+            radicand,
+            Equation::Variable(Variable::Rational(Rational64::new(1, n))),
+        )))
+    };
+    if n.is_odd() && value(&radicand).is_some_and(|radicand| radicand < 0.) {
+        Equation::Negative(Box::new(root(Equation::Negative(Box::new(radicand)))))
+    } else {
+        root(radicand)
+    }
+}
+
+fn square_root(equation: Equation) -> Equation {
+    Equation::Power(Box::new((
+        equation,
+        Equation::Variable(Variable::Rational(Rational64::new(1, 2))),
+    )))
+}
+
+///Returns the square root of a rational number if both numerator and denominator are perfect squares
+pub(super) fn exact_square_root(rational: Rational64) -> Option<Rational64> {
+    let numerator = rational.numer().sqrt();
+    let denominator = rational.denom().sqrt();
+    if numerator * numerator == *rational.numer() && denominator * denominator == *rational.denom()
+    {
+        Some(Rational64::new(numerator, denominator))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,230 @@
+use super::{Equation, EvalError, Variable};
+use std::collections::BTreeMap;
+
+///Equations that hold at the same time
+#[derive(Clone, Debug, PartialEq)]
+pub struct System {
+    pub equations: Vec<Equation>,
+}
+
+impl System {
+    ///The letters in the equations in sorted order, which are the unknowns if none are given
+    pub fn letters(&self) -> Vec<Variable> {
+        let mut letters = self
+            .equations
+            .iter()
+            .flat_map(|equation| equation.letters())
+            .collect::<Vec<_>>();
+        letters.sort();
+        letters.dedup();
+        letters
+    }
+
+    ///Solves the equations for the unknowns, returning every solution as `unknown = value` for the unknowns the equations determine, in the order of the unknowns.
+    ///Linear systems with as many equations as unknowns are solved by elimination, any other system by solving an equation for one unknown and substituting it into the others
+    pub fn solve(&self, unknowns: &[Variable]) -> Vec<Vec<Equation>> {
+        let differences = self.equations.iter().map(difference).collect::<Vec<_>>();
+        if let Some(solution) = solve_linear(&differences, unknowns) {
+            return vec![solution];
+        }
+        let mut solutions = vec![];
+        for assignments in substitute(differences.clone(), unknowns) {
+            if !satisfies(&differences, &assignments) {
+                continue;
+            }
+            let solution = unknowns
+                .iter()
+                .filter_map(|unknown| {
+                    let value = assignments.get(unknown)?.clone();
+                    Some(Equation::Equals(Box::new((
+                        Equation::Variable(unknown.clone()),
+                        value,
+                    ))))
+                })
+                .collect::<Vec<_>>();
+            if !solutions.contains(&solution) {
+                solutions.push(solution);
+            }
+        }
+        solutions
+    }
+}
+
+///The left hand side minus the right hand side, an equation without = equals zero
+fn difference(equation: &Equation) -> Equation {
+    match equation {
+        Equation::Equals(sides) => Equation::Addition(vec![
+            sides.0.clone(),
+            Equation::Negative(Box::new(sides.1.clone())),
+        ])
+        .simplify_until_complete(),
+        equation => equation.clone().simplify_until_complete(),
+    }
+}
+
+///Writes the system as a matrix of coefficients times the unknowns and solves it by elimination, None if an equation is not linear in the unknowns or the matrix is singular
+fn solve_linear(differences: &[Equation], unknowns: &[Variable]) -> Option<Vec<Equation>> {
+    if differences.len() != unknowns.len() {
+        return None;
+    }
+    let (mut coefficients, mut constants) = (vec![], vec![]);
+    for difference in differences {
+        let row = unknowns
+            .iter()
+            .map(|unknown| linear_coefficient(difference, unknown, unknowns))
+            .collect::<Option<Vec<_>>>()?;
+        let constant = unknowns
+            .iter()
+            .fold(difference.clone(), |constant, unknown| {
+                constant.substitute(
+                    &Equation::Variable(unknown.clone()),
+                    &Equation::Variable(Variable::Integer(0)),
+                )
+            });
+        coefficients.push(row);
+        constants.push(vec![
+            Equation::Negative(Box::new(constant)).simplify_until_complete()
+        ]);
+    }
+    let solution = Equation::Matrix(coefficients).solve_linear(&Equation::Matrix(constants))?;
+    Some(
+        unknowns
+            .iter()
+            .zip(solution.rows()?)
+            .map(|(unknown, row)| {
+                Equation::Equals(Box::new((
+                    Equation::Variable(unknown.clone()),
+                    row[0].clone().simplify_until_complete(),
+                )))
+            })
+            .collect(),
+    )
+}
+
+///The coefficient of the unknown, None if it contains any of the unknowns so the equation is not linear
+fn linear_coefficient(
+    difference: &Equation,
+    unknown: &Variable,
+    unknowns: &[Variable],
+) -> Option<Equation> {
+    let coefficient = difference.differentiate(unknown).simplify_until_complete();
+    (!unknowns
+        .iter()
+        .any(|unknown| coefficient.contains_variable(unknown)))
+    .then_some(coefficient)
+}
+
+///Solves one of the equations for one of its unknowns and substitutes every solution into the other equations, returning the values of the unknowns for every branch.
+///Unknowns the equations are linear in are solved for first, so nonlinear equations are left with as few unknowns as possible, and other choices are tried if the rest cannot be solved
+fn substitute(
+    differences: Vec<Equation>,
+    unknowns: &[Variable],
+) -> Vec<BTreeMap<Variable, Equation>> {
+    let mut remaining = vec![];
+    for difference in differences {
+        if unknowns
+            .iter()
+            .any(|unknown| difference.contains_variable(unknown))
+        {
+            remaining.push(difference);
+        } else if difference
+            .calculate_exact()
+            .is_some_and(|value| value != 0.into())
+        {
+            //Without unknowns the equation is either always true or a contradiction
+            return vec![];
+        }
+    }
+    if remaining.is_empty() {
+        return vec![BTreeMap::new()];
+    }
+
+    let mut candidates = vec![];
+    for (i, difference) in remaining.iter().enumerate() {
+        for unknown in unknowns
+            .iter()
+            .filter(|unknown| difference.contains_variable(unknown))
+        {
+            //Constant coefficients first, then coefficients with other unknowns, then nonlinear occurrences
+            let rank = if linear_coefficient(difference, unknown, unknowns).is_some() {
+                0
+            } else if linear_coefficient(difference, unknown, std::slice::from_ref(unknown))
+                .is_some()
+            {
+                1
+            } else {
+                2
+            };
+            candidates.push((rank, i, unknown));
+        }
+    }
+    candidates.sort_by_key(|(rank, _, _)| *rank);
+
+    for (_, i, unknown) in candidates {
+        let values = remaining[i]
+            .solve(unknown)
+            .into_iter()
+            .filter_map(|solution| match solution {
+                Equation::Equals(sides) if !sides.1.contains_variable(unknown) => Some(sides.1),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if values.is_empty() {
+            continue;
+        }
+        let others = unknowns
+            .iter()
+            .filter(|other| *other != unknown)
+            .cloned()
+            .collect::<Vec<_>>();
+        let unknown_equation = Equation::Variable(unknown.clone());
+        let mut solutions = vec![];
+        for value in values {
+            let rest = remaining
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                //Expanding turns substituted sums back into polynomials the equations can be solved as
+                .map(|(_, difference)| difference.substitute(&unknown_equation, &value).expand())
+                .collect();
+            for mut assignments in substitute(rest, &others) {
+                let value =
+                    assignments
+                        .iter()
+                        .fold(value.clone(), |value, (variable, assigned)| {
+                            value.substitute(&Equation::Variable(variable.clone()), assigned)
+                        });
+                assignments.insert(unknown.clone(), value.simplify_until_complete());
+                solutions.push(assignments);
+            }
+        }
+        if !solutions.is_empty() {
+            return solutions;
+        }
+    }
+    vec![]
+}
+
+///Whether the values solve every equation, values outside the domain of a function solve nothing and
+///equations with free parameters are not checked
+fn satisfies(differences: &[Equation], assignments: &BTreeMap<Variable, Equation>) -> bool {
+    let mut values = BTreeMap::new();
+    for (variable, value) in assignments {
+        match value.try_calculate(&BTreeMap::new()) {
+            Ok(value) => {
+                values.insert(variable.clone(), value);
+            }
+            Err(EvalError::UnboundVariables(_)) => {}
+            Err(_) => return false,
+        }
+    }
+    let scale = values
+        .values()
+        .fold(1f64, |scale, value| scale.max(value.abs()));
+    differences
+        .iter()
+        .all(|difference| match difference.try_calculate(&values) {
+            Ok(value) => value.abs() < 1e-9 * scale,
+            Err(error) => matches!(error, EvalError::UnboundVariables(_)),
+        })
+}
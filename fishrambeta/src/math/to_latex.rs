@@ -1,4 +1,4 @@
-use crate::math::Equation;
+use crate::math::{Equation, Series, System};
 use crate::parser::IR;
 use std::fmt;
 
@@ -16,3 +16,40 @@ impl fmt::Display for Equation {
         write!(f, "{}", self.to_latex())
     }
 }
+
+impl Series {
+    ///The truncated series followed by its order term, as in x-\\frac{x^3}{6}+O(x^5)
+    pub fn to_latex(&self) -> String {
+        format!(
+            "{}+O({})",
+            self.to_equation().to_latex(),
+            self.order_term().to_latex()
+        )
+    }
+}
+
+impl fmt::Display for Series {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_latex())
+    }
+}
+
+impl System {
+    ///The equations in a cases environment, which parses back into the same system
+    pub fn to_latex(&self) -> String {
+        format!(
+            "\\begin{{cases}}{}\\end{{cases}}",
+            self.equations
+                .iter()
+                .map(|equation| equation.to_latex())
+                .collect::<Vec<_>>()
+                .join("\\\\")
+        )
+    }
+}
+
+impl fmt::Display for System {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_latex())
+    }
+}
@@ -0,0 +1,93 @@
+use super::{Equation, EvalError, Variable};
+use num_rational::Rational64;
+use std::collections::BTreeMap;
+use std::fmt;
+
+///A measured or calculated value with its standard uncertainty
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Measurement {
+    pub value: f64,
+    pub uncertainty: f64,
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ± {}", self.value, self.uncertainty)
+    }
+}
+
+impl Equation {
+    ///The uncertainty of the equation from independent inputs, every pair is a variable and the variable holding its uncertainty
+    pub fn propagate_uncertainty(&self, uncertainties: &[(Variable, Variable)]) -> Equation {
+        self.propagate_correlated_uncertainty(uncertainties, &[])
+    }
+
+    ///The uncertainty of the equation when inputs are correlated, every covariance is two variables and the variable holding their covariance
+    pub fn propagate_correlated_uncertainty(
+        &self,
+        uncertainties: &[(Variable, Variable)],
+        covariances: &[(Variable, Variable, Variable)],
+    ) -> Equation {
+        let derivative =
+            |variable: &Variable| self.differentiate(variable).simplify_until_complete();
+        let mut terms = uncertainties
+            .iter()
+            .map(|(variable, uncertainty)| {
+                Equation::Power(Box::new((
+                    Equation::Multiplication(vec![
+                        derivative(variable),
+                        Equation::Variable(uncertainty.clone()),
+                    ]),
+                    Equation::Variable(Variable::Integer(2)),
+                )))
+            })
+            .collect::<Vec<_>>();
+        for (a, b, covariance) in covariances {
+            terms.push(Equation::Multiplication(vec![
+                Equation::Variable(Variable::Integer(2)),
+                derivative(a),
+                derivative(b),
+                Equation::Variable(covariance.clone()),
+            ]));
+        }
+        Equation::Power(Box::new((
+            Equation::Addition(terms),
+            Equation::Variable(Variable::Rational(Rational64::new(1, 2))),
+        )))
+        .simplify_until_complete()
+    }
+
+    ///Calculates the value and its uncertainty from measurements, values are used for the variables that were not measured
+    pub fn calculate_with_uncertainty(
+        &self,
+        measurements: &BTreeMap<Variable, Measurement>,
+        covariances: &[((Variable, Variable), f64)],
+        values: &BTreeMap<Variable, f64>,
+    ) -> Result<Measurement, EvalError> {
+        let mut values = values.clone();
+        values.extend(
+            measurements
+                .iter()
+                .map(|(variable, measurement)| (variable.clone(), measurement.value)),
+        );
+        let value = self.try_calculate(&values)?;
+        let derivative = |variable: &Variable| {
+            self.differentiate(variable)
+                .simplify_until_complete()
+                .try_calculate(&values)
+        };
+
+        let mut variance = 0.;
+        for (variable, measurement) in measurements {
+            variance += (derivative(variable)? * measurement.uncertainty).powi(2);
+        }
+        for ((a, b), covariance) in covariances {
+            let (a, b) = (derivative(a)?, derivative(b)?);
+            variance += 2. * a * b * covariance;
+        }
+        Ok(Measurement {
+            value,
+            uncertainty: variance.max(0.).sqrt(),
+        })
+    }
+}
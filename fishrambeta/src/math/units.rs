@@ -0,0 +1,388 @@
+use super::{Equation, EvalError, Variable};
+use num::{One, Zero};
+use num_rational::Rational64;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Div, Mul};
+
+///The symbols of the SI base units, in the order of the powers in a dimension
+const BASE_UNITS: [&str; 7] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+
+///The symbols of units with their value in SI base units and the powers of m, kg, s, A, K, mol and cd
+const UNITS: [(&str, f64, [i64; 7]); 28] = [
+    ("m", 1., [1, 0, 0, 0, 0, 0, 0]),
+    ("g", 1e-3, [0, 1, 0, 0, 0, 0, 0]),
+    ("s", 1., [0, 0, 1, 0, 0, 0, 0]),
+    ("A", 1., [0, 0, 0, 1, 0, 0, 0]),
+    ("K", 1., [0, 0, 0, 0, 1, 0, 0]),
+    ("mol", 1., [0, 0, 0, 0, 0, 1, 0]),
+    ("cd", 1., [0, 0, 0, 0, 0, 0, 1]),
+    ("Hz", 1., [0, 0, -1, 0, 0, 0, 0]),
+    ("N", 1., [1, 1, -2, 0, 0, 0, 0]),
+    ("Pa", 1., [-1, 1, -2, 0, 0, 0, 0]),
+    ("J", 1., [2, 1, -2, 0, 0, 0, 0]),
+    ("W", 1., [2, 1, -3, 0, 0, 0, 0]),
+    ("C", 1., [0, 0, 1, 1, 0, 0, 0]),
+    ("V", 1., [2, 1, -3, -1, 0, 0, 0]),
+    ("\\Omega", 1., [2, 1, -3, -2, 0, 0, 0]),
+    ("Ohm", 1., [2, 1, -3, -2, 0, 0, 0]),
+    ("S", 1., [-2, -1, 3, 2, 0, 0, 0]),
+    ("F", 1., [-2, -1, 4, 2, 0, 0, 0]),
+    ("Wb", 1., [2, 1, -2, -1, 0, 0, 0]),
+    ("T", 1., [0, 1, -2, -1, 0, 0, 0]),
+    ("H", 1., [2, 1, -2, -2, 0, 0, 0]),
+    ("min", 60., [0, 0, 1, 0, 0, 0, 0]),
+    ("h", 3600., [0, 0, 1, 0, 0, 0, 0]),
+    ("d", 86400., [0, 0, 1, 0, 0, 0, 0]),
+    ("L", 1e-3, [3, 0, 0, 0, 0, 0, 0]),
+    ("eV", 1.602176634e-19, [2, 1, -2, 0, 0, 0, 0]),
+    ("bar", 1e5, [-1, 1, -2, 0, 0, 0, 0]),
+    ("atm", 101325., [-1, 1, -2, 0, 0, 0, 0]),
+];
+
+const PREFIXES: [(&str, f64); 21] = [
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("da", 1e1),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("\\mu", 1e-6),
+    ("μ", 1e-6),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+    ("a", 1e-18),
+    ("z", 1e-21),
+];
+
+///The powers of the SI base units m, kg, s, A, K, mol and cd that make up a unit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Dimension(pub [Rational64; 7]);
+
+impl Dimension {
+    pub const DIMENSIONLESS: Dimension = Dimension([Rational64::new_raw(0, 1); 7]);
+
+    fn from_powers(powers: [i64; 7]) -> Dimension {
+        Dimension(powers.map(Rational64::from))
+    }
+
+    pub fn is_dimensionless(&self) -> bool {
+        self.0.iter().all(|power| power.is_zero())
+    }
+
+    ///The dimension raised to a power, which multiplies the power of every base unit
+    pub fn pow(self, exponent: Rational64) -> Dimension {
+        Dimension(self.0.map(|power| power * exponent))
+    }
+
+    ///This dimension times the other to the power, the powers of the base units add when multiplying
+    fn combine(self, other: Dimension, exponent: Rational64) -> Dimension {
+        Dimension(std::array::from_fn(|i| self.0[i] + other.0[i] * exponent))
+    }
+}
+
+impl Mul for Dimension {
+    type Output = Dimension;
+
+    fn mul(self, other: Dimension) -> Dimension {
+        self.combine(other, Rational64::one())
+    }
+}
+
+impl Div for Dimension {
+    type Output = Dimension;
+
+    fn div(self, other: Dimension) -> Dimension {
+        self.combine(other, -Rational64::one())
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_dimensionless() {
+            return write!(f, "1");
+        }
+        let units = BASE_UNITS
+            .iter()
+            .zip(self.0)
+            .filter(|(_, power)| !power.is_zero())
+            .map(|(unit, power)| {
+                if power.is_one() {
+                    unit.to_string()
+                } else if power.is_integer() {
+                    format!("{}^{}", unit, power)
+                } else {
+                    format!("{}^({})", unit, power)
+                }
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", units.join(" "))
+    }
+}
+
+///A unit as a multiple of a product of powers of SI base units
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unit {
+    ///The value of the unit in SI base units
+    pub factor: f64,
+    pub dimension: Dimension,
+}
+
+impl Unit {
+    ///Parses units like kg m/s^2, km/h or J/(mol K), where every unit after a / divides and units can have SI prefixes, None if a unit is not known
+    pub fn parse(unit: &str) -> Option<Unit> {
+        let unit = unit
+            .replace("\\cdot", " ")
+            .replace("\\,", " ")
+            //The space that ends the command is not a product in \mu s
+            .replace("\\mu ", "\\mu")
+            .replace(['*', '·'], " ");
+        let mut parsed = Unit {
+            factor: 1.,
+            dimension: Dimension::DIMENSIONLESS,
+        };
+        for (i, part) in split_top_level(&unit, '/').into_iter().enumerate() {
+            let part = part.trim();
+            let part = part
+                .strip_prefix('(')
+                .and_then(|part| part.strip_suffix(')'))
+                .unwrap_or(part);
+            let sign = if i == 0 { 1 } else { -1 };
+            for factor in part.split_whitespace().filter(|factor| *factor != "1") {
+                let (symbol, exponent) = match factor.split_once('^') {
+                    Some((symbol, exponent)) => (symbol, parse_exponent(exponent)?),
+                    None => (factor, Rational64::one()),
+                };
+                let exponent = exponent * sign;
+                let unit = prefixed_unit(symbol)?;
+                parsed.factor *= unit
+                    .factor
+                    .powf(*exponent.numer() as f64 / *exponent.denom() as f64);
+                parsed.dimension = parsed.dimension * unit.dimension.pow(exponent);
+            }
+        }
+        Some(parsed)
+    }
+
+    ///The quantity of value times this unit
+    pub fn quantity(&self, value: f64) -> Quantity {
+        Quantity {
+            value: value * self.factor,
+            dimension: self.dimension,
+        }
+    }
+}
+
+///Splits at the separator where it is not inside braces or parentheses
+fn split_top_level(string: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, character) in string.char_indices() {
+        match character {
+            '{' | '(' => depth += 1,
+            '}' | ')' => depth -= 1,
+            _ if character == separator && depth == 0 => {
+                parts.push(&string[start..i]);
+                start = i + character.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&string[start..]);
+    parts
+}
+
+///Parses exponents like 2, -1, {-2} or (1/2)
+fn parse_exponent(exponent: &str) -> Option<Rational64> {
+    let exponent = exponent
+        .strip_prefix('{')
+        .and_then(|exponent| exponent.strip_suffix('}'))
+        .or_else(|| {
+            exponent
+                .strip_prefix('(')
+                .and_then(|exponent| exponent.strip_suffix(')'))
+        })
+        .unwrap_or(exponent);
+    match exponent.split_once('/') {
+        Some((numerator, denominator)) => {
+            let numerator = numerator.trim().parse::<i64>().ok()?;
+            let denominator = denominator.trim().parse::<i64>().ok()?;
+            (denominator != 0).then(|| Rational64::new(numerator, denominator))
+        }
+        None => exponent.trim().parse::<i64>().ok().map(Rational64::from),
+    }
+}
+
+///A unit by its symbol, which can start with an SI prefix if the symbol itself is not a unit
+fn prefixed_unit(symbol: &str) -> Option<Unit> {
+    let unit = |symbol: &str| {
+        UNITS
+            .iter()
+            .find(|(unit, _, _)| *unit == symbol)
+            .map(|(_, factor, powers)| Unit {
+                factor: *factor,
+                dimension: Dimension::from_powers(*powers),
+            })
+    };
+    unit(symbol).or_else(|| {
+        PREFIXES.iter().find_map(|(prefix, factor)| {
+            let unit = unit(symbol.strip_prefix(prefix)?)?;
+            Some(Unit {
+                factor: factor * unit.factor,
+                ..unit
+            })
+        })
+    })
+}
+
+///A value in SI base units with its dimension
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub dimension: Dimension,
+}
+
+impl Quantity {
+    ///The value expressed in the unit, None if the unit has another dimension
+    pub fn value_in(&self, unit: &Unit) -> Option<f64> {
+        (self.dimension == unit.dimension).then(|| self.value / unit.factor)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.dimension.is_dimensionless() {
+            write!(f, "{}", self.value)
+        } else {
+            write!(f, "{} {}", self.value, self.dimension)
+        }
+    }
+}
+
+impl Equation {
+    ///The dimension of the equation, where variables have the dimension in dimensions or are dimensionless if they are not in it.
+    ///Terms of a sum and both sides of = need the same dimension, arguments of functions and exponents need to be dimensionless
+    pub fn dimension(
+        &self,
+        dimensions: &BTreeMap<Variable, Dimension>,
+    ) -> Result<Dimension, EvalError> {
+        let dimension = |equation: &Equation| equation.dimension(dimensions);
+        let dimensionless = |equation: &Equation| match dimension(equation)? {
+            dimension if dimension.is_dimensionless() => Ok(dimension),
+            dimension => Err(EvalError::NotDimensionless(
+                equation.clone(),
+                Box::new(dimension),
+            )),
+        };
+        //An exact zero fits any dimension, so it takes the dimension of the other equations
+        let same = |equations: &mut dyn Iterator<Item = &Equation>| {
+            let mut same = None;
+            for equation in
+                equations.filter(|equation| equation.calculate_exact() != Some(0.into()))
+            {
+                let other = dimension(equation)?;
+                match same {
+                    Some(same) if same != other => {
+                        return Err(EvalError::IncompatibleUnits(
+                            self.clone(),
+                            Box::new((same, other)),
+                        ))
+                    }
+                    _ => same = Some(other),
+                }
+            }
+            Ok(same.unwrap_or(Dimension::DIMENSIONLESS))
+        };
+
+        match self {
+            Equation::Variable(variable) => Ok(dimensions
+                .get(variable)
+                .copied()
+                .unwrap_or(Dimension::DIMENSIONLESS)),
+            Equation::Negative(negative) | Equation::Abs(negative) => dimension(negative),
+            Equation::Addition(terms) => same(&mut terms.iter()),
+            Equation::Equals(sides) => same(&mut [&sides.0, &sides.1].into_iter()),
+            Equation::Multiplication(factors) => factors
+                .iter()
+                .try_fold(Dimension::DIMENSIONLESS, |product, factor| {
+                    Ok(product * dimension(factor)?)
+                }),
+            Equation::Dot(pair) | Equation::Cross(pair) => {
+                Ok(dimension(&pair.0)? * dimension(&pair.1)?)
+            }
+            Equation::Division(division) => Ok(dimension(&division.0)? / dimension(&division.1)?),
+            Equation::Power(power) => {
+                dimensionless(&power.1)?;
+                let base = dimension(&power.0)?;
+                if base.is_dimensionless() {
+                    return Ok(base);
+                }
+                //A base with a dimension needs a fixed exponent to know the dimension of the power
+                match power.1.calculate_exact() {
+                    Some(exponent) => Ok(base.pow(exponent)),
+                    None => Err(EvalError::NotDimensionless(power.0.clone(), Box::new(base))),
+                }
+            }
+            Equation::Ln(argument)
+            | Equation::Exp(argument)
+            | Equation::Sin(argument)
+            | Equation::Cos(argument)
+            | Equation::Tan(argument)
+            | Equation::Arcsin(argument)
+            | Equation::Arccos(argument)
+            | Equation::Arctan(argument)
+            | Equation::Sinh(argument)
+            | Equation::Cosh(argument)
+            | Equation::Tanh(argument) => dimensionless(argument),
+            Equation::Integral(integral) => {
+                let variable = dimension(&Equation::Variable(integral.variable.clone()))?;
+                //Pure numbers as bounds are in the unit of the variable
+                if let Some((lower, upper)) = &integral.bounds {
+                    same(
+                        &mut [&Equation::Variable(integral.variable.clone()), lower, upper]
+                            .into_iter()
+                            .filter(|bound| bound.calculate_exact().is_none()),
+                    )?;
+                }
+                Ok(dimension(&integral.integrand)? * variable)
+            }
+            Equation::Limit(limit) => dimension(&limit.expression),
+            Equation::Derivative(derivative) => derivative.variables.iter().try_fold(
+                dimension(&derivative.expression)?,
+                |quotient, variable| {
+                    Ok(quotient / dimension(&Equation::Variable(variable.clone()))?)
+                },
+            ),
+            Equation::Matrix(rows) => same(&mut rows.iter().flatten()),
+        }
+    }
+
+    ///Calculates the equation with the value of every variable in SI base units, checking that the units are consistent and giving the result with its dimension
+    pub fn calculate_with_units(
+        &self,
+        quantities: &BTreeMap<Variable, Quantity>,
+    ) -> Result<Quantity, EvalError> {
+        let dimensions = quantities
+            .iter()
+            .map(|(variable, quantity)| (variable.clone(), quantity.dimension))
+            .collect();
+        let dimension = self.dimension(&dimensions)?;
+        let values = quantities
+            .iter()
+            .map(|(variable, quantity)| (variable.clone(), quantity.value))
+            .collect();
+        Ok(Quantity {
+            value: self.try_calculate(&values)?,
+            dimension,
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use super::{Equation, EvalError, Variable};
+use std::collections::BTreeMap;
+
+///The value of an equation that can contain vectors
+#[derive(Clone, Debug, PartialEq)]
+pub enum VectorValue {
+    Scalar(f64),
+    Vector(Vec<f64>),
+}
+
+impl Equation {
+    ///Whether the equation has a vector as value, a product of two vectors is their dot product
+    pub fn is_vector(&self) -> bool {
+        match self {
+            Equation::Variable(variable) => matches!(variable, Variable::Vector(_)),
+            Equation::Negative(negative) => negative.is_vector(),
+            Equation::Addition(terms) => terms.iter().any(|term| term.is_vector()),
+            Equation::Multiplication(factors) => {
+                factors.iter().filter(|factor| factor.is_vector()).count() == 1
+            }
+            Equation::Division(division) => division.0.is_vector(),
+            Equation::Equals(sides) => sides.0.is_vector() || sides.1.is_vector(),
+            Equation::Cross(_) => true,
+            Equation::Integral(integral) => integral.integrand.is_vector(),
+            Equation::Limit(limit) => limit.expression.is_vector(),
+            Equation::Derivative(derivative) => derivative.expression.is_vector(),
+            _ => false,
+        }
+    }
+
+    ///Calculates the equation component-wise, vector variables take their components from vectors and scalar variables their value from values
+    pub fn calculate_vector(
+        &self,
+        values: &BTreeMap<Variable, f64>,
+        vectors: &BTreeMap<Variable, Vec<f64>>,
+    ) -> Result<VectorValue, EvalError> {
+        let unbound_variables = self
+            .free_variables()
+            .into_iter()
+            .filter(|variable| !values.contains_key(variable) && !vectors.contains_key(variable))
+            .collect::<Vec<_>>();
+        if !unbound_variables.is_empty() {
+            return Err(EvalError::UnboundVariables(unbound_variables));
+        }
+        self.checked_calculate_vector(values, vectors)
+    }
+
+    fn checked_calculate_vector(
+        &self,
+        values: &BTreeMap<Variable, f64>,
+        vectors: &BTreeMap<Variable, Vec<f64>>,
+    ) -> Result<VectorValue, EvalError> {
+        if !self
+            .free_variables()
+            .iter()
+            .any(|variable| matches!(variable, Variable::Vector(_)))
+        {
+            return self.checked_calculate(values).map(VectorValue::Scalar);
+        }
+        let calculate = |equation: &Equation| equation.checked_calculate_vector(values, vectors);
+        let scalar = |equation: &Equation| match calculate(equation)? {
+            VectorValue::Scalar(value) => Ok(value),
+            VectorValue::Vector(_) => Err(EvalError::NotAScalar(equation.clone())),
+        };
+        let vector = |equation: &Equation| match calculate(equation)? {
+            VectorValue::Vector(components) => Ok(components),
+            VectorValue::Scalar(_) => Err(EvalError::NotAVector(equation.clone())),
+        };
+        let mismatch = || EvalError::DimensionMismatch(self.clone());
+
+        match self {
+            Equation::Variable(variable) => vectors
+                .get(variable)
+                .map(|components| VectorValue::Vector(components.clone()))
+                .ok_or_else(|| EvalError::NotAVector(self.clone())),
+            Equation::Negative(negative) => Ok(calculate(negative)?.scale(-1.)),
+            Equation::Addition(terms) => {
+                let mut sum = calculate(&terms[0])?;
+                for term in &terms[1..] {
+                    sum = match (sum, calculate(term)?) {
+                        (VectorValue::Scalar(a), VectorValue::Scalar(b)) => {
+                            VectorValue::Scalar(a + b)
+                        }
+                        (VectorValue::Vector(a), VectorValue::Vector(b)) if a.len() == b.len() => {
+                            VectorValue::Vector(a.iter().zip(&b).map(|(a, b)| a + b).collect())
+                        }
+                        _ => return Err(mismatch()),
+                    };
+                }
+                Ok(sum)
+            }
+            Equation::Multiplication(factors) => {
+                let mut product = VectorValue::Scalar(1.);
+                for factor in factors {
+                    product = match (product, calculate(factor)?) {
+                        (VectorValue::Scalar(a), b) | (b, VectorValue::Scalar(a)) => b.scale(a),
+                        (VectorValue::Vector(a), VectorValue::Vector(b)) => {
+                            VectorValue::Scalar(dot(&a, &b).ok_or_else(mismatch)?)
+                        }
+                    };
+                }
+                Ok(product)
+            }
+            Equation::Division(division) => {
+                let denominator = scalar(&division.1)?;
+                if denominator == 0. {
+                    return Err(EvalError::DivisionByZero(division.1.clone()));
+                }
+                Ok(calculate(&division.0)?.scale(1. / denominator))
+            }
+            Equation::Dot(pair) => Ok(VectorValue::Scalar(
+                dot(&vector(&pair.0)?, &vector(&pair.1)?).ok_or_else(mismatch)?,
+            )),
+            Equation::Cross(pair) => match (&vector(&pair.0)?[..], &vector(&pair.1)?[..]) {
+                ([a1, a2, a3], [b1, b2, b3]) => Ok(VectorValue::Vector(vec![
+                    a2 * b3 - a3 * b2,
+                    a3 * b1 - a1 * b3,
+                    a1 * b2 - a2 * b1,
+                ])),
+                _ => Err(mismatch()),
+            },
+            Equation::Abs(abs) => Ok(VectorValue::Scalar(match calculate(abs)? {
+                VectorValue::Scalar(value) => value.abs(),
+                VectorValue::Vector(components) => {
+                    components.iter().map(|x| x * x).sum::<f64>().sqrt()
+                }
+            })),
+            Equation::Power(power) => Ok(VectorValue::Scalar(
+                scalar(&power.0)?.powf(scalar(&power.1)?),
+            )),
+            Equation::Ln(x) => Ok(VectorValue::Scalar(scalar(x)?.ln())),
+            Equation::Exp(x) => Ok(VectorValue::Scalar(scalar(x)?.exp())),
+            Equation::Sin(x) => Ok(VectorValue::Scalar(scalar(x)?.sin())),
+            Equation::Cos(x) => Ok(VectorValue::Scalar(scalar(x)?.cos())),
+            Equation::Tan(x) => Ok(VectorValue::Scalar(scalar(x)?.tan())),
+            Equation::Arcsin(x) => Ok(VectorValue::Scalar(scalar(x)?.asin())),
+            Equation::Arccos(x) => Ok(VectorValue::Scalar(scalar(x)?.acos())),
+            Equation::Arctan(x) => Ok(VectorValue::Scalar(scalar(x)?.atan())),
+            Equation::Sinh(x) => Ok(VectorValue::Scalar(scalar(x)?.sinh())),
+            Equation::Cosh(x) => Ok(VectorValue::Scalar(scalar(x)?.cosh())),
+            Equation::Tanh(x) => Ok(VectorValue::Scalar(scalar(x)?.tanh())),
+            Equation::Derivative(derivative) => calculate(&derivative.evaluate()),
+            Equation::Equals(_) => Err(EvalError::Equals),
+            Equation::Integral(_) | Equation::Limit(_) | Equation::Matrix(_) => {
+                Err(EvalError::NotAScalar(self.clone()))
+            }
+        }
+    }
+}
+
+impl VectorValue {
+    fn scale(self, factor: f64) -> VectorValue {
+        match self {
+            VectorValue::Scalar(value) => VectorValue::Scalar(value * factor),
+            VectorValue::Vector(components) => {
+                VectorValue::Vector(components.into_iter().map(|x| x * factor).collect())
+            }
+        }
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> Option<f64> {
+    (a.len() == b.len()).then(|| a.iter().zip(b).map(|(a, b)| a * b).sum())
+}
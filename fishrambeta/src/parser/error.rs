@@ -0,0 +1,55 @@
+use std::fmt;
+use std::ops::Range;
+
+///An error encountered while parsing latex, the span contains the character indices of the offending part of the input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub span: Range<usize>,
+    pub expected: Option<String>,
+    pub message: String,
+}
+
+impl ParseError {
+    pub(super) fn new(span: Range<usize>, message: impl Into<String>) -> Self {
+        ParseError {
+            span,
+            expected: None,
+            message: message.into(),
+        }
+    }
+    ///An error for a single character that is not allowed at its position
+    pub(super) fn unexpected(position: usize, character: char) -> Self {
+        Self::new(
+            position..position + 1,
+            format!("unexpected `{}`", character),
+        )
+    }
+    pub(super) fn expected(mut self, expected: impl Into<String>) -> Self {
+        self.expected = Some(expected.into());
+        self
+    }
+    ///Translates the span from positions in the sanitized latex to positions in the original input
+    pub(super) fn map_span(mut self, positions: &[usize], input_length: usize) -> Self {
+        let map = |index: usize| positions.get(index).copied().unwrap_or(input_length);
+        let start = map(self.span.start);
+        let end = if self.span.end > self.span.start {
+            map(self.span.end - 1) + 1
+        } else {
+            start
+        };
+        self.span = start..end;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.span.start + 1)?;
+        if let Some(expected) = &self.expected {
+            write!(f, ", expected {}", expected)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
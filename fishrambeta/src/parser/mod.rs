@@ -1,20 +1,69 @@
 use num_rational::Rational64;
+use std::ops::Range;
 
-use crate::math::{Constant, Equation, Variable};
+use crate::math::{
+    Constant, Derivative, Equation, Integral, Limit, LimitDirection, System, Variable,
+};
 
+mod error;
 mod numpy;
 
+pub use error::ParseError;
+
+///The superscript and subscript following a command
+pub type Scripts = (Option<Vec<char>>, Option<Vec<char>>);
+
 pub struct IR {
     name: Vec<char>,
     parameters: Vec<(IR, BracketType)>,
 }
 impl IR {
-    pub fn latex_to_equation(latex: Vec<char>, implicit_multiplication: bool) -> Equation {
-        if !Self::calculate_depth_difference(&latex) == 0 {
-            panic!("Invalid latex");
+    pub fn latex_to_equation(
+        latex: Vec<char>,
+        implicit_multiplication: bool,
+    ) -> Result<Equation, ParseError> {
+        Self::check_brackets(&latex)?;
+        let (grouped_latex, grouped_positions) = group_integrals(&latex, 0)?;
+        let (grouped_latex, limit_positions) = group_limits(&grouped_latex, 0)
+            .map_err(|error| error.map_span(&grouped_positions, latex.len()))?;
+        //Positions one past the end of a grouped part stand for the end of the input
+        let in_input = |positions: Vec<usize>, grouped_positions: &[usize]| {
+            positions
+                .into_iter()
+                .map(|position| {
+                    grouped_positions
+                        .get(position)
+                        .copied()
+                        .unwrap_or(latex.len())
+                })
+                .collect::<Vec<_>>()
+        };
+        let grouped_positions = in_input(limit_positions, &grouped_positions);
+        let (grouped_latex, derivative_positions) = group_derivatives(&grouped_latex, 0)
+            .map_err(|error| error.map_span(&grouped_positions, latex.len()))?;
+        let grouped_positions = in_input(derivative_positions, &grouped_positions);
+        let (sanitized_latex, positions) = cleanup_latex_with_positions(&grouped_latex)
+            .map_err(|error| error.map_span(&grouped_positions, latex.len()))?;
+        let positions = in_input(positions, &grouped_positions);
+        Self::latex_to_ir(sanitized_latex, implicit_multiplication)
+            .and_then(|ir| ir.ir_to_equation())
+            .map_err(|error| error.map_span(&positions, latex.len()))
+    }
+    ///Parses equations separated by \\\\, commas or newlines, which can be in a cases environment, a chain a=b=c gives the equations a=b and b=c
+    pub fn latex_to_system(
+        latex: Vec<char>,
+        implicit_multiplication: bool,
+    ) -> Result<System, ParseError> {
+        let mut equations = vec![];
+        for (part, positions) in split_system(&latex) {
+            let equation = Self::latex_to_equation(part, implicit_multiplication)
+                .map_err(|error| error.map_span(&positions, latex.len()))?;
+            equations.append(&mut unchain(equation));
         }
-        let sanitized_latex = cleanup_latex(latex);
-        Self::latex_to_ir(sanitized_latex, implicit_multiplication).ir_to_equation()
+        if equations.is_empty() {
+            return Err(ParseError::new(0..latex.len(), "missing equation").expected("an equation"));
+        }
+        Ok(System { equations })
     }
     pub fn equation_to_latex(equation: Equation, implicit_multiplication: bool) -> String {
         Self::equation_to_ir(equation)
@@ -28,89 +77,91 @@ impl IR {
             .into_iter()
             .collect::<String>()
     }
-    pub fn latex_to_ir(latex: Vec<char>, implicit_multiplication: bool) -> Self {
-        let mut latex = latex;
-        while latex[0] == '+' {
+    pub fn latex_to_ir(
+        latex: Vec<char>,
+        implicit_multiplication: bool,
+    ) -> Result<Self, ParseError> {
+        Self::latex_to_ir_at(latex, 0, implicit_multiplication)
+    }
+    ///Converts latex to IR, offset is the position of the first character within the whole input and is only used to report errors
+    fn latex_to_ir_at(
+        mut latex: Vec<char>,
+        mut offset: usize,
+        implicit_multiplication: bool,
+    ) -> Result<Self, ParseError> {
+        while latex.first() == Some(&'+') {
             latex.remove(0);
+            offset += 1;
+        }
+        if latex.is_empty() {
+            return Err(
+                ParseError::new(offset..offset, "missing expression").expected("an expression")
+            );
         }
         let top_level_operators =
             Self::get_operators_in_top_level_from_latex(&latex, implicit_multiplication);
+        //Splitting on the last operator makes a-b+c parse as (a-b)+c, a sign directly after another operator belongs to the term, so 2*-3 is split on the *
+        let sum_operator = top_level_operators
+            .additions_and_subtractions
+            .iter()
+            .rev()
+            .find(|i| {
+                let previous = latex[..**i].iter().rev().find(|c| **c != ' ');
+                !matches!(previous, Some('+' | '-' | '*' | '/' | '×'))
+            })
+            .copied();
         if top_level_operators.any() {
-            return if !top_level_operators.equals.is_empty() {
-                let (lhs, rhs) = latex.split_at(top_level_operators.equals[0]);
-                let (lhs, mut rhs) = (lhs.to_vec(), rhs.to_vec());
-                let operator = rhs.remove(0);
-                IR {
-                    name: vec![operator],
-                    parameters: vec![
-                        (
-                            Self::latex_to_ir(lhs, implicit_multiplication),
-                            BracketType::None,
-                        ),
-                        (
-                            Self::latex_to_ir(rhs, implicit_multiplication),
-                            BracketType::None,
-                        ),
-                    ],
-                }
-            } else if !top_level_operators.additions_and_subtractions.is_empty() {
-                let (lhs, rhs) = latex.split_at(top_level_operators.additions_and_subtractions[0]);
-                let (lhs, mut rhs) = (lhs.to_vec(), rhs.to_vec());
-                let operator = rhs.remove(0);
-                IR {
-                    name: vec![operator],
-                    parameters: vec![
-                        (
-                            Self::latex_to_ir(lhs, implicit_multiplication),
-                            BracketType::None,
-                        ),
-                        (
-                            Self::latex_to_ir(rhs, implicit_multiplication),
-                            BracketType::None,
-                        ),
-                    ],
-                }
+            if !top_level_operators.equals.is_empty() {
+                Self::split_on_operator(
+                    latex,
+                    top_level_operators.equals[0],
+                    offset,
+                    implicit_multiplication,
+                )
+            } else if let Some(position) = sum_operator {
+                Self::split_on_operator(latex, position, offset, implicit_multiplication)
             } else if !top_level_operators.multiplications_and_divisions.is_empty() {
-                let (lhs, rhs) =
-                    latex.split_at(top_level_operators.multiplications_and_divisions[0]);
-                let (lhs, mut rhs) = (lhs.to_vec(), rhs.to_vec());
-                let operator = rhs.remove(0);
-                IR {
-                    name: vec![operator],
-                    parameters: vec![
-                        (
-                            Self::latex_to_ir(lhs, implicit_multiplication),
-                            BracketType::None,
-                        ),
-                        (
-                            Self::latex_to_ir(rhs, implicit_multiplication),
-                            BracketType::None,
-                        ),
-                    ],
-                }
+                Self::split_on_operator(
+                    latex,
+                    *top_level_operators
+                        .multiplications_and_divisions
+                        .last()
+                        .unwrap(),
+                    offset,
+                    implicit_multiplication,
+                )
+            } else if !top_level_operators.additions_and_subtractions.is_empty() {
+                Self::split_on_operator(
+                    latex,
+                    top_level_operators.additions_and_subtractions[0],
+                    offset,
+                    implicit_multiplication,
+                )
             } else {
                 let mut parts = vec![];
+                let mut consumed = 0;
                 for power in top_level_operators.powers {
-                    let (lhs, rhs) = latex.split_at(power);
+                    let (lhs, rhs) = latex.split_at(power - consumed);
                     let (lhs, mut rhs) = (lhs.to_vec(), rhs.to_vec());
                     rhs.remove(0);
+                    parts.push((lhs, offset + consumed));
+                    consumed = power + 1;
                     latex = rhs;
-                    parts.push(lhs);
                 }
-                parts.push(latex);
-                Self {
+                parts.push((latex, offset + consumed));
+                Ok(Self {
                     name: vec!['^'],
                     parameters: parts
                         .into_iter()
-                        .map(|parts| {
-                            (
-                                Self::latex_to_ir(parts, implicit_multiplication),
+                        .map(|(part, part_offset)| {
+                            Ok((
+                                Self::latex_to_ir_at(part, part_offset, implicit_multiplication)?,
                                 BracketType::None,
-                            )
+                            ))
                         })
-                        .collect::<Vec<_>>(),
-                }
-            };
+                        .collect::<Result<Vec<_>, ParseError>>()?,
+                })
+            }
         } else {
             if BracketType::is_opening_bracket(latex[0])
                 && BracketType::is_closing_bracket(latex[latex.len() - 1])
@@ -118,39 +169,181 @@ impl IR {
             {
                 let _bracket_type = BracketType::get_opening_bracket_type(latex.remove(0));
                 latex.remove(latex.len() - 1);
-                return Self::latex_to_ir(latex, implicit_multiplication);
+                return Self::latex_to_ir_at(latex, offset + 1, implicit_multiplication);
+            }
+            if latex[0] == '-' {
+                latex.remove(0);
+                return Ok(IR {
+                    name: vec!['\\', 'i', 'n', 'v'],
+                    parameters: vec![(
+                        Self::latex_to_ir_at(latex, offset + 1, implicit_multiplication)?,
+                        BracketType::Round,
+                    )],
+                });
             }
             if latex.starts_with(&['\\']) {
                 latex.remove(0);
                 let mut command = vec![];
-                loop {
-                    if latex[0] == '{'
-                        || latex[0] == '('
-                        || latex[0] == '['
-                        || latex[0] == '^'
-                        || latex[0] == '_'
-                        || latex[0] == '\\'
+                while let Some(next) = latex.first() {
+                    if *next == '{'
+                        || *next == '('
+                        || *next == '['
+                        || *next == '^'
+                        || *next == '_'
+                        || *next == '\\'
                     {
                         break;
                     }
                     command.push(latex.remove(0));
-                    if latex.is_empty() {
-                        break;
-                    }
                 }
+                let command_span = offset..offset + command.len() + 1;
+                if command.is_empty() {
+                    return Err(
+                        ParseError::new(command_span, "missing command name").expected("a command")
+                    );
+                }
+                let mut position = command_span.end;
                 if command == ['i', 'n', 't'] {
-                    let (_superscript, _subscript) =
-                        Self::get_super_and_subscript(&mut latex, implicit_multiplication);
-                    todo!();
+                    let scripts_offset = position;
+                    let (upper, lower) = Self::get_super_and_subscript(&mut latex, &mut position)?;
+                    let mut parameters = vec![];
+                    for _ in 0..2 {
+                        parameters.push((
+                            Self::get_first_parameter(
+                                &mut latex,
+                                &mut position,
+                                implicit_multiplication,
+                            )?,
+                            BracketType::Curly,
+                        ));
+                    }
+                    match (lower, upper) {
+                        (Some(lower), Some(upper)) => {
+                            for bound in [lower, upper] {
+                                parameters.push((
+                                    Self::latex_to_ir_at(
+                                        bound,
+                                        scripts_offset,
+                                        implicit_multiplication,
+                                    )?,
+                                    BracketType::Curly,
+                                ));
+                            }
+                        }
+                        (None, None) => {}
+                        _ => {
+                            return Err(ParseError::new(command_span, "integral with one bound")
+                                .expected("both a lower and an upper bound"))
+                        }
+                    }
+                    let integral = Self {
+                        name: command.to_vec(),
+                        parameters,
+                    };
+                    if latex.is_empty() {
+                        Ok(integral)
+                    } else {
+                        let other_ir =
+                            Self::latex_to_ir_at(latex, position, implicit_multiplication)?;
+                        Ok(Self {
+                            name: vec!['*'],
+                            parameters: vec![
+                                (integral, BracketType::Round),
+                                (other_ir, BracketType::Round),
+                            ],
+                        })
+                    }
+                } else if command == ['l', 'i', 'm'] {
+                    let mut parameters = vec![];
+                    for _ in 0..3 {
+                        parameters.push((
+                            Self::get_first_parameter(
+                                &mut latex,
+                                &mut position,
+                                implicit_multiplication,
+                            )?,
+                            BracketType::Curly,
+                        ));
+                    }
+                    if latex.first() == Some(&'{') {
+                        let direction = Self::take_group(&mut latex, &mut position)?;
+                        parameters.push((
+                            Self {
+                                name: direction,
+                                parameters: vec![],
+                            },
+                            BracketType::Curly,
+                        ));
+                    }
+                    let limit = Self {
+                        name: command.to_vec(),
+                        parameters,
+                    };
+                    if latex.is_empty() {
+                        Ok(limit)
+                    } else {
+                        let other_ir =
+                            Self::latex_to_ir_at(latex, position, implicit_multiplication)?;
+                        Ok(Self {
+                            name: vec!['*'],
+                            parameters: vec![
+                                (limit, BracketType::Round),
+                                (other_ir, BracketType::Round),
+                            ],
+                        })
+                    }
+                } else if matches!(
+                    command[..],
+                    ['d', 'i', 'f', 'f'] | ['m', 'a', 't', 'r', 'i', 'x'] | ['r', 'o', 'w']
+                ) {
+                    let (mut parameters, mut spans) = (vec![], vec![]);
+                    while latex.first() == Some(&'{') {
+                        let start = position;
+                        parameters.push((
+                            Self::get_first_parameter(
+                                &mut latex,
+                                &mut position,
+                                implicit_multiplication,
+                            )?,
+                            BracketType::Curly,
+                        ));
+                        //The contents of the group, without its brackets
+                        spans.push(start + 1..position - 1);
+                    }
+                    if command == ['m', 'a', 't', 'r', 'i', 'x'] {
+                        Self::check_matrix(&parameters, &spans, command_span)?;
+                    }
+                    let grouped = Self {
+                        name: command.to_vec(),
+                        parameters,
+                    };
+                    if latex.is_empty() {
+                        Ok(grouped)
+                    } else {
+                        let other_ir =
+                            Self::latex_to_ir_at(latex, position, implicit_multiplication)?;
+                        Ok(Self {
+                            name: vec!['*'],
+                            parameters: vec![
+                                (grouped, BracketType::Round),
+                                (other_ir, BracketType::Round),
+                            ],
+                        })
+                    }
                 } else if command == ['f', 'r', 'a', 'c'] {
                     let mut params = vec![];
-                    if !BracketType::is_opening_bracket(latex[0]) {
-                        panic!("Invalid fraction");
-                    }
-                    let first = Self::get_first_parameter(&mut latex, implicit_multiplication);
+                    let first = Self::get_first_parameter(
+                        &mut latex,
+                        &mut position,
+                        implicit_multiplication,
+                    )?;
                     params.push((first, BracketType::Curly));
                     params.push((
-                        Self::get_first_parameter(&mut latex, implicit_multiplication),
+                        Self::get_first_parameter(
+                            &mut latex,
+                            &mut position,
+                            implicit_multiplication,
+                        )?,
                         BracketType::Curly,
                     ));
                     let fraction = Self {
@@ -158,55 +351,76 @@ impl IR {
                         parameters: params,
                     };
                     if latex.is_empty() {
-                        return fraction;
+                        Ok(fraction)
                     } else {
-                        let other_ir = Self::latex_to_ir(latex, implicit_multiplication);
-                        return Self {
+                        let other_ir =
+                            Self::latex_to_ir_at(latex, position, implicit_multiplication)?;
+                        Ok(Self {
                             name: vec!['*'],
                             parameters: vec![
                                 (fraction, BracketType::Curly),
                                 (other_ir, BracketType::Curly),
                             ],
-                        };
+                        })
                     }
                 } else if command == ['s', 'q', 'r', 't'] {
                     let parameters = vec![(
-                        Self::get_first_parameter(&mut latex, implicit_multiplication),
+                        Self::get_first_parameter(
+                            &mut latex,
+                            &mut position,
+                            implicit_multiplication,
+                        )?,
                         BracketType::Curly,
                     )];
                     let sqrt = Self {
                         name: command.to_vec(),
                         parameters,
                     };
-                    return if latex.is_empty() {
-                        sqrt
+                    if latex.is_empty() {
+                        Ok(sqrt)
                     } else {
-                        let other_ir = Self::latex_to_ir(latex, implicit_multiplication);
-                        Self {
+                        let other_ir =
+                            Self::latex_to_ir_at(latex, position, implicit_multiplication)?;
+                        Ok(Self {
                             name: command.to_vec(),
                             parameters: vec![
                                 (sqrt, BracketType::None),
                                 (other_ir, BracketType::None),
                             ],
-                        }
-                    };
-                } else if command == ['s', 'i', 'n']
-                    || command == ['c', 'o', 's']
-                    || command == ['t', 'a', 'n']
-                    || command == ['l', 'n']
-                    || command == ['l', 'o', 'g']
-                {
+                        })
+                    }
+                } else if matches!(
+                    command.iter().collect::<String>().as_str(),
+                    "sin"
+                        | "cos"
+                        | "tan"
+                        | "arcsin"
+                        | "arccos"
+                        | "arctan"
+                        | "sinh"
+                        | "cosh"
+                        | "tanh"
+                        | "ln"
+                        | "log"
+                        | "exp"
+                        | "abs"
+                        | "vec"
+                ) {
                     let parameters = vec![(
-                        Self::get_first_parameter(&mut latex, implicit_multiplication),
+                        Self::get_first_parameter(
+                            &mut latex,
+                            &mut position,
+                            implicit_multiplication,
+                        )?,
                         BracketType::Curly,
                     )];
-                    return if latex.is_empty() {
-                        Self {
+                    if latex.is_empty() {
+                        Ok(Self {
                             name: command.to_vec(),
                             parameters,
-                        }
+                        })
                     } else {
-                        Self {
+                        Ok(Self {
                             name: vec!['*'],
                             parameters: vec![
                                 (
@@ -217,141 +431,141 @@ impl IR {
                                     BracketType::Round,
                                 ),
                                 (
-                                    Self::latex_to_ir(latex, implicit_multiplication),
+                                    Self::latex_to_ir_at(latex, position, implicit_multiplication)?,
                                     BracketType::Round,
                                 ),
                             ],
-                        }
-                    };
+                        })
+                    }
                 } else {
-                    return if latex.is_empty() {
-                        let mut slash_command = vec!['\\'];
-                        slash_command.append(&mut command);
-                        Self {
-                            name: slash_command,
-                            parameters: vec![],
-                        }
+                    let mut slash_command = vec!['\\'];
+                    slash_command.append(&mut command);
+                    //Only the commands above take a group, \\foo{x} is not (\\foo)*(x)
+                    if latex.first() == Some(&'{') {
+                        return Err(ParseError::new(
+                            command_span,
+                            format!(
+                                "unknown command `{}`",
+                                slash_command.iter().collect::<String>()
+                            ),
+                        ));
+                    }
+                    let command = Self {
+                        name: slash_command,
+                        parameters: vec![],
+                    };
+                    if latex.is_empty() {
+                        Ok(command)
                     } else {
-                        let command = Self {
-                            parameters: vec![],
-                            name: command,
-                        };
-                        Self {
+                        Ok(Self {
                             name: vec!['*'],
                             parameters: vec![
                                 (command, BracketType::Round),
                                 (
-                                    Self::latex_to_ir(latex, implicit_multiplication),
+                                    Self::latex_to_ir_at(latex, position, implicit_multiplication)?,
                                     BracketType::Round,
                                 ),
                             ],
-                        }
-                    };
+                        })
+                    }
                 }
-            } else if latex.contains(&'\\') {
-                let slash = latex.iter().position(|f| f == &'\\').unwrap();
+            } else if let Some(slash) = latex.iter().position(|f| f == &'\\') {
                 let (lhs, rhs) = latex.split_at(slash);
-                return Self {
+                Ok(Self {
                     name: vec!['*'],
                     parameters: vec![
                         (
-                            Self::latex_to_ir(lhs.to_vec(), implicit_multiplication),
+                            Self::latex_to_ir_at(lhs.to_vec(), offset, implicit_multiplication)?,
                             BracketType::Round,
                         ),
                         (
-                            Self::latex_to_ir(rhs.to_vec(), implicit_multiplication),
+                            Self::latex_to_ir_at(
+                                rhs.to_vec(),
+                                offset + slash,
+                                implicit_multiplication,
+                            )?,
                             BracketType::Round,
                         ),
                     ],
-                };
-            } else if latex.contains(&'{')
-                || latex.contains(&'(')
-                || latex.contains(&'[')
-                || latex.contains(&'⟨')
+                })
+            } else if let Some(bracket) = latex
+                .iter()
+                .position(|c| BracketType::is_opening_bracket(*c))
             {
-                if BracketType::is_opening_bracket(latex[0])
-                    && BracketType::is_closing_bracket(latex[latex.len() - 1])
-                    && Self::first_and_last_bracket_connected(&latex)
-                {
-                    latex.remove(0);
-                    latex.remove(latex.len() - 1);
-                    return Self::latex_to_ir(latex, implicit_multiplication);
-                } else if BracketType::is_opening_bracket(latex[0])
-                    && BracketType::is_closing_bracket(latex[latex.len() - 1])
-                {
+                if bracket == 0 {
                     let (lhs, rhs) = Self::split_on_brackets(latex);
-                    return Self {
+                    let rhs_offset = offset + lhs.len();
+                    return Ok(Self {
                         name: vec!['*'],
                         parameters: vec![
                             (
-                                Self::latex_to_ir(lhs, implicit_multiplication),
+                                Self::latex_to_ir_at(lhs, offset, implicit_multiplication)?,
                                 BracketType::Round,
                             ),
                             (
-                                Self::latex_to_ir(rhs, implicit_multiplication),
+                                Self::latex_to_ir_at(rhs, rhs_offset, implicit_multiplication)?,
                                 BracketType::Round,
                             ),
                         ],
-                    };
+                    });
                 }
-                todo!()
+                Err(ParseError::unexpected(offset + bracket, latex[bracket])
+                    .expected("an operator"))
             } else if latex.iter().any(|char| char.is_numeric()) {
                 if latex.iter().any(|char| !char.is_numeric() && char != &'.') {
-                    if latex[0] == '-' {
-                        latex.remove(0);
-                        return IR {
-                            name: vec!['\\', 'i', 'n', 'v'],
-                            parameters: vec![(
-                                Self::latex_to_ir(latex, implicit_multiplication),
-                                BracketType::Round,
-                            )],
-                        };
-                    }
                     if latex.iter().any(|c| c.is_alphabetic()) {
                         let mut parts = vec![];
                         let mut is_number = latex[0].is_numeric();
                         let mut next_buf = vec![];
+                        let mut part_offset = offset;
                         for char in latex.into_iter() {
                             if (is_number && (char.is_numeric() || char == '.'))
                                 || (!is_number && !char.is_numeric())
                             {
                                 next_buf.push(char)
                             } else {
-                                parts.push(next_buf);
+                                let next_offset = part_offset + next_buf.len();
+                                parts.push((next_buf, part_offset));
+                                part_offset = next_offset;
                                 is_number = char.is_numeric();
                                 next_buf = vec![char];
                             }
                         }
-                        parts.push(next_buf);
-                        return Self {
+                        parts.push((next_buf, part_offset));
+                        return Ok(Self {
                             name: vec!['*'],
                             parameters: parts
                                 .into_iter()
-                                .map(|part| {
-                                    (
-                                        Self::latex_to_ir(part, implicit_multiplication),
+                                .map(|(part, part_offset)| {
+                                    Ok((
+                                        Self::latex_to_ir_at(
+                                            part,
+                                            part_offset,
+                                            implicit_multiplication,
+                                        )?,
                                         BracketType::Round,
-                                    )
+                                    ))
                                 })
-                                .collect::<Vec<_>>(),
-                        };
+                                .collect::<Result<Vec<_>, ParseError>>()?,
+                        });
                     }
-                    todo!()
+                    let invalid = latex
+                        .iter()
+                        .position(|char| !char.is_numeric() && char != &'.')
+                        .unwrap();
+                    Err(ParseError::unexpected(offset + invalid, latex[invalid]))
                 } else {
-                    return IR {
+                    if Self::parse_number(&latex).is_none() {
+                        return Err(ParseError::new(
+                            offset..offset + latex.len(),
+                            format!("invalid number `{}`", latex.iter().collect::<String>()),
+                        ));
+                    }
+                    Ok(IR {
                         name: latex,
                         parameters: vec![],
-                    };
+                    })
                 }
-            } else if latex[0] == '-' {
-                latex.remove(0);
-                return IR {
-                    name: vec!['\\', 'i', 'n', 'v'],
-                    parameters: vec![(
-                        Self::latex_to_ir(latex, implicit_multiplication),
-                        BracketType::Round,
-                    )],
-                };
             } else if implicit_multiplication {
                 let letters = latex
                     .into_iter()
@@ -365,17 +579,93 @@ impl IR {
                         )
                     })
                     .collect();
-                return Self {
+                Ok(Self {
                     name: vec!['*'],
                     parameters: letters,
-                };
+                })
             } else {
-                return IR {
+                Ok(IR {
                     name: latex,
                     parameters: vec![],
-                };
+                })
+            }
+        }
+    }
+    ///Splits the latex into two parameters around the operator at the given position
+    fn split_on_operator(
+        latex: Vec<char>,
+        position: usize,
+        offset: usize,
+        implicit_multiplication: bool,
+    ) -> Result<Self, ParseError> {
+        let (lhs, rhs) = latex.split_at(position);
+        let (lhs, mut rhs) = (lhs.to_vec(), rhs.to_vec());
+        let operator = rhs.remove(0);
+        Ok(IR {
+            name: vec![operator],
+            parameters: vec![
+                (
+                    Self::latex_to_ir_at(lhs, offset, implicit_multiplication)?,
+                    BracketType::None,
+                ),
+                (
+                    Self::latex_to_ir_at(rhs, offset + position + 1, implicit_multiplication)?,
+                    BracketType::None,
+                ),
+            ],
+        })
+    }
+    ///Checks that every parameter of a matrix is a row and that the rows have the same length, spans contains the position of every row
+    fn check_matrix(
+        rows: &[(IR, BracketType)],
+        spans: &[Range<usize>],
+        command_span: Range<usize>,
+    ) -> Result<(), ParseError> {
+        let Some((first, _)) = rows.first() else {
+            return Err(ParseError::new(command_span, "matrix without rows").expected("a row"));
+        };
+        for ((row, _), span) in rows.iter().zip(spans) {
+            if row.name != ['r', 'o', 'w'] {
+                return Err(ParseError::new(
+                    span.clone(),
+                    "matrix entry outside of a row",
+                ));
+            }
+            if row.parameters.len() != first.parameters.len() {
+                return Err(ParseError::new(
+                    span.clone(),
+                    "the rows of a matrix have different lengths",
+                )
+                .expected(match first.parameters.len() {
+                    1 => "1 entry".to_string(),
+                    length => format!("{} entries", length),
+                }));
+            }
+        }
+        Ok(())
+    }
+    ///Checks that every bracket is closed by a bracket of the same type
+    fn check_brackets(latex: &[char]) -> Result<(), ParseError> {
+        let mut open_brackets: Vec<(usize, char)> = vec![];
+        for (i, char) in latex.iter().enumerate() {
+            if BracketType::is_opening_bracket(*char) {
+                open_brackets.push((i, *char));
+            } else if BracketType::is_closing_bracket(*char) {
+                match open_brackets.pop() {
+                    Some((_, opening)) if BracketType::closing_bracket_of(opening) == *char => {}
+                    Some((_, opening)) => {
+                        return Err(ParseError::unexpected(i, *char)
+                            .expected(format!("`{}`", BracketType::closing_bracket_of(opening))))
+                    }
+                    None => return Err(ParseError::unexpected(i, *char)),
+                }
             }
         }
+        match open_brackets.pop() {
+            Some((i, opening)) => Err(ParseError::new(i..i + 1, format!("unclosed `{}`", opening))
+                .expected(format!("`{}`", BracketType::closing_bracket_of(opening)))),
+            None => Ok(()),
+        }
     }
     pub fn ir_to_latex(mut self, _implicit_multiplication: bool) -> Vec<char> {
         let name = self.name.clone();
@@ -475,6 +765,137 @@ impl IR {
                 }
                 panic!();
             }
+            ['\\', 'i', 'n', 't'] => {
+                let mut parameters = self
+                    .parameters
+                    .into_iter()
+                    .map(|parameter| parameter.0.ir_to_latex(_implicit_multiplication));
+                let (mut integrand, mut variable) =
+                    (parameters.next().unwrap(), parameters.next().unwrap());
+                let mut result = self.name;
+                if let (Some(mut lower), Some(mut upper)) = (parameters.next(), parameters.next()) {
+                    result.extend(['_', '{']);
+                    result.append(&mut lower);
+                    result.extend(['}', '^', '{']);
+                    result.append(&mut upper);
+                    result.push('}');
+                }
+                result.push('(');
+                result.append(&mut integrand);
+                result.extend([')', '\\', ',', 'd']);
+                result.append(&mut variable);
+                return result;
+            }
+            ['\\', 'l', 'i', 'm'] => {
+                let direction =
+                    (self.parameters.len() == 4).then(|| self.parameters.pop().unwrap().0.name);
+                let mut parameters = self
+                    .parameters
+                    .into_iter()
+                    .map(|parameter| parameter.0.ir_to_latex(_implicit_multiplication));
+                let (mut expression, mut variable, mut point) = (
+                    parameters.next().unwrap(),
+                    parameters.next().unwrap(),
+                    parameters.next().unwrap(),
+                );
+                let mut result = self.name;
+                result.extend(['_', '{']);
+                result.append(&mut variable);
+                result.extend("\\to ".chars());
+                result.append(&mut point);
+                if let Some(direction) = direction {
+                    result.extend(['^', '{']);
+                    result.extend(direction);
+                    result.push('}');
+                }
+                result.extend(['}', '(']);
+                result.append(&mut expression);
+                result.push(')');
+                return result;
+            }
+            ['\\', 'd', 'i', 'f', 'f'] | ['\\', 'p', 'a', 'r', 't', 'i', 'a', 'l']
+                if !self.parameters.is_empty() =>
+            {
+                let mut parameters = self
+                    .parameters
+                    .into_iter()
+                    .map(|parameter| parameter.0.ir_to_latex(_implicit_multiplication));
+                let mut expression = parameters.next().unwrap();
+                //Repeated variables are written as powers
+                let mut variables: Vec<(Vec<char>, usize)> = vec![];
+                for variable in parameters {
+                    match variables.last_mut() {
+                        Some((last, power)) if *last == variable => *power += 1,
+                        _ => variables.push((variable, 1)),
+                    }
+                }
+                let order = variables.iter().map(|(_, power)| power).sum::<usize>();
+                let operator = if name[1] == 'd' { "d" } else { "\\partial " };
+                let power = |power: usize| match power {
+                    1 => String::new(),
+                    _ => format!("^{{{}}}", power),
+                };
+                let mut result = format!("\\frac{{{}{}}}{{", operator.trim_end(), power(order))
+                    .chars()
+                    .collect::<Vec<_>>();
+                for (variable, variable_power) in variables {
+                    result.extend(operator.chars());
+                    result.extend(variable);
+                    result.extend(power(variable_power).chars());
+                }
+                result.extend(['}', '(']);
+                result.append(&mut expression);
+                result.push(')');
+                return result;
+            }
+            ['\\', 'c', 'd', 'o', 't'] | ['\\', 't', 'i', 'm', 'e', 's']
+                if self.parameters.len() == 2 =>
+            {
+                let mut result = vec![];
+                for (i, (parameter, bracket)) in self.parameters.into_iter().enumerate() {
+                    if i > 0 {
+                        result.extend(&name);
+                        result.push(' ');
+                    }
+                    result.push(bracket.opening_bracket());
+                    result.append(&mut parameter.ir_to_latex(_implicit_multiplication));
+                    result.push(bracket.closing_bracket());
+                }
+                return result;
+            }
+            ['\\', 'm', 'a', 't', 'r', 'i', 'x'] => {
+                let rows = self
+                    .parameters
+                    .into_iter()
+                    .map(|(row, _)| {
+                        row.parameters
+                            .into_iter()
+                            .map(|(entry, _)| {
+                                entry
+                                    .ir_to_latex(_implicit_multiplication)
+                                    .into_iter()
+                                    .collect::<String>()
+                            })
+                            .collect::<Vec<_>>()
+                            .join("&")
+                    })
+                    .collect::<Vec<_>>();
+                return format!("\\begin{{pmatrix}}{}\\end{{pmatrix}}", rows.join("\\\\"))
+                    .chars()
+                    .collect();
+            }
+            ['|'] | ['\\', 'n', 'o', 'r', 'm'] if self.parameters.len() == 1 => {
+                let mut result = "\\left|".chars().collect::<Vec<_>>();
+                result.append(
+                    &mut self
+                        .parameters
+                        .remove(0)
+                        .0
+                        .ir_to_latex(_implicit_multiplication),
+                );
+                result.extend("\\right|".chars());
+                return result;
+            }
             _ => {
                 if self.parameters.is_empty() {
                     return self.name;
@@ -482,7 +903,10 @@ impl IR {
                     let mut string = self.name.into_iter().collect::<Vec<_>>();
                     for parameter in self.parameters {
                         string.push(parameter.1.opening_bracket());
-                        string.append(&mut Self::ir_to_latex(parameter.0, _implicit_multiplication));
+                        string.append(&mut Self::ir_to_latex(
+                            parameter.0,
+                            _implicit_multiplication,
+                        ));
                         string.push(parameter.1.closing_bracket())
                     }
                     return string;
@@ -491,247 +915,303 @@ impl IR {
         }
         return_data
     }
-    pub fn ir_to_equation(mut self) -> Equation {
+    pub fn ir_to_equation(mut self) -> Result<Equation, ParseError> {
         let name = self.name.clone();
         match name[..] {
-            ['+'] => {
-                Equation::Addition(
-                    self.parameters
-                        .into_iter()
-                        .map(|param| param.0.ir_to_equation())
-                        .collect::<Vec<_>>(),
-                )
-            }
-            ['-'] => {
-                Equation::Addition(
-                    self.parameters
-                        .into_iter()
-                        .enumerate()
-                        .map(|(index, param)| {
-                            if index == 0 {
-                                param.0.ir_to_equation()
-                            } else {
-                                Equation::Negative(Box::new(param.0.ir_to_equation()))
-                            }
-                        })
-                        .collect::<Vec<_>>(),
-                )
-            }
-            ['*'] => {
-                Equation::Multiplication(
-                    self.parameters
-                        .into_iter()
-                        .map(|param| param.0.ir_to_equation())
-                        .collect::<Vec<_>>(),
-                )
+            ['+'] => Ok(Equation::Addition(self.into_equations()?)),
+            ['-'] => Ok(Equation::Addition(
+                self.into_equations()?
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, equation)| {
+                        if index == 0 {
+                            equation
+                        } else {
+                            Equation::Negative(Box::new(equation))
+                        }
+                    })
+                    .collect::<Vec<_>>(),
+            )),
+            ['*'] => Ok(dot_product(self.into_equations()?)),
+            ['×'] => {
+                let (left, right) = (self.next_equation()?, self.next_equation()?);
+                if left.is_vector() && right.is_vector() {
+                    Ok(Equation::Cross(Box::new((left, right))))
+                } else {
+                    Ok(dot_product(vec![left, right]))
+                }
             }
             ['/'] => {
-                if self.parameters.len() != 2 {
-                    let actual_division = Equation::Division(Box::new((
-                        self.parameters.remove(0).0.ir_to_equation(),
-                        self.parameters.remove(0).0.ir_to_equation(),
-                    )));
-                    let mut params = Vec::from([actual_division]);
-                    params.append(
-                        &mut self
-                            .parameters
-                            .into_iter()
-                            .map(|param| param.0.ir_to_equation())
-                            .collect::<Vec<_>>(),
-                    );
-                    Equation::Multiplication(params)
+                let actual_division =
+                    Equation::Division(Box::new((self.next_equation()?, self.next_equation()?)));
+                if self.parameters.is_empty() {
+                    Ok(actual_division)
                 } else {
-                    Equation::Division(Box::new((
-                        self.parameters.remove(0).0.ir_to_equation(),
-                        self.parameters.remove(0).0.ir_to_equation(),
-                    )))
+                    let mut params = Vec::from([actual_division]);
+                    params.append(&mut self.into_equations()?);
+                    Ok(Equation::Multiplication(params))
                 }
             }
             ['^'] => {
-                if self.parameters.len() != 2 {
-                    let actual_power = Equation::Power(Box::new((
-                        self.parameters.remove(0).0.ir_to_equation(),
-                        self.parameters.remove(0).0.ir_to_equation(),
-                    )));
-                    let mut params = Vec::from([actual_power]);
-                    params.append(
-                        &mut self
-                            .parameters
-                            .into_iter()
-                            .map(|param| param.0.ir_to_equation())
-                            .collect::<Vec<_>>(),
-                    );
-                    Equation::Multiplication(params)
+                let actual_power = match (self.next_equation()?, self.next_equation()?) {
+                    (Equation::Variable(Variable::Constant(Constant::E)), exponent) => {
+                        Equation::Exp(Box::new(exponent))
+                    }
+                    (base, exponent) => Equation::Power(Box::new((base, exponent))),
+                };
+                if self.parameters.is_empty() {
+                    Ok(actual_power)
                 } else {
-                    Equation::Power(Box::new((
-                        self.parameters.remove(0).0.ir_to_equation(),
-                        self.parameters.remove(0).0.ir_to_equation(),
-                    )))
+                    let mut params = Vec::from([actual_power]);
+                    params.append(&mut self.into_equations()?);
+                    Ok(Equation::Multiplication(params))
                 }
             }
-            ['='] => {
-                Equation::Equals(Box::new((
-                    self.parameters.remove(0).0.ir_to_equation(),
-                    self.parameters.remove(0).0.ir_to_equation(),
-                )))
-            }
+            ['='] => Ok(Equation::Equals(Box::new((
+                self.next_equation()?,
+                self.next_equation()?,
+            )))),
             ['s', 'q', 'r', 't'] => {
                 if self.parameters.len() == 1 {
-                    Equation::Power(Box::new((
-                        self.parameters.remove(0).0.ir_to_equation(),
+                    Ok(Equation::Power(Box::new((
+                        self.next_equation()?,
                         Equation::Variable(Variable::Rational(Rational64::new(1, 2))),
-                    )))
+                    ))))
                 } else {
-                    let sqrt = self.parameters.remove(0).0.ir_to_equation();
+                    let sqrt = self.next_equation()?;
                     let mut params = Vec::from([sqrt]);
-                    params.append(
-                        &mut self
-                            .parameters
-                            .into_iter()
-                            .map(|param| param.0.ir_to_equation())
-                            .collect::<Vec<_>>(),
-                    );
-                    Equation::Multiplication(params)
+                    params.append(&mut self.into_equations()?);
+                    Ok(Equation::Multiplication(params))
                 }
             }
-            ['s', 'i', 'n'] | ['c', 'o', 's'] | ['t', 'a', 'n'] | ['l', 'n'] | ['l', 'o', 'g'] => {
-                if self.parameters.len() == 1 {
-                    let param = self.parameters.remove(0).0.ir_to_equation();
-                    match name[..] {
-                        ['s', 'i', 'n'] => Equation::Sin(Box::new(param)),
-                        ['c', 'o', 's'] => Equation::Cos(Box::new(param)),
-                        ['l', 'n'] => Equation::Ln(Box::new(param)),
-                        ['l', 'o', 'g'] => {
-                            Equation::Division(Box::new((
-                                Equation::Ln(Box::new(param)),
-                                Equation::Ln(Box::new(Equation::Variable(Variable::Integer(10)))),
-                            )))
-                        }
-                        ['t', 'a', 'n'] => {
-                            Equation::Division(Box::new((
-                                Equation::Sin(Box::new(param.clone())),
-                                Equation::Cos(Box::new(param)),
-                            )))
-                        }
-                        _ => {
-                            panic!()
-                        }
-                    }
+            ['s', 'i', 'n']
+            | ['c', 'o', 's']
+            | ['t', 'a', 'n']
+            | ['a', 'r', 'c', 's', 'i', 'n']
+            | ['a', 'r', 'c', 'c', 'o', 's']
+            | ['a', 'r', 'c', 't', 'a', 'n']
+            | ['s', 'i', 'n', 'h']
+            | ['c', 'o', 's', 'h']
+            | ['t', 'a', 'n', 'h']
+            | ['l', 'n']
+            | ['l', 'o', 'g']
+            | ['e', 'x', 'p']
+            | ['a', 'b', 's'] => {
+                let param = self.next_equation()?;
+                let gonio = match name[..] {
+                    ['s', 'i', 'n'] => Equation::Sin(Box::new(param)),
+                    ['c', 'o', 's'] => Equation::Cos(Box::new(param)),
+                    ['t', 'a', 'n'] => Equation::Tan(Box::new(param)),
+                    ['a', 'r', 'c', 's', 'i', 'n'] => Equation::Arcsin(Box::new(param)),
+                    ['a', 'r', 'c', 'c', 'o', 's'] => Equation::Arccos(Box::new(param)),
+                    ['a', 'r', 'c', 't', 'a', 'n'] => Equation::Arctan(Box::new(param)),
+                    ['s', 'i', 'n', 'h'] => Equation::Sinh(Box::new(param)),
+                    ['c', 'o', 's', 'h'] => Equation::Cosh(Box::new(param)),
+                    ['t', 'a', 'n', 'h'] => Equation::Tanh(Box::new(param)),
+                    ['l', 'n'] => Equation::Ln(Box::new(param)),
+                    ['e', 'x', 'p'] => Equation::Exp(Box::new(param)),
+                    ['a', 'b', 's'] => Equation::Abs(Box::new(param)),
+                    ['l', 'o', 'g'] => Equation::Division(Box::new((
+                        Equation::Ln(Box::new(param)),
+                        Equation::Ln(Box::new(Equation::Variable(Variable::Integer(10)))),
+                    ))),
+                    _ => unreachable!(),
+                };
+                if self.parameters.is_empty() {
+                    Ok(gonio)
                 } else {
-                    let param = self.parameters.remove(0).0.ir_to_equation();
-                    let gonio = match name[..] {
-                        ['s', 'i', 'n'] => Equation::Sin(Box::new(param)),
-                        ['c', 'o', 's'] => Equation::Cos(Box::new(param)),
-                        ['t', 'a', 'n'] => Equation::Division(Box::new((
-                            Equation::Sin(Box::new(param.clone())),
-                            Equation::Cos(Box::new(param)),
-                        ))),
-                        ['l', 'n'] => Equation::Ln(Box::new(param)),
-                        ['l', 'o', 'g'] => Equation::Division(Box::new((
-                            param,
-                            Equation::Ln(Box::new(Equation::Variable(Variable::Integer(10)))),
-                        ))),
-                        _ => {
-                            panic!()
-                        }
-                    };
                     let mut params = Vec::from([gonio]);
-                    params.append(
-                        &mut self
-                            .parameters
-                            .into_iter()
-                            .map(|param| param.0.ir_to_equation())
-                            .collect::<Vec<_>>(),
-                    );
-                    Equation::Multiplication(params)
+                    params.append(&mut self.into_equations()?);
+                    Ok(Equation::Multiplication(params))
                 }
             }
-            ['\\', 'i', 'n', 'v'] => {
-                Equation::Negative(Box::new(self.parameters.remove(0).0.ir_to_equation()))
-            }
-            _ => {
+            ['\\', 'i', 'n', 'v'] => Ok(Equation::Negative(Box::new(self.next_equation()?))),
+            ['v', 'e', 'c'] => {
+                let Equation::Variable(Variable::Letter(name)) = self.next_equation()? else {
+                    return Err(ParseError::new(0..0, "vector name is not a letter"));
+                };
+                let vector = Equation::Variable(Variable::Vector(name));
                 if self.parameters.is_empty() {
-                    let is_int = self.name.iter().all(|char| char.is_numeric());
-                    let is_float = self
-                        .name
-                        .iter()
-                        .all(|char| char.is_numeric() || char == &'.');
-
-                    return if is_int {
-                        let expression = self.name.into_iter().collect::<String>();
-                        Equation::Variable(Variable::Integer(expression.parse::<i64>().unwrap()))
-                    } else if is_float {
-                        Self::parse_float(self.name)
-                    } else {
-                        let expression = self.name.into_iter().collect::<String>();
-                        match expression.as_str() {
-                            "e" => Equation::Variable(Variable::Constant(Constant::E)),
-                            "\\pi" => Equation::Variable(Variable::Constant(Constant::PI)),
-                            _ => Equation::Variable(Variable::Letter(expression)),
-                        }
-                    };
+                    Ok(vector)
+                } else {
+                    let mut params = vec![vector];
+                    params.append(&mut self.into_equations()?);
+                    Ok(dot_product(params))
+                }
+            }
+            ['i', 'n', 't'] => {
+                let integrand = self.next_equation()?;
+                let Equation::Variable(variable) = self.next_equation()? else {
+                    return Err(ParseError::new(
+                        0..0,
+                        "integration variable is not a variable",
+                    ));
+                };
+                let bounds = if self.parameters.is_empty() {
+                    None
                 } else {
-                    todo!();
+                    Some((self.next_equation()?, self.next_equation()?))
+                };
+                Ok(Equation::Integral(Box::new(Integral {
+                    integrand,
+                    variable,
+                    bounds,
+                })))
+            }
+            ['l', 'i', 'm'] => {
+                let expression = self.next_equation()?;
+                let Equation::Variable(variable) = self.next_equation()? else {
+                    return Err(ParseError::new(0..0, "limit variable is not a variable"));
+                };
+                let point = self.next_equation()?;
+                let direction = match self
+                    .parameters
+                    .first()
+                    .map(|parameter| &parameter.0.name[..])
+                {
+                    Some(['+']) => LimitDirection::Right,
+                    Some(['-']) => LimitDirection::Left,
+                    _ => LimitDirection::Both,
+                };
+                Ok(Equation::Limit(Box::new(Limit {
+                    expression,
+                    variable,
+                    point,
+                    direction,
+                })))
+            }
+            ['d', 'i', 'f', 'f'] => {
+                let expression = self.next_equation()?;
+                let variables = self
+                    .into_equations()?
+                    .into_iter()
+                    .map(|variable| match variable {
+                        Equation::Variable(variable) => Ok(variable),
+                        _ => Err(ParseError::new(
+                            0..0,
+                            "differentiation variable is not a variable",
+                        )),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Equation::Derivative(Box::new(Derivative {
+                    expression,
+                    variables,
+                })))
+            }
+            ['m', 'a', 't', 'r', 'i', 'x'] => {
+                //The rows were checked by check_matrix when the IR was made
+                let rows = self
+                    .parameters
+                    .into_iter()
+                    .map(|(row, _)| row.into_equations())
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Equation::Matrix(rows))
+            }
+            _ => {
+                if !self.parameters.is_empty() {
+                    return Err(ParseError::new(
+                        0..0,
+                        format!("unknown command `{}`", name.iter().collect::<String>()),
+                    ));
                 }
+                if self
+                    .name
+                    .iter()
+                    .all(|char| char.is_numeric() || char == &'.')
+                {
+                    return Self::parse_number(&self.name).ok_or_else(|| {
+                        ParseError::new(
+                            0..self.name.len(),
+                            format!("invalid number `{}`", name.iter().collect::<String>()),
+                        )
+                    });
+                }
+                let expression = self.name.into_iter().collect::<String>();
+                Ok(match expression.as_str() {
+                    "e" => Equation::Variable(Variable::Constant(Constant::E)),
+                    "\\pi" => Equation::Variable(Variable::Constant(Constant::PI)),
+                    "\\infty" => Equation::Variable(Variable::Constant(Constant::Infinity)),
+                    _ => Equation::Variable(Variable::Letter(expression)),
+                })
             }
         }
     }
+    ///Converts the next parameter into an equation
+    fn next_equation(&mut self) -> Result<Equation, ParseError> {
+        if self.parameters.is_empty() {
+            return Err(ParseError::new(
+                0..0,
+                format!(
+                    "missing parameter for `{}`",
+                    self.name.iter().collect::<String>()
+                ),
+            ));
+        }
+        self.parameters.remove(0).0.ir_to_equation()
+    }
+    ///Converts all remaining parameters into equations
+    fn into_equations(self) -> Result<Vec<Equation>, ParseError> {
+        self.parameters
+            .into_iter()
+            .map(|param| param.0.ir_to_equation())
+            .collect()
+    }
     pub fn equation_to_ir(equation: Equation) -> Self {
         match equation {
-            Equation::Variable(variable) => {
-                return match variable {
-                    Variable::Letter(letter) => IR {
-                        name: letter.chars().collect::<Vec<char>>(),
+            Equation::Variable(variable) => match variable {
+                Variable::Letter(letter) => IR {
+                    name: letter.chars().collect::<Vec<char>>(),
+                    parameters: vec![],
+                },
+                Variable::Integer(integer) => IR {
+                    name: integer.to_string().chars().collect::<Vec<char>>(),
+                    parameters: vec![],
+                },
+                Variable::Vector(vector) => IR {
+                    name: format!("\\vec{{{}}}", vector)
+                        .chars()
+                        .collect::<Vec<char>>(),
+                    parameters: vec![],
+                },
+                Variable::Rational(ratio) => IR {
+                    name: vec!['\\', 'f', 'r', 'a', 'c'],
+                    parameters: vec![
+                        (
+                            Self::equation_to_ir(Equation::Variable(Variable::Integer(
+                                *ratio.numer(),
+                            ))),
+                            BracketType::Curly,
+                        ),
+                        (
+                            Self::equation_to_ir(Equation::Variable(Variable::Integer(
+                                *ratio.denom(),
+                            ))),
+                            BracketType::Curly,
+                        ),
+                    ],
+                },
+                Variable::Constant(constant) => match constant {
+                    Constant::PI => IR {
+                        name: vec!['\\', 'p', 'i'],
                         parameters: vec![],
                     },
-                    Variable::Integer(integer) => IR {
-                        name: integer.to_string().chars().collect::<Vec<char>>(),
+                    Constant::E => IR {
+                        name: vec!['e'],
                         parameters: vec![],
                     },
-                    Variable::Vector(vector) => IR {
-                        name: format!("\\vec{{{}}}", vector)
-                            .chars()
-                            .collect::<Vec<char>>(),
+                    Constant::Infinity => IR {
+                        name: "\\infty".chars().collect(),
                         parameters: vec![],
                     },
-                    Variable::Rational(ratio) => IR {
-                        name: vec!['\\', 'f', 'r', 'a', 'c'],
-                        parameters: vec![
-                            (
-                                Self::equation_to_ir(Equation::Variable(Variable::Integer(
-                                    *ratio.numer(),
-                                ))),
-                                BracketType::Curly,
-                            ),
-                            (
-                                Self::equation_to_ir(Equation::Variable(Variable::Integer(
-                                    *ratio.denom(),
-                                ))),
-                                BracketType::Curly,
-                            ),
-                        ],
-                    },
-                    Variable::Constant(constant) => match constant {
-                        Constant::PI => IR {
-                            name: vec!['\\', 'p', 'i'],
-                            parameters: vec![],
-                        },
-                        Constant::E => IR {
-                            name: vec!['e'],
-                            parameters: vec![],
-                        },
-                    },
-                }
-            }
-            Equation::Multiplication(eq) => {
-                IR {
-                    name: vec!['*'],
-                    parameters: eq
-                        .into_iter()
-                        .map(|subeq| (Self::equation_to_ir(subeq), BracketType::Round))
-                        .collect(),
-                }
-            }
+                },
+            },
+            Equation::Multiplication(eq) => IR {
+                name: vec!['*'],
+                parameters: eq
+                    .into_iter()
+                    .map(|subeq| (Self::equation_to_ir(subeq), BracketType::Round))
+                    .collect(),
+            },
             Equation::Power(data) => {
                 let (lower, upper) = *data;
                 IR {
@@ -742,15 +1222,13 @@ impl IR {
                     ],
                 }
             }
-            Equation::Addition(eqs) => {
-                IR {
-                    name: vec!['+'],
-                    parameters: eqs
-                        .into_iter()
-                        .map(|eq| (Self::equation_to_ir(eq), BracketType::Round))
-                        .collect(),
-                }
-            }
+            Equation::Addition(eqs) => IR {
+                name: vec!['+'],
+                parameters: eqs
+                    .into_iter()
+                    .map(|eq| (Self::equation_to_ir(eq), BracketType::Round))
+                    .collect(),
+            },
             Equation::Division(div) => {
                 let (lhs, rhs) = *div;
                 IR {
@@ -761,28 +1239,176 @@ impl IR {
                     ],
                 }
             }
-            Equation::Cos(cos) => {
-                IR {
-                    name: vec!['\\', 'c', 'o', 's'],
-                    parameters: vec![(Self::equation_to_ir(*cos), BracketType::Round)],
+            Equation::Cos(cos) => IR {
+                name: vec!['\\', 'c', 'o', 's'],
+                parameters: vec![(Self::equation_to_ir(*cos), BracketType::Round)],
+            },
+            Equation::Sin(sin) => IR {
+                name: vec!['\\', 's', 'i', 'n'],
+                parameters: vec![(Self::equation_to_ir(*sin), BracketType::Round)],
+            },
+            Equation::Negative(core) => IR {
+                name: vec!['\\', 'i', 'n', 'v'],
+                parameters: vec![(Self::equation_to_ir(*core), BracketType::Round)],
+            },
+            Equation::Ln(core) => IR {
+                name: vec!['\\', 'l', 'n'],
+                parameters: vec![(Self::equation_to_ir(*core), BracketType::Round)],
+            },
+            Equation::Exp(exp) => IR {
+                name: vec!['^'],
+                parameters: vec![
+                    (
+                        Self::equation_to_ir(Equation::Variable(Variable::Constant(Constant::E))),
+                        BracketType::Round,
+                    ),
+                    (Self::equation_to_ir(*exp), BracketType::Curly),
+                ],
+            },
+            Equation::Abs(abs) => IR {
+                //The norm of a vector is written the same, but is not the absolute value in numpy
+                name: if abs.is_vector() {
+                    "\\norm".chars().collect()
+                } else {
+                    vec!['|']
+                },
+                parameters: vec![(Self::equation_to_ir(*abs), BracketType::None)],
+            },
+            Equation::Matrix(rows) => IR {
+                name: "\\matrix".chars().collect(),
+                parameters: rows
+                    .into_iter()
+                    .map(|row| {
+                        let row = IR {
+                            name: "\\row".chars().collect(),
+                            parameters: row
+                                .into_iter()
+                                .map(|entry| (Self::equation_to_ir(entry), BracketType::None))
+                                .collect(),
+                        };
+                        (row, BracketType::None)
+                    })
+                    .collect(),
+            },
+            Equation::Dot(dot) => IR {
+                name: "\\cdot".chars().collect(),
+                parameters: vec![
+                    (Self::equation_to_ir(dot.0), BracketType::Round),
+                    (Self::equation_to_ir(dot.1), BracketType::Round),
+                ],
+            },
+            Equation::Cross(cross) => IR {
+                name: "\\times".chars().collect(),
+                parameters: vec![
+                    (Self::equation_to_ir(cross.0), BracketType::Round),
+                    (Self::equation_to_ir(cross.1), BracketType::Round),
+                ],
+            },
+            Equation::Arcsin(arcsin) => IR {
+                name: "\\arcsin".chars().collect(),
+                parameters: vec![(Self::equation_to_ir(*arcsin), BracketType::Round)],
+            },
+            Equation::Arccos(arccos) => IR {
+                name: "\\arccos".chars().collect(),
+                parameters: vec![(Self::equation_to_ir(*arccos), BracketType::Round)],
+            },
+            Equation::Tan(tan) => IR {
+                name: "\\tan".chars().collect(),
+                parameters: vec![(Self::equation_to_ir(*tan), BracketType::Round)],
+            },
+            Equation::Arctan(arctan) => IR {
+                name: "\\arctan".chars().collect(),
+                parameters: vec![(Self::equation_to_ir(*arctan), BracketType::Round)],
+            },
+            Equation::Sinh(sinh) => IR {
+                name: "\\sinh".chars().collect(),
+                parameters: vec![(Self::equation_to_ir(*sinh), BracketType::Round)],
+            },
+            Equation::Cosh(cosh) => IR {
+                name: "\\cosh".chars().collect(),
+                parameters: vec![(Self::equation_to_ir(*cosh), BracketType::Round)],
+            },
+            Equation::Tanh(tanh) => IR {
+                name: "\\tanh".chars().collect(),
+                parameters: vec![(Self::equation_to_ir(*tanh), BracketType::Round)],
+            },
+            Equation::Integral(integral) => {
+                let Integral {
+                    integrand,
+                    variable,
+                    bounds,
+                } = *integral;
+                let mut parameters = vec![
+                    (Self::equation_to_ir(integrand), BracketType::None),
+                    (
+                        Self::equation_to_ir(Equation::Variable(variable)),
+                        BracketType::None,
+                    ),
+                ];
+                if let Some((lower, upper)) = bounds {
+                    parameters.push((Self::equation_to_ir(lower), BracketType::None));
+                    parameters.push((Self::equation_to_ir(upper), BracketType::None));
                 }
-            }
-            Equation::Sin(sin) => {
                 IR {
-                    name: vec!['\\', 's', 'i', 'n'],
-                    parameters: vec![(Self::equation_to_ir(*sin), BracketType::Round)],
+                    name: "\\int".chars().collect(),
+                    parameters,
                 }
             }
-            Equation::Negative(core) => {
+            Equation::Limit(limit) => {
+                let Limit {
+                    expression,
+                    variable,
+                    point,
+                    direction,
+                } = *limit;
+                let mut parameters = vec![
+                    (Self::equation_to_ir(expression), BracketType::None),
+                    (
+                        Self::equation_to_ir(Equation::Variable(variable)),
+                        BracketType::None,
+                    ),
+                    (Self::equation_to_ir(point), BracketType::None),
+                ];
+                let direction = match direction {
+                    LimitDirection::Both => None,
+                    LimitDirection::Left => Some('-'),
+                    LimitDirection::Right => Some('+'),
+                };
+                if let Some(direction) = direction {
+                    parameters.push((
+                        IR {
+                            name: vec![direction],
+                            parameters: vec![],
+                        },
+                        BracketType::None,
+                    ));
+                }
                 IR {
-                    name: vec!['\\', 'i', 'n', 'v'],
-                    parameters: vec![(Self::equation_to_ir(*core), BracketType::Round)],
+                    name: "\\lim".chars().collect(),
+                    parameters,
                 }
             }
-            Equation::Ln(core) => {
+            Equation::Derivative(derivative) => {
+                let Derivative {
+                    expression,
+                    variables,
+                } = *derivative;
+                //Partial derivatives are written with \partial, ordinary ones with d
+                let name = if expression.letters().len() > 1 {
+                    "\\partial"
+                } else {
+                    "\\diff"
+                };
+                let mut parameters = vec![(Self::equation_to_ir(expression), BracketType::None)];
+                for variable in variables {
+                    parameters.push((
+                        Self::equation_to_ir(Equation::Variable(variable)),
+                        BracketType::None,
+                    ));
+                }
                 IR {
-                    name: vec!['\\', 'l', 'n'],
-                    parameters: vec![(Self::equation_to_ir(*core), BracketType::Round)],
+                    name: name.chars().collect(),
+                    parameters,
                 }
             }
             Equation::Equals(core) => {
@@ -795,9 +1421,6 @@ impl IR {
                     ],
                 }
             }
-            _ => {
-                todo!()
-            }
         }
     }
     ///Checks for the operators within the latex with the highest priority in the top level
@@ -825,7 +1448,7 @@ impl IR {
                             additions_and_subtractions.push(i);
                         }
                     }
-                    '*' | '/' => {
+                    '*' | '/' | '×' => {
                         multiplications_and_divisions.push(i);
                     }
                     '^' => {
@@ -885,7 +1508,6 @@ impl IR {
         } else {
             chars_until_command_start.into_iter().collect::<String>()
         };
-        println!("{}", command);
         if &*command == "int" {
             return false;
         }
@@ -897,6 +1519,9 @@ impl IR {
         caret: usize,
         implicit_multiplication: bool,
     ) -> bool {
+        if caret == 0 {
+            return true;
+        }
         let mut i = caret - 1;
         while i > 0 {
             if latex[i] == '^' {
@@ -919,7 +1544,7 @@ impl IR {
         part: Vec<char>,
         implicit_multiplication: bool,
     ) -> bool {
-        if Self::calculate_depth_difference(&part) != 0 {
+        if part.is_empty() || Self::calculate_depth_difference(&part) != 0 {
             return false;
         } else if BracketType::is_opening_bracket(part[0])
             && BracketType::is_closing_bracket(part[0])
@@ -939,72 +1564,75 @@ impl IR {
     //Requires latex to start with either _ or ^, otherwise, will return only None
     pub fn get_super_and_subscript(
         latex: &mut Vec<char>,
-        implicit_multiplication: bool,
-    ) -> (Option<Vec<char>>, Option<Vec<char>>) {
+        offset: &mut usize,
+    ) -> Result<Scripts, ParseError> {
         let (mut superscript, mut subscript) = (None, None);
-        for _ in 0..1 {
-            match latex[0] {
-                '_' => {
-                    latex.remove(0);
-                    //let no_brackets = latex[0] != '{';
-                    let no_brackets = !BracketType::is_opening_bracket(latex[0]);
-                    let mut depth = if no_brackets { 1 } else { 0 };
-                    if BracketType::is_opening_bracket(latex[0]) {
-                        latex.remove(0);
-                    }
-                    let mut subscript_buffer = vec![];
-                    if !no_brackets {
-                        while depth > 0 {
-                            let next = latex.remove(0);
-                            if BracketType::is_opening_bracket(next) {
-                                depth += 1;
-                            } else if BracketType::is_closing_bracket(next) {
-                                depth -= 1;
-                            }
-                            if depth != 0 || no_brackets {
-                                subscript_buffer.push(next);
-                            } else {
-                                break;
-                            }
-                        }
-                    } else if !implicit_multiplication {
-                        subscript_buffer.push(latex.remove(0));
-                    } else {
-                        todo!() //No brackets but implicit multiplication
-                    }
-                    subscript = Some(subscript_buffer);
-                }
-                '^' => {
-                    latex.remove(0);
-                    let no_brackets = BracketType::is_opening_bracket(latex[0]);
-                    let mut depth = if no_brackets { 1 } else { 0 };
-                    if BracketType::is_opening_bracket(latex[0]) {
-                        latex.remove(0);
-                    }
-                    let mut superscript_buffer = vec![];
-                    if !no_brackets {
-                        while depth > 0 {
-                            let next = latex.remove(0);
-                            if BracketType::is_opening_bracket(next) {
-                                depth += 1;
-                            } else if BracketType::is_closing_bracket(next) {
-                                depth -= 1;
-                            }
-                            if depth != 0 || no_brackets {
-                                superscript_buffer.push(next);
-                            } else {
-                                break;
-                            }
-                        }
-                    } else {
-                        todo!() //NOBRACKETS
-                    }
-                    superscript = Some(superscript_buffer);
+        for _ in 0..2 {
+            let script = match latex.first() {
+                Some('_') if subscript.is_none() => &mut subscript,
+                Some('^') if superscript.is_none() => &mut superscript,
+                _ => break,
+            };
+            latex.remove(0);
+            *offset += 1;
+            *script = Some(Self::take_script(latex, offset)?);
+        }
+        Ok((superscript, subscript))
+    }
+    ///Takes a script, which is either a group in brackets, a single command or a single character
+    fn take_script(latex: &mut Vec<char>, offset: &mut usize) -> Result<Vec<char>, ParseError> {
+        match latex.first() {
+            Some(&bracket) if BracketType::is_opening_bracket(bracket) => {
+                Self::take_group(latex, offset)
+            }
+            Some(&'\\') => {
+                let mut command = vec![latex.remove(0)];
+                while latex.first().is_some_and(|c| c.is_alphabetic()) {
+                    command.push(latex.remove(0));
                 }
-                _ => {}
+                *offset += command.len();
+                Ok(command)
+            }
+            Some(&character) if !BracketType::is_closing_bracket(character) => {
+                *offset += 1;
+                Ok(vec![latex.remove(0)])
+            }
+            Some(&character) => Err(ParseError::unexpected(*offset, character)),
+            None => Err(ParseError::new(*offset..*offset, "missing script").expected("a script")),
+        }
+    }
+    ///Takes a group in brackets from the start of the latex and returns its contents without the brackets
+    fn take_group(latex: &mut Vec<char>, offset: &mut usize) -> Result<Vec<char>, ParseError> {
+        match latex.first() {
+            Some(&bracket) if BracketType::is_opening_bracket(bracket) => {}
+            Some(&character) => {
+                return Err(ParseError::unexpected(*offset, character).expected("`{`"));
+            }
+            None => {
+                return Err(ParseError::new(*offset..*offset, "missing parameter").expected("`{`"));
             }
         }
-        (superscript, subscript)
+        let opening = latex.remove(0);
+        let mut group = vec![];
+        let mut depth = 1;
+        while depth > 0 {
+            let Some(next) = latex.first() else {
+                return Err(ParseError::new(
+                    *offset..*offset + 1,
+                    format!("unclosed `{}`", opening),
+                )
+                .expected(format!("`{}`", BracketType::closing_bracket_of(opening))));
+            };
+            if BracketType::is_opening_bracket(*next) {
+                depth += 1;
+            } else if BracketType::is_closing_bracket(*next) {
+                depth -= 1;
+            }
+            group.push(latex.remove(0));
+        }
+        group.pop();
+        *offset += group.len() + 2;
+        Ok(group)
     }
     pub fn calculate_depth_difference(latex: &[char]) -> i32 {
         let mut depth_diff = 0;
@@ -1018,32 +1646,33 @@ impl IR {
         }
         depth_diff
     }
-    ///Get the first parameter (brackets required)
-    pub fn get_first_parameter(latex: &mut Vec<char>, implicit_multiplication: bool) -> Self {
-        let _bracket_type = BracketType::get_opening_bracket_type(latex.remove(0));
-        let mut parameter = vec![];
-        let mut depth = 1;
-        while depth > 0 {
-            if BracketType::is_opening_bracket(latex[0]) {
-                depth += 1;
-            } else if BracketType::is_closing_bracket(latex[0]) {
-                depth -= 1;
-            }
-            parameter.push(latex.remove(0));
-        }
-        parameter.remove(parameter.len() - 1);
-        Self::latex_to_ir(parameter, implicit_multiplication)
+    ///Get the first parameter (brackets required), offset is moved past the parameter
+    pub fn get_first_parameter(
+        latex: &mut Vec<char>,
+        offset: &mut usize,
+        implicit_multiplication: bool,
+    ) -> Result<Self, ParseError> {
+        let parameter_offset = *offset + 1;
+        let parameter = Self::take_group(latex, offset)?;
+        Self::latex_to_ir_at(parameter, parameter_offset, implicit_multiplication)
     }
-    pub fn parse_float(float: Vec<char>) -> Equation {
-        let period_pos = float.iter().position(|c| c == &'.').unwrap();
-        let (int, dec) = float.split_at(period_pos);
-        let int: String = int.iter().collect();
-        let mut dec: String = dec.iter().collect();
-        dec.remove(0);
-        let denominator = 10i64.pow(dec.len() as u32);
-        let nominator: i64 =
-            int.parse::<i64>().unwrap() * denominator + dec.parse::<i64>().unwrap();
-        Equation::Variable(Variable::Rational(Rational64::new(nominator, denominator)))
+    ///Parses an integer or a decimal number, returns None if it is malformed or too large
+    pub fn parse_number(number: &[char]) -> Option<Equation> {
+        let number: String = number.iter().collect();
+        let Some((int, dec)) = number.split_once('.') else {
+            return Some(Equation::Variable(Variable::Integer(number.parse().ok()?)));
+        };
+        if int.is_empty() && dec.is_empty() {
+            return None;
+        }
+        let int: i64 = if int.is_empty() { 0 } else { int.parse().ok()? };
+        let decimals: i64 = if dec.is_empty() { 0 } else { dec.parse().ok()? };
+        let denominator = 10i64.checked_pow(dec.len() as u32)?;
+        let nominator = int.checked_mul(denominator)?.checked_add(decimals)?;
+        Some(Equation::Variable(Variable::Rational(Rational64::new(
+            nominator,
+            denominator,
+        ))))
     }
     pub fn first_and_last_bracket_connected(latex: &[char]) -> bool {
         let mut depth = 1;
@@ -1071,7 +1700,7 @@ impl IR {
     }
     pub fn split_on_brackets(latex: Vec<char>) -> (Vec<char>, Vec<char>) {
         let mut depth = 1;
-        for i in 1..(latex.len() - 1) {
+        for i in 1..latex.len() {
             if BracketType::is_opening_bracket(latex[i]) {
                 depth += 1
             } else if BracketType::is_closing_bracket(latex[i]) {
@@ -1083,7 +1712,7 @@ impl IR {
                 return (lhs, rhs);
             }
         }
-        panic!()
+        (latex, vec![])
     }
 }
 pub enum BracketType {
@@ -1118,6 +1747,9 @@ impl BracketType {
     pub fn is_closing_bracket(char: char) -> bool {
         char == '}' || char == ']' || char == ')' || char == '⟩'
     }
+    pub fn closing_bracket_of(opening: char) -> char {
+        Self::get_opening_bracket_type(opening).closing_bracket()
+    }
     pub fn get_opening_bracket_type(char: char) -> Self {
         match char {
             '(' => BracketType::Round,
@@ -1143,28 +1775,963 @@ impl TopLevelOperators {
     }
 }
 pub fn cleanup_latex(latex: Vec<char>) -> Vec<char> {
-    return latex
-        .into_iter()
+    cleanup(&latex).0
+}
+///Cleans up the latex like cleanup_latex, also returning the position in the input of every remaining character, an error if a | is not closed
+pub fn cleanup_latex_with_positions(latex: &[char]) -> Result<(Vec<char>, Vec<usize>), ParseError> {
+    match cleanup(latex) {
+        (_, _, Some(bar)) => Err(ParseError::new(bar..bar + 1, "unclosed `|`").expected("`|`")),
+        (cleaned, positions, None) => Ok((cleaned, positions)),
+    }
+}
+///Cleans up the latex, returning the position in the input of every remaining character and of the innermost | that is not closed
+fn cleanup(latex: &[char]) -> (Vec<char>, Vec<usize>, Option<usize>) {
+    let (mut cleaned, mut positions, mut open_bars) = (vec![], vec![], vec![]);
+    let (mut i, mut matrix_depth) = (0, 0);
+    while i < latex.len() {
+        let rest = &latex[i..];
+        if rest.starts_with(&['\\', 'c', 'd', 'o', 't']) {
+            cleaned.push('*');
+            positions.push(i);
+            i += 5;
+        } else if is_command_at(latex, i, "times") {
+            cleaned.push('×');
+            positions.push(i);
+            i += 6;
+        } else if let Some(environment) = matrix_environment(latex, i, "begin") {
+            cleaned.extend("\\matrix{\\row{".chars());
+            positions.extend([i; 13]);
+            matrix_depth += 1;
+            i += environment;
+        } else if let Some(environment) =
+            matrix_environment(latex, i, "end").filter(|_| matrix_depth > 0)
+        {
+            cleaned.extend(['}', '}']);
+            positions.extend([i; 2]);
+            matrix_depth -= 1;
+            i += environment;
+        } else if matrix_depth > 0 && latex[i] == '&' {
+            cleaned.extend(['}', '{']);
+            positions.extend([i; 2]);
+            i += 1;
+        } else if matrix_depth > 0 && rest.starts_with(&['\\', '\\']) {
+            //A row separator directly before the end of the matrix does not start a new row
+            if matrix_environment(latex, skip_spaces(latex, i + 2), "end").is_none() {
+                cleaned.extend("}}{\\row{".chars());
+                positions.extend([i; 9]);
+            }
+            i += 2;
+        } else if latex[i] == '|' {
+            //A bar opens an absolute value unless it can close the innermost one, which it can after an operand
+            let closes = !open_bars.is_empty()
+                && !matches!(
+                    cleaned.last(),
+                    Some('+' | '-' | '*' | '/' | '×' | '=' | '^' | '_' | '(' | '[' | '{')
+                );
+            if closes {
+                cleaned.push('}');
+                open_bars.pop();
+            } else {
+                cleaned.extend("\\abs{".chars());
+                positions.extend([i; 4]);
+                open_bars.push(i);
+            }
+            positions.push(i);
+            i += 1;
+        } else if rest.starts_with(&['\\', 'l', 'e', 'f', 't']) {
+            i += 5;
+        } else if rest.starts_with(&['\\', 'r', 'i', 'g', 'h', 't']) {
+            i += 6;
+        } else if rest.len() > 1 && rest[0] == '\\' && matches!(rest[1], ',' | ';' | ':' | '!') {
+            i += 2;
+        } else {
+            if latex[i] != ' ' {
+                cleaned.push(latex[i]);
+                positions.push(i);
+            }
+            i += 1;
+        }
+    }
+    (cleaned, positions, open_bars.pop())
+}
+///Wraps the body and the variable of every integral in brackets, so \int_a^b f(x) dx becomes \int_a^b{f(x)}{x}, also returning the position in the input of every character
+fn group_integrals(latex: &[char], offset: usize) -> Result<(Vec<char>, Vec<usize>), ParseError> {
+    let (mut grouped, mut positions) = (vec![], vec![]);
+    let mut i = 0;
+    while i < latex.len() {
+        if !is_command_at(latex, i, "int") {
+            grouped.push(latex[i]);
+            positions.push(offset + i);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i = skip_scripts(latex, i + 4);
+        grouped.extend(&latex[start..i]);
+        positions.extend(offset + start..offset + i);
+        //A letter directly before the d is only allowed if there is no other candidate, as in \int xdx
+        let (body_end, variable) = find_differential(latex, i, true)
+            .or_else(|| find_differential(latex, i, false))
+            .ok_or_else(|| {
+                ParseError::new(
+                    offset + start..offset + start + 4,
+                    "missing integration variable",
+                )
+                .expected("`dx`")
+            })?;
+        let (mut body, mut body_positions) = group_integrals(&latex[i..body_end], offset + i)?;
+        //Nothing but spacing before the d, as in \int_0^1 dx, integrates 1
+        if cleanup_latex(body.clone()).is_empty() {
+            (body, body_positions) = (vec!['1'], vec![offset + i]);
+        }
+        grouped.push('{');
+        positions.push(offset + i);
+        grouped.append(&mut body);
+        positions.append(&mut body_positions);
+        grouped.extend(['}', '{']);
+        positions.extend([offset + body_end, offset + variable.start]);
+        grouped.extend(&latex[variable.clone()]);
+        positions.extend(offset + variable.start..offset + variable.end);
+        grouped.push('}');
+        positions.push(offset + variable.end - 1);
+        i = variable.end;
+    }
+    Ok((grouped, positions))
+}
+///Moves the variable and point of every limit out of its subscript and wraps its body, which ends at the next +, - or = outside of brackets, so \lim_{x\to a^+} f(x) becomes \lim{f(x)}{x}{a}{+}, also returning the position in the input of every character
+fn group_limits(latex: &[char], offset: usize) -> Result<(Vec<char>, Vec<usize>), ParseError> {
+    let (mut grouped, mut positions) = (vec![], vec![]);
+    let mut i = 0;
+    while i < latex.len() {
+        if !is_command_at(latex, i, "lim") {
+            grouped.push(latex[i]);
+            positions.push(offset + i);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let missing_point = || {
+            ParseError::new(offset + start..offset + start + 4, "limit without a point")
+                .expected("`_{x\\to a}`")
+        };
+        i += 4;
+        while latex.get(i) == Some(&' ') {
+            i += 1;
+        }
+        if latex.get(i) != Some(&'_') {
+            return Err(missing_point());
+        }
+        i += 1;
+        while latex.get(i) == Some(&' ') {
+            i += 1;
+        }
+        if latex.get(i) != Some(&'{') {
+            return Err(missing_point());
+        }
+        let subscript_start = i + 1;
+        let mut depth = 0;
+        let mut arrow = None;
+        loop {
+            let Some(&character) = latex.get(i) else {
+                return Err(missing_point());
+            };
+            if BracketType::is_opening_bracket(character) {
+                depth += 1;
+            } else if BracketType::is_closing_bracket(character) {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            } else if depth == 1 && arrow.is_none() {
+                for command in ["to", "rightarrow"] {
+                    if is_command_at(latex, i, command) {
+                        arrow = Some((i, i + 1 + command.len()));
+                    }
+                }
+            }
+            i += 1;
+        }
+        let Some((arrow_start, arrow_end)) = arrow else {
+            return Err(missing_point());
+        };
+        let without_spaces = |range: std::ops::Range<usize>| {
+            range
+                .filter(|&k| latex[k] != ' ')
+                .map(|k| (latex[k], offset + k))
+                .collect::<Vec<_>>()
+        };
+        let variable = without_spaces(subscript_start..arrow_start);
+        let mut point = without_spaces(arrow_end..i);
+        if point.is_empty() {
+            return Err(missing_point());
+        }
+        let point_characters = point.iter().map(|(c, _)| *c).collect::<String>();
+        let mut direction = None;
+        for (sign, suffixes) in [('+', ["^+", "^{+}"]), ('-', ["^-", "^{-}"])] {
+            for suffix in suffixes {
+                if point_characters.ends_with(suffix) && point_characters.len() > suffix.len() {
+                    let sign_position = point[point.len() - suffix.len() + 1].1;
+                    point.truncate(point.len() - suffix.len());
+                    direction = Some((sign, sign_position));
+                }
+            }
+        }
+
+        let body_start = i + 1;
+        i = operand_end(latex, body_start);
+        if latex[body_start..i].iter().all(|c| *c == ' ') {
+            return Err(ParseError::new(
+                offset + start..offset + start + 4,
+                "limit without a body",
+            )
+            .expected("an expression"));
+        }
+        let (mut body, mut body_positions) =
+            group_limits(&latex[body_start..i], offset + body_start)?;
+
+        grouped.extend(&latex[start..start + 4]);
+        positions.extend(offset + start..offset + start + 4);
+        grouped.push('{');
+        positions.push(offset + body_start);
+        grouped.append(&mut body);
+        positions.append(&mut body_positions);
+        grouped.push('}');
+        positions.push(offset + i.max(body_start + 1) - 1);
+        for part in [variable, point] {
+            grouped.push('{');
+            positions.push(part.first().map_or(offset + subscript_start, |(_, p)| *p));
+            for (character, position) in part {
+                grouped.push(character);
+                positions.push(position);
+            }
+            grouped.push('}');
+            positions.push(offset + arrow_start);
+        }
+        if let Some((sign, position)) = direction {
+            grouped.extend(['{', sign, '}']);
+            positions.extend([position; 3]);
+        }
+    }
+    Ok((grouped, positions))
+}
+///The length of \begin{pmatrix} or \end{pmatrix} at i, for any of the matrix environments, if the command is there
+fn matrix_environment(latex: &[char], i: usize, command: &str) -> Option<usize> {
+    if i >= latex.len() || !is_command_at(latex, i, command) {
+        return None;
+    }
+    let group = group_at(latex, i + command.len() + 1)?;
+    let environment = latex[group.clone()].iter().collect::<String>();
+    matches!(
+        environment.as_str(),
+        "matrix" | "pmatrix" | "bmatrix" | "Bmatrix"
+    )
+    .then_some(group.end + 1 - i)
+}
+///Splits a system on the \\\\, commas and newlines outside of brackets and other environments, dropping a surrounding cases environment and the & used to align the equations, also returning the position in the input of every character of every part
+#[allow(clippy::type_complexity)]
+fn split_system(latex: &[char]) -> Vec<(Vec<char>, Vec<usize>)> {
+    let mut parts = vec![(vec![], vec![])];
+    let (mut i, mut depth) = (0, 0);
+    while i < latex.len() {
+        let environment = |command: &str| {
+            (latex[i] == '\\' && is_command_at(latex, i, command))
+                .then(|| group_at(latex, i + command.len() + 1))
+                .flatten()
+        };
+        if let Some(group) = environment("begin").or_else(|| environment("end")) {
+            if latex[group.clone()].iter().copied().eq("cases".chars()) {
+                parts.push((vec![], vec![]));
+                i = group.end + 1;
+                continue;
+            }
+            depth += if latex[i + 1] == 'b' { 1 } else { -1 };
+        }
+        let (part, positions) = parts.last_mut().unwrap();
+        if BracketType::is_opening_bracket(latex[i]) {
+            depth += 1;
+        } else if BracketType::is_closing_bracket(latex[i]) {
+            depth -= 1;
+        } else if depth == 0 && latex[i..].starts_with(&['\\', '\\']) {
+            parts.push((vec![], vec![]));
+            i += 2;
+            continue;
+        } else if depth == 0 && matches!(latex[i], ',' | '\n') {
+            if !(i > 0 && latex[i - 1] == '\\') {
+                parts.push((vec![], vec![]));
+                i += 1;
+                continue;
+            }
+        } else if depth == 0 && latex[i] == '&' {
+            i += 1;
+            continue;
+        }
+        part.push(latex[i]);
+        positions.push(i);
+        i += 1;
+    }
+    parts.retain(|(part, _)| part.iter().any(|character| !character.is_whitespace()));
+    parts
+}
+///Splits a chain of equalities a=b=c into a=b and b=c
+fn unchain(equation: Equation) -> Vec<Equation> {
+    match equation {
+        Equation::Equals(sides) => match *sides {
+            (lhs, Equation::Equals(rest)) => {
+                let mut equations = vec![Equation::Equals(Box::new((lhs, rest.0.clone())))];
+                equations.append(&mut unchain(Equation::Equals(rest)));
+                equations
+            }
+            sides => vec![Equation::Equals(Box::new(sides))],
+        },
+        equation => vec![equation],
+    }
+}
+///Multiplies the factors, the product of two vectors is their dot product
+fn dot_product(factors: Vec<Equation>) -> Equation {
+    let (vectors, mut scalars): (Vec<_>, Vec<_>) = factors
+        .iter()
+        .cloned()
+        .partition(|factor| factor.is_vector());
+    match <[Equation; 2]>::try_from(vectors) {
+        Ok([left, right]) => {
+            let dot = Equation::Dot(Box::new((left, right)));
+            if scalars.is_empty() {
+                dot
+            } else {
+                scalars.push(dot);
+                Equation::Multiplication(scalars)
+            }
+        }
+        Err(_) => Equation::Multiplication(factors),
+    }
+}
+///Rewrites every derivative written as a fraction, with the variables repeated for higher orders, so \frac{\partial^2 f}{\partial x^2} becomes \diff{f}{x}{x}, without a function in the numerator the body ends like the body of a limit, also returning the position in the input of every character
+fn group_derivatives(latex: &[char], offset: usize) -> Result<(Vec<char>, Vec<usize>), ParseError> {
+    let (mut grouped, mut positions) = (vec![], vec![]);
+    let mut i = 0;
+    while i < latex.len() {
+        let Some((function, variables, end)) = is_command_at(latex, i, "frac")
+            .then(|| derivative_fraction(latex, i))
+            .flatten()
+        else {
+            grouped.push(latex[i]);
+            positions.push(offset + i);
+            i += 1;
+            continue;
+        };
+        let in_numerator = function.is_some();
+        let body = function.unwrap_or_else(|| end..operand_end(latex, end));
+        if latex[body.clone()].iter().all(|c| *c == ' ') {
+            return Err(
+                ParseError::new(offset + i..offset + end, "derivative without a function")
+                    .expected("an expression"),
+            );
+        }
+        let (mut inner, mut inner_positions) =
+            group_derivatives(&latex[body.clone()], offset + body.start)?;
+        grouped.extend("\\diff{".chars());
+        positions.extend([offset + i; 6]);
+        grouped.append(&mut inner);
+        positions.append(&mut inner_positions);
+        grouped.push('}');
+        positions.push(offset + body.end.max(body.start + 1) - 1);
+        for variable in variables {
+            grouped.push('{');
+            positions.push(offset + variable.start);
+            grouped.extend(&latex[variable.clone()]);
+            positions.extend(offset + variable.start..offset + variable.end);
+            grouped.push('}');
+            positions.push(offset + variable.end - 1);
+        }
+        i = if in_numerator { end } else { body.end };
+    }
+    Ok((grouped, positions))
+}
+///Reads \frac{d^n f}{dx^n} or \frac{\partial^n f}{\partial x \partial y} starting at the \frac, returning the range of f if it is in the numerator, the range of every variable repeated for its power and the end of the fraction
+#[allow(clippy::type_complexity)]
+fn derivative_fraction(
+    latex: &[char],
+    start: usize,
+) -> Option<(
+    Option<std::ops::Range<usize>>,
+    Vec<std::ops::Range<usize>>,
+    usize,
+)> {
+    let numerator = group_at(latex, start + 5)?;
+    let denominator = group_at(latex, numerator.end + 1)?;
+    let operator_start = skip_spaces(latex, numerator.start);
+    let partial = is_command_at(latex, operator_start, "partial");
+    let operator_end = |k: usize| {
+        if k < latex.len() && partial && is_command_at(latex, k, "partial") {
+            Some(k + 8)
+        } else if !partial && latex.get(k) == Some(&'d') {
+            Some(k + 1)
+        } else {
+            None
+        }
+    };
+
+    let (order, function_start) = script_number(latex, operator_end(operator_start)?)?;
+    let function_start = skip_spaces(latex, function_start);
+    let mut function_end = numerator.end;
+    while function_end > function_start && latex[function_end - 1] == ' ' {
+        function_end -= 1;
+    }
+    let function = (function_start < function_end).then_some(function_start..function_end);
+
+    let (mut variables, mut total) = (vec![], 0);
+    let mut k = skip_spaces(latex, denominator.start);
+    while k < denominator.end {
+        k = skip_spaces(latex, operator_end(k)?);
+        let variable_end = match latex.get(k) {
+            Some(c) if c.is_alphabetic() => k + 1,
+            Some('\\') if !is_command_at(latex, k, "partial") => {
+                let mut end = k + 1;
+                while latex.get(end).is_some_and(|c| c.is_alphabetic()) {
+                    end += 1;
+                }
+                end
+            }
+            _ => return None,
+        };
+        if variable_end == k + 1 && latex[k] == '\\' {
+            return None;
+        }
+        let (power, end) = script_number(latex, variable_end)?;
+        variables.extend(std::iter::repeat_n(k..variable_end, power));
+        total += power;
+        k = skip_spaces(latex, end);
+    }
+    (!variables.is_empty() && total == order).then_some((function, variables, denominator.end + 1))
+}
+///The contents of the curly brackets starting at i, after any spaces
+fn group_at(latex: &[char], i: usize) -> Option<std::ops::Range<usize>> {
+    let open = skip_spaces(latex, i);
+    if latex.get(open) != Some(&'{') {
+        return None;
+    }
+    let mut depth = 0;
+    for (k, &character) in latex.iter().enumerate().skip(open) {
+        if BracketType::is_opening_bracket(character) {
+            depth += 1;
+        } else if BracketType::is_closing_bracket(character) {
+            depth -= 1;
+            if depth == 0 {
+                return Some(open + 1..k);
+            }
+        }
+    }
+    None
+}
+///Reads an optional positive integer superscript at i, which is 1 if there is none, returning it and the position after it
+fn script_number(latex: &[char], i: usize) -> Option<(usize, usize)> {
+    let caret = skip_spaces(latex, i);
+    if latex.get(caret) != Some(&'^') {
+        return Some((1, i));
+    }
+    let k = skip_spaces(latex, caret + 1);
+    let (digits, end) = match latex.get(k) {
+        Some('{') => {
+            let group = group_at(latex, k)?;
+            (group.clone(), group.end + 1)
+        }
+        Some(c) if c.is_ascii_digit() => (k..k + 1, k + 1),
+        _ => return None,
+    };
+    let number = latex[digits]
+        .iter()
+        .filter(|c| **c != ' ')
         .collect::<String>()
-        .replace("\\cdot", "*")
-        .replace(' ', "")
-        .replace("\\left", "")
-        .replace("\\right", "")
-        .chars()
-        .collect::<Vec<char>>();
+        .parse::<usize>()
+        .ok()?;
+    (number > 0).then_some((number, end))
+}
+fn skip_spaces(latex: &[char], mut i: usize) -> usize {
+    while latex.get(i) == Some(&' ') {
+        i += 1;
+    }
+    i
+}
+///The end of the operand of a prefix operator like \lim starting at start, which is the next +, - or = outside of brackets or the bracket closing around it
+fn operand_end(latex: &[char], start: usize) -> usize {
+    let (mut depth, mut first) = (0, true);
+    let mut i = start;
+    while i < latex.len() {
+        let character = latex[i];
+        if BracketType::is_opening_bracket(character) {
+            depth += 1;
+        } else if BracketType::is_closing_bracket(character) {
+            if depth == 0 {
+                break;
+            }
+            depth -= 1;
+        } else if depth == 0 && matches!(character, '+' | '-' | '=') && !first {
+            break;
+        }
+        first &= character == ' ';
+        i += 1;
+    }
+    i
+}
+fn is_command_at(latex: &[char], i: usize, command: &str) -> bool {
+    let end = i + 1 + command.len();
+    latex[i] == '\\'
+        && latex.len() >= end
+        && latex[i + 1..end].iter().copied().eq(command.chars())
+        && !latex.get(end).is_some_and(|c| c.is_alphabetic())
+}
+///Returns the position after the sub- and superscripts starting at i
+fn skip_scripts(latex: &[char], mut i: usize) -> usize {
+    loop {
+        let mut j = i;
+        while latex.get(j) == Some(&' ') {
+            j += 1;
+        }
+        if !matches!(latex.get(j), Some('_' | '^')) {
+            return i;
+        }
+        j += 1;
+        while latex.get(j) == Some(&' ') {
+            j += 1;
+        }
+        match latex.get(j) {
+            Some(&bracket) if BracketType::is_opening_bracket(bracket) => {
+                let mut depth = 0;
+                while j < latex.len() {
+                    if BracketType::is_opening_bracket(latex[j]) {
+                        depth += 1;
+                    } else if BracketType::is_closing_bracket(latex[j]) {
+                        depth -= 1;
+                    }
+                    j += 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            Some('\\') => {
+                j += 1;
+                while latex.get(j).is_some_and(|c| c.is_alphabetic()) {
+                    j += 1;
+                }
+            }
+            Some(_) => j += 1,
+            None => {}
+        }
+        i = j;
+    }
+}
+///Finds the differential ending the integral body that starts at start, skipping the differentials of nested integrals
+fn find_differential(
+    latex: &[char],
+    start: usize,
+    strict: bool,
+) -> Option<(usize, std::ops::Range<usize>)> {
+    let (mut depth, mut nested) = (0, 0);
+    let mut k = start;
+    while k < latex.len() {
+        if BracketType::is_opening_bracket(latex[k]) {
+            depth += 1;
+        } else if BracketType::is_closing_bracket(latex[k]) {
+            if depth == 0 {
+                return None;
+            }
+            depth -= 1;
+        } else if depth == 0 {
+            let d_length = if latex[k] == 'd' {
+                1
+            } else if latex[k..].starts_with(&['\\', 'm', 'a', 't', 'h', 'r', 'm', '{', 'd', '}']) {
+                10
+            } else {
+                0
+            };
+            let separated = k == start || !strict || !latex[k - 1].is_alphanumeric();
+            if d_length > 0 && separated {
+                if let Some(variable) = differential_variable(latex, k + d_length) {
+                    if nested == 0 {
+                        return Some((k, variable));
+                    }
+                    nested -= 1;
+                    k = variable.end;
+                    continue;
+                }
+            }
+            if is_command_at(latex, k, "int") {
+                nested += 1;
+            }
+        }
+        if latex[k] == '\\' {
+            k += 1;
+            while latex.get(k).is_some_and(|c| c.is_alphabetic()) {
+                k += 1;
+            }
+            continue;
+        }
+        k += 1;
+    }
+    None
+}
+///The variable of a differential, which is a letter or a command not followed by another letter or digit
+fn differential_variable(latex: &[char], mut i: usize) -> Option<std::ops::Range<usize>> {
+    while latex.get(i) == Some(&' ') {
+        i += 1;
+    }
+    let end = match latex.get(i) {
+        Some(c) if c.is_alphabetic() => i + 1,
+        Some('\\') => {
+            let mut end = i + 1;
+            while latex.get(end).is_some_and(|c| c.is_alphabetic()) {
+                end += 1;
+            }
+            if end == i + 1 {
+                return None;
+            }
+            end
+        }
+        _ => return None,
+    };
+    if latex.get(end).is_some_and(|c| c.is_alphanumeric()) {
+        return None;
+    }
+    Some(i..end)
 }
 #[cfg(test)]
 mod test {
     #[test]
     fn test_check_if_caret_is_power() {
-        assert!(
-            !super::IR::check_if_caret_is_power(&"\\int^10{a}{b}".chars().collect::<Vec<char>>(), 4)
+        assert!(!super::IR::check_if_caret_is_power(
+            &"\\int^10{a}{b}".chars().collect::<Vec<char>>(),
+            4
+        ));
+        assert!(super::IR::check_if_caret_is_power(
+            &"\\frac{a}{b}^10".chars().collect::<Vec<char>>(),
+            11
+        ));
+    }
+    #[test]
+    fn test_parse_errors() {
+        let parse = |latex: &str| super::IR::latex_to_equation(latex.chars().collect(), false);
+
+        let error = parse("\\frac{a+b}{c+d)}").unwrap_err();
+        assert_eq!(error.span, 14..15);
+        assert_eq!(error.expected.as_deref(), Some("`}`"));
+        assert_eq!(
+            error.to_string(),
+            "unexpected `)` at column 15, expected `}`"
         );
-        assert!(
-            super::IR::check_if_caret_is_power(
-                &"\\frac{a}{b}^10".chars().collect::<Vec<char>>(),
-                11
-            )
+
+        assert_eq!(parse("").unwrap_err().span, 0..0);
+        assert_eq!(parse("x + ").unwrap_err().span, 4..4);
+        assert_eq!(parse("((x)").unwrap_err().span, 0..1);
+        assert_eq!(parse("\\frac{a}").unwrap_err().span, 8..8);
+        assert_eq!(parse("2.3.4").unwrap_err().span, 0..5);
+        assert_eq!(
+            parse("\\left(x\\right) \\cdot 3!").unwrap_err().span,
+            22..23
+        );
+        for latex in [
+            "\\",
+            "^2",
+            "a^^b",
+            "x=",
+            "\\sin",
+            "\\int_0^1 x",
+            "\\int_0 x dx",
+            "99999999999999999999",
+        ] {
+            assert!(parse(latex).is_err());
+        }
+        assert!(parse("(x+1)y").is_ok());
+        assert_eq!(parse("2+\\int_0^1 x").unwrap_err().span, 2..6);
+        for (latex, span) in [
+            ("\\lim_{x\\to 0}", 0..4),
+            ("\\lim_{x\\to}", 0..4),
+            ("1+\\lim_{x\\to 0}", 2..6),
+            ("\\frac{d}{dx}", 0..12),
+            ("\\frac{\\partial}{\\partial x}", 0..27),
+            ("x+\\frac{d}{dx}", 2..14),
+            ("2+\\foo{x}", 2..6),
+            ("|x", 0..1),
+            ("x|", 1..2),
+            ("|x|+|y", 4..5),
+        ] {
+            assert_eq!(parse(latex).unwrap_err().span, span, "{}", latex);
+        }
+        assert_eq!(
+            parse("\\foo{x}").unwrap_err().to_string(),
+            "unknown command `\\foo` at column 1"
+        );
+        assert_eq!(
+            parse("|x").unwrap_err().to_string(),
+            "unclosed `|` at column 1, expected `|`"
+        );
+    }
+    #[test]
+    fn test_integrals() {
+        let parse = |latex: &str| super::IR::latex_to_equation(latex.chars().collect(), false);
+
+        for latex in [
+            "\\int_0^1 x dx",
+            "\\int_{0}^{1}x\\,dx",
+            "\\int^1_0 \\sin(x)dx",
+            "\\int_0^1xdx",
+            "\\int_0^1 \\int_0^1 x*y \\mathrm{d}x dy",
+        ] {
+            let equation = parse(latex).unwrap();
+            assert!(matches!(equation, super::Equation::Integral(_)));
+            let latex = super::IR::equation_to_latex(equation.clone(), false);
+            assert_eq!(parse(&latex).unwrap(), equation);
+        }
+        assert!(matches!(
+            parse("\\int_0^1 x dx + 1").unwrap(),
+            super::Equation::Addition(_)
+        ));
+        for (latex, with_one) in [
+            ("\\int_0^1 dx", "\\int_0^1 1 dx"),
+            ("\\int_0^1\\,dx", "\\int_0^1 1 dx"),
+            ("\\int dx", "\\int 1 dx"),
+            ("\\int_0^1\\int_0^1 dx dy", "\\int_0^1\\int_0^1 1 dx dy"),
+        ] {
+            assert_eq!(parse(latex).unwrap(), parse(with_one).unwrap(), "{}", latex);
+        }
+    }
+    #[test]
+    fn test_limits() {
+        use super::{Constant, LimitDirection, Variable};
+        let parse = |latex: &str| super::IR::latex_to_equation(latex.chars().collect(), false);
+
+        for (latex, direction) in [
+            ("\\lim_{x\\to 0}\\frac{\\sin(x)}{x}", LimitDirection::Both),
+            ("\\lim_{x \\to a^+} \\ln(x-a)", LimitDirection::Right),
+            (
+                "\\lim_{x\\rightarrow 0^{-}}\\frac{1}{x}",
+                LimitDirection::Left,
+            ),
+            ("\\lim_{x\\to-\\infty}e^{x}", LimitDirection::Both),
+            (
+                "\\lim_{y\\to\\infty}\\lim_{x\\to 0}x*y",
+                LimitDirection::Both,
+            ),
+        ] {
+            let equation = parse(latex).unwrap();
+            let super::Equation::Limit(limit) = &equation else {
+                panic!("{} is not a limit", latex);
+            };
+            assert_eq!(limit.direction, direction);
+            let latex = super::IR::equation_to_latex(equation.clone(), false);
+            assert_eq!(parse(&latex).unwrap(), equation);
+        }
+        assert!(matches!(
+            parse("\\lim_{x\\to 0}x+1").unwrap(),
+            super::Equation::Addition(_)
+        ));
+        assert_eq!(
+            parse("\\infty").unwrap(),
+            super::Equation::Variable(Variable::Constant(Constant::Infinity))
         );
+        assert!(matches!(
+            parse("-(x+1)").unwrap(),
+            super::Equation::Negative(_)
+        ));
+        assert_eq!(parse("2+\\lim x").unwrap_err().span, 2..6);
+        assert_eq!(parse("\\lim_{x} x").unwrap_err().span, 0..4);
+    }
+    #[test]
+    fn test_derivatives() {
+        use super::Variable;
+        let parse = |latex: &str| super::IR::latex_to_equation(latex.chars().collect(), false);
+        let letters = |names: &str| {
+            names
+                .chars()
+                .map(|name| Variable::Letter(name.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        for (latex, variables) in [
+            ("\\frac{d}{dx}x^2", letters("x")),
+            ("\\frac{d^2}{dx^2} \\sin(x)", letters("xx")),
+            ("\\frac{d y}{d x}", letters("x")),
+            (
+                "\\frac{\\partial^2 (x*y)}{\\partial x \\partial y}",
+                letters("xy"),
+            ),
+            (
+                "\\frac{\\partial^3}{\\partial x^{2}\\partial y}x^3*y",
+                letters("xxy"),
+            ),
+            (
+                "\\frac{d}{dt}\\theta",
+                vec![Variable::Letter("t".to_string())],
+            ),
+        ] {
+            let equation = parse(latex).unwrap();
+            let super::Equation::Derivative(derivative) = &equation else {
+                panic!("{} is not a derivative", latex);
+            };
+            assert_eq!(derivative.variables, variables);
+            let latex = super::IR::equation_to_latex(equation.clone(), false);
+            assert_eq!(parse(&latex).unwrap(), equation);
+        }
+        assert!(matches!(
+            parse("\\frac{d}{dx}x+1").unwrap(),
+            super::Equation::Addition(_)
+        ));
+        for fraction in ["\\frac{dx}{2}", "\\frac{d^2}{dx}", "\\frac{a}{dx}"] {
+            assert!(matches!(
+                parse(fraction).unwrap(),
+                super::Equation::Division(_) | super::Equation::Multiplication(_)
+            ));
+        }
+    }
+    #[test]
+    fn test_vectors() {
+        use super::{Equation, Variable};
+        let parse =
+            |latex: &str| super::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+        let vector = |name: &str| Equation::Variable(Variable::Vector(name.to_string()));
+        let letter = |name: &str| Equation::Variable(Variable::Letter(name.to_string()));
+
+        assert_eq!(
+            parse("\\vec{a}\\cdot\\vec{b}"),
+            Equation::Dot(Box::new((vector("a"), vector("b"))))
+        );
+        assert_eq!(
+            parse("\\vec{F}=q\\vec{v}\\times\\vec{B}"),
+            Equation::Equals(Box::new((
+                vector("F"),
+                Equation::Cross(Box::new((
+                    Equation::Multiplication(vec![letter("q"), vector("v")]),
+                    vector("B")
+                )))
+            )))
+        );
+        assert_eq!(
+            parse("a\\times b"),
+            Equation::Multiplication(vec![letter("a"), letter("b")])
+        );
+        assert_eq!(
+            parse("|x-|y||"),
+            Equation::Abs(Box::new(Equation::Addition(vec![
+                letter("x"),
+                Equation::Negative(Box::new(Equation::Abs(Box::new(letter("y")))))
+            ])))
+        );
+        assert!(super::IR::latex_to_equation("\\vec{2}".chars().collect(), false).is_err());
+
+        for latex in [
+            "\\vec{a}\\times(2\\vec{b})",
+            "\\left|\\vec{E}\\right|^2",
+            "|a|*|b|",
+            "\\vec{a}\\cdot(\\vec{b}+\\vec{c})",
+        ] {
+            let equation = parse(latex);
+            let latex = super::IR::equation_to_latex(equation.clone(), false);
+            assert_eq!(parse(&latex), equation);
+        }
+        assert_eq!(
+            super::IR::equation_to_numpy(parse("|\\vec{v}\\times\\vec{w}|"), false),
+            "np.linalg.norm(np.cross(v,w))"
+        );
+        assert_eq!(
+            super::IR::equation_to_numpy(parse("\\vec{v}\\cdot\\vec{w}"), false),
+            "np.dot(v,w)"
+        );
+    }
+    #[test]
+    fn test_matrices() {
+        use super::{Equation, Variable};
+        let parse = |latex: &str| super::IR::latex_to_equation(latex.chars().collect(), false);
+        let letter = |name: &str| Equation::Variable(Variable::Letter(name.to_string()));
+
+        let expected = Equation::Matrix(vec![
+            vec![letter("a"), letter("b")],
+            vec![letter("c"), letter("d")],
+        ]);
+        for latex in [
+            "\\begin{pmatrix}a&b\\\\c&d\\end{pmatrix}",
+            "\\begin{bmatrix} a & b \\\\ c & d \\\\ \\end{bmatrix}",
+            "\\left(\\begin{matrix}a&b\\\\c&d\\end{matrix}\\right)",
+        ] {
+            assert_eq!(parse(latex).unwrap(), expected);
+        }
+        let error = parse("\\begin{pmatrix}a&b\\\\c\\end{pmatrix}").unwrap_err();
+        assert_eq!(error.span, 18..21);
+        assert_eq!(error.expected.as_deref(), Some("2 entries"));
+        assert_eq!(parse("\\matrix").unwrap_err().span, 0..7);
+        assert_eq!(parse("\\matrix{x}").unwrap_err().span, 8..9);
+
+        for latex in [
+            "\\begin{pmatrix}\\frac{1}{2}&\\sin(x)\\\\x^2&-1\\end{pmatrix}^T",
+            "2\\begin{pmatrix}1\\\\x\\end{pmatrix}+\\begin{pmatrix}\\begin{pmatrix}y\\end{pmatrix}\\end{pmatrix}",
+        ] {
+            let equation = parse(latex).unwrap();
+            let latex = super::IR::equation_to_latex(equation.clone(), false);
+            assert_eq!(parse(&latex).unwrap(), equation);
+        }
+        assert_eq!(
+            super::IR::equation_to_numpy(
+                parse("(\\begin{pmatrix}1&2\\end{pmatrix}^T)*\\begin{pmatrix}x&y\\end{pmatrix}")
+                    .unwrap(),
+                false
+            ),
+            "(np.transpose(np.array([[1,2]]))@np.array([[x,y]]))"
+        );
+    }
+    #[test]
+    fn test_systems() {
+        use super::{Equation, Variable};
+        let parse = |latex: &str| super::IR::latex_to_system(latex.chars().collect(), false);
+        let letter = |name: &str| Equation::Variable(Variable::Letter(name.to_string()));
+        let equals = |lhs: Equation, rhs: Equation| Equation::Equals(Box::new((lhs, rhs)));
+
+        let expected = vec![
+            equals(letter("x"), letter("y")),
+            equals(letter("y"), Equation::Variable(Variable::Integer(2))),
+        ];
+        for latex in [
+            "x=y, y=2",
+            "x=y\\\\y=2",
+            "x=y\ny=2\n",
+            "x=y=2",
+            "\\begin{cases} x &= y \\\\ y &= 2 \\end{cases}",
+        ] {
+            assert_eq!(parse(latex).unwrap().equations, expected, "{}", latex);
+        }
+        //Separators inside brackets and matrices belong to the equation
+        assert_eq!(
+            parse("\\begin{pmatrix}x\\\\y\\end{pmatrix}=\\begin{pmatrix}1\\\\2\\end{pmatrix}, z=\\frac{1}{2}\\,x")
+                .unwrap()
+                .equations
+                .len(),
+            2
+        );
+        let system = parse("x=1, y=\\sin(x)").unwrap();
+        assert_eq!(parse(&system.to_latex()).unwrap(), system);
+
+        let error = parse("x=1, y=").unwrap_err();
+        assert_eq!(error.span.start, 7);
+        assert!(parse(" , ").is_err());
+    }
+    #[test]
+    fn test_left_associative_operators() {
+        use std::collections::BTreeMap;
+        let mut values = BTreeMap::new();
+        for (name, value) in [("a", 5.), ("b", 3.), ("c", 2.)] {
+            values.insert(super::Variable::Letter(name.to_string()), value);
+        }
+        let calculate = |latex: &str| {
+            super::IR::latex_to_equation(latex.chars().collect(), false)
+                .unwrap()
+                .calculate(&values)
+        };
+
+        assert_eq!(calculate("a-b+c"), 4.);
+        assert_eq!(calculate("a-b-c"), 0.);
+        assert_eq!(calculate("a+-b"), 2.);
+        assert_eq!(calculate("a/b*c"), 5. / 3. * 2.);
+        assert_eq!(calculate("2*-3"), -6.);
+        assert_eq!(calculate("2\\cdot -3"), -6.);
+        assert_eq!(calculate("a*-b+c"), -13.);
+        assert_eq!(calculate("-a*b"), -15.);
     }
 }
@@ -7,11 +7,16 @@ impl IR {
         match name[..] {
             ['+'] | ['-'] | ['*'] => {
                 return_data.push('(');
+                let mut previous_is_matrix = false;
                 while !self.parameters.is_empty() {
-                    return_data.append(&mut IR::ir_to_numpy(
-                        self.parameters.remove(0).0,
-                        implicit_multiplication,
-                    ));
+                    let parameter = self.parameters.remove(0).0;
+                    //Neighbouring matrices are multiplied as matrices instead of element-wise
+                    let is_matrix = parameter.is_matrix();
+                    if name[0] == '*' && previous_is_matrix && is_matrix {
+                        *return_data.last_mut().unwrap() = '@';
+                    }
+                    previous_is_matrix = is_matrix;
+                    return_data.append(&mut IR::ir_to_numpy(parameter, implicit_multiplication));
                     return_data.push(name[0])
                 }
                 return_data.pop();
@@ -21,6 +26,31 @@ impl IR {
                 if self.parameters.len() != 2 {
                     panic!("Invalid power, not two parameters");
                 }
+                if self.parameters[0].0.name == ['e'] {
+                    return_data.extend("np.exp(".chars());
+                    return_data.append(&mut IR::ir_to_numpy(
+                        self.parameters.remove(1).0,
+                        implicit_multiplication,
+                    ));
+                    return_data.push(')');
+                    return return_data;
+                }
+                if self.parameters[0].0.is_matrix() {
+                    let (matrix, exponent) =
+                        (self.parameters.remove(0).0, self.parameters.remove(0).0);
+                    if exponent.name == ['T'] {
+                        return_data.extend("np.transpose(".chars());
+                    } else {
+                        return_data.extend("np.linalg.matrix_power(".chars());
+                    }
+                    return_data.append(&mut IR::ir_to_numpy(matrix, implicit_multiplication));
+                    if exponent.name != ['T'] {
+                        return_data.push(',');
+                        return_data.append(&mut IR::ir_to_numpy(exponent, implicit_multiplication));
+                    }
+                    return_data.push(')');
+                    return return_data;
+                }
                 return_data.extend("np.power(".chars().collect::<Vec<char>>());
                 return_data.append(&mut IR::ir_to_numpy(
                     self.parameters.remove(0).0,
@@ -71,7 +101,6 @@ impl IR {
                     implicit_multiplication,
                 ));
                 return_data.push(')');
-
             }
             ['\\', 's', 'i', 'n'] => {
                 return_data.extend("np.sin(".chars().collect::<Vec<char>>());
@@ -89,6 +118,108 @@ impl IR {
                 ));
                 return_data.push(')');
             }
+            ['\\', 'i', 'n', 't'] => {
+                if self.parameters.len() != 4 {
+                    panic!("Indefinite integrals cannot be converted to numpy");
+                }
+                let mut parameters = self
+                    .parameters
+                    .into_iter()
+                    .map(|parameter| parameter.0.ir_to_numpy(implicit_multiplication));
+                let integrand = parameters.next().unwrap();
+                let variable = parameters.next().unwrap();
+                return_data.extend("scipy.integrate.quad(lambda ".chars());
+                return_data.extend(variable);
+                return_data.push(':');
+                return_data.extend(integrand);
+                for bound in parameters {
+                    return_data.push(',');
+                    return_data.extend(bound);
+                }
+                return_data.extend(")[0]".chars());
+            }
+            ['\\', 'l', 'i', 'm'] => {
+                panic!("Limits cannot be converted to numpy");
+            }
+            ['\\', 'd', 'i', 'f', 'f'] | ['\\', 'p', 'a', 'r', 't', 'i', 'a', 'l']
+                if !self.parameters.is_empty() =>
+            {
+                panic!("Derivatives cannot be converted to numpy, simplify them first");
+            }
+            ['\\', 'i', 'n', 'f', 't', 'y'] => {
+                return_data.extend("np.inf".chars());
+            }
+            ['\\', 'm', 'a', 't', 'r', 'i', 'x'] => {
+                return_data.extend("np.array([".chars());
+                for (i, (row, _)) in self.parameters.into_iter().enumerate() {
+                    if i > 0 {
+                        return_data.push(',');
+                    }
+                    return_data.push('[');
+                    for (j, (entry, _)) in row.parameters.into_iter().enumerate() {
+                        if j > 0 {
+                            return_data.push(',');
+                        }
+                        return_data.append(&mut IR::ir_to_numpy(entry, implicit_multiplication));
+                    }
+                    return_data.push(']');
+                }
+                return_data.extend("])".chars());
+            }
+            ['\\', 'n', 'o', 'r', 'm'] if self.parameters.len() == 1 => {
+                return_data.extend("np.linalg.norm(".chars());
+                return_data.append(&mut IR::ir_to_numpy(
+                    self.parameters.remove(0).0,
+                    implicit_multiplication,
+                ));
+                return_data.push(')');
+            }
+            ['\\', 'c', 'd', 'o', 't'] | ['\\', 't', 'i', 'm', 'e', 's']
+                if self.parameters.len() == 2 =>
+            {
+                return_data.extend(
+                    if name[1] == 'c' {
+                        "np.dot("
+                    } else {
+                        "np.cross("
+                    }
+                    .chars(),
+                );
+                return_data.append(&mut IR::ir_to_numpy(
+                    self.parameters.remove(0).0,
+                    implicit_multiplication,
+                ));
+                return_data.push(',');
+                return_data.append(&mut IR::ir_to_numpy(
+                    self.parameters.remove(0).0,
+                    implicit_multiplication,
+                ));
+                return_data.push(')');
+            }
+            ['|'] => {
+                return_data.extend("np.abs(".chars());
+                return_data.append(&mut IR::ir_to_numpy(
+                    self.parameters.remove(0).0,
+                    implicit_multiplication,
+                ));
+                return_data.push(')');
+            }
+            ['\\', 't', 'a', 'n']
+            | ['\\', 'a', 'r', 'c', 's', 'i', 'n']
+            | ['\\', 'a', 'r', 'c', 'c', 'o', 's']
+            | ['\\', 'a', 'r', 'c', 't', 'a', 'n']
+            | ['\\', 's', 'i', 'n', 'h']
+            | ['\\', 'c', 'o', 's', 'h']
+            | ['\\', 't', 'a', 'n', 'h'] => {
+                return_data.extend("np.".chars());
+                return_data.extend(&name[1..]);
+                return_data.push('(');
+                return_data.append(&mut IR::ir_to_numpy(
+                    self.parameters.remove(0).0,
+                    implicit_multiplication,
+                ));
+                return_data.push(')');
+            }
             ['\\', 'l', 'n'] => {
                 return_data.extend("np.log(".chars().collect::<Vec<char>>());
                 return_data.append(&mut IR::ir_to_numpy(
@@ -97,6 +228,10 @@ impl IR {
                 ));
                 return_data.push(')');
             }
+            ['\\', 'v', 'e', 'c', '{', .., '}'] if self.parameters.is_empty() => {
+                //Vectors are numpy arrays named after the vector
+                return name[5..name.len() - 1].to_vec();
+            }
             _ => {
                 if self.parameters.is_empty() {
                     return self.name;
@@ -113,4 +248,20 @@ impl IR {
         }
         return_data
     }
+
+    ///Whether the IR has a matrix as value, a product of a scalar and a matrix is a matrix
+    fn is_matrix(&self) -> bool {
+        match self.name[..] {
+            ['\\', 'm', 'a', 't', 'r', 'i', 'x'] => true,
+            ['^'] | ['\\', 'f', 'r', 'a', 'c'] => self
+                .parameters
+                .first()
+                .is_some_and(|parameter| parameter.0.is_matrix()),
+            ['+'] | ['-'] | ['*'] | ['\\', 'i', 'n', 'v'] => self
+                .parameters
+                .iter()
+                .any(|parameter| parameter.0.is_matrix()),
+            _ => false,
+        }
+    }
 }
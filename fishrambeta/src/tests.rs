@@ -1,4 +1,12 @@
-use crate::{math::Variable, parser};
+use crate::{
+    math::{
+        solve_linear_system, Constant, Dimension, DomainHint, Equation, Equivalence, EvalError,
+        LimitDirection, Measurement, Polynomial, QuadratureMethod, QuadratureOptions, RootOptions,
+        SimplifyOptions, Unit, Variable, VectorValue,
+    },
+    parser, physicsvalues,
+};
+use num_rational::Rational64;
 use std::collections::BTreeMap;
 
 #[test]
@@ -7,7 +15,453 @@ fn simplify() {
 
     assert!(simplified_is_equal("x^2", &valuedicts));
     assert!(simplified_is_equal("\\frac{x}{x}", &valuedicts));
-    assert!(simplified_is_equal("\\frac{\\frac{-x}{x}}{x}", &valuedicts))
+    assert!(simplified_is_equal("\\frac{\\frac{-x}{x}}{x}", &valuedicts));
+    assert!(simplified_is_equal("\\tan(\\arctan(x))", &valuedicts));
+    assert!(simplified_is_equal("\\cosh(-x)", &valuedicts));
+
+    let x = Equation::Variable(Variable::Letter("x".to_string()));
+    for latex in ["\\ln(e^{x})", "e^{\\ln(x)}", "\\exp(\\ln(x))"] {
+        let parsed = parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+        assert_eq!(parsed.simplify_until_complete(), x);
+    }
+
+    let parsed =
+        parser::IR::latex_to_equation("\\frac{2*x+x}{x}+\\ln(e^{y})".chars().collect(), false)
+            .unwrap();
+    let options = SimplifyOptions {
+        trace: true,
+        ..Default::default()
+    };
+    let simplification = parsed.clone().simplify_with(&options);
+    assert!(simplification.converged);
+    assert_eq!(
+        simplification.equation.clone().simplify_until_complete(),
+        simplification.equation
+    );
+    let rules = simplification
+        .trace
+        .iter()
+        .map(|rewrite| rewrite.rule)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rules,
+        [
+            "collect like terms",
+            "cancel common factors",
+            "inverse functions"
+        ]
+    );
+    //Every rewrite starts from the subexpression with its own subexpressions already simplified
+    assert_eq!(
+        simplification.trace[1].to_string(),
+        "cancel common factors: \\frac{((3)*(x))}{x} = 3"
+    );
+    let options = SimplifyOptions {
+        max_passes: 1,
+        ..Default::default()
+    };
+    assert!(!parsed.simplify_with(&options).converged);
+}
+
+#[test]
+fn equivalent() {
+    let parse =
+        |latex: &str| parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+    let equivalent = [
+        ("x+y", "y+x"),
+        ("2*x", "x*2"),
+        ("(a+b)+c", "a+(b+c)"),
+        ("a*(b*c)", "(c*a)*b"),
+        ("-x*y", "x*(-y)"),
+        ("-1*(-x)", "x"),
+        ("-2*x", "0-x*2"),
+        ("\\frac{-a}{b}", "0-\\frac{a}{b}"),
+        ("\\sin(y+x)=z", "z=\\sin(x+y)"),
+    ];
+    for (a, b) in equivalent {
+        assert!(parse(a).equivalent(&parse(b)), "{} and {}", a, b);
+    }
+    let different = [
+        ("x+x", "2*x"),
+        ("x-y", "y-x"),
+        ("\\frac{a}{b}", "\\frac{b}{a}"),
+    ];
+    for (a, b) in different {
+        assert!(!parse(a).equivalent(&parse(b)), "{} and {}", a, b);
+    }
+
+    let two = Equation::Variable(Variable::Rational((2, 1).into()));
+    assert_eq!(two.canonical(), Equation::Variable(Variable::Integer(2)));
+}
+
+#[test]
+fn probably_equals() {
+    let parse =
+        |latex: &str| parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+    let x = Variable::Letter("x".to_string());
+    let positive = BTreeMap::from([(x.clone(), DomainHint::Positive)]);
+    let real = BTreeMap::new();
+
+    let probably_equal = |a: &str, b: &str, hints: &BTreeMap<Variable, DomainHint>| {
+        matches!(
+            parse(a).probably_equals(&parse(b), hints),
+            Equivalence::ProbablyEqual { .. }
+        )
+    };
+    assert!(probably_equal("(x+y)^2", "x^2+2*x*y+y^2", &real));
+    assert!(probably_equal("\\sin(x)^2+\\cos(x)^2", "1", &real));
+    assert!(probably_equal("\\frac{x^2-1}{x-1}", "x+1", &real));
+    assert!(probably_equal("\\ln(x^2)", "2*\\ln(x)", &positive));
+    assert!(probably_equal("\\sqrt{x^2}", "x", &positive));
+
+    let Equivalence::Different { point, values } =
+        parse("\\sqrt{x^2}").probably_equals(&parse("x"), &real)
+    else {
+        panic!("the square root of x^2 and x differ for negative x");
+    };
+    assert!(point[&x] < 0.);
+    assert_eq!(values.0, -values.1);
+
+    let interval = BTreeMap::from([(x.clone(), DomainHint::Interval(2., 3.))]);
+    assert!(matches!(
+        parse("\\ln(1-x)").probably_equals(&parse("x"), &interval),
+        Equivalence::Undecided { samples: 0 }
+    ));
+
+    let integrand = parse("x*\\cos(x^2)");
+    let integral = integrand.integrate(&x).unwrap();
+    assert!(matches!(
+        integral
+            .differentiate(&x)
+            .probably_equals(&integrand, &real),
+        Equivalence::ProbablyEqual { samples: 32 }
+    ));
+}
+
+#[test]
+fn polynomial() {
+    let x = Variable::Letter("x".to_string());
+    let polynomial = |latex: &str| {
+        let parsed = parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+        Polynomial::from_equation(&parsed, &x).unwrap()
+    };
+    let rationals = |coefficients: &[(i64, i64)]| {
+        Polynomial::new(
+            coefficients
+                .iter()
+                .map(|c| Rational64::new(c.0, c.1))
+                .collect(),
+        )
+    };
+
+    let p = polynomial("(x+1)^2*(x-2)-\\frac{x}{2}");
+    assert_eq!(p, rationals(&[(-2, 1), (-7, 2), (0, 1), (1, 1)]));
+    assert_eq!(p.degree(), Some(3));
+    assert_eq!(p.coefficient(1), Rational64::new(-7, 2));
+    assert_eq!(p.coefficient(5), Rational64::from(0));
+    assert_eq!(polynomial("x-x").degree(), None);
+    assert_eq!(polynomial("2*(3+x)-2*x"), rationals(&[(6, 1)]));
+
+    let (quotient, remainder) = polynomial("x^3-1").div_rem(&polynomial("x-1")).unwrap();
+    assert_eq!(quotient, polynomial("x^2+x+1"));
+    assert!(remainder.is_zero());
+    let (quotient, remainder) = polynomial("x^2+1").div_rem(&polynomial("2*x")).unwrap();
+    assert_eq!(quotient, rationals(&[(0, 1), (1, 2)]));
+    assert_eq!(remainder, rationals(&[(1, 1)]));
+
+    assert_eq!(polynomial("x").div_rem(&Polynomial::zero()), None);
+
+    assert_eq!(
+        polynomial("2*x^3-2").gcd(&polynomial("x^2-1")),
+        Some(polynomial("x-1"))
+    );
+    assert_eq!(
+        polynomial("x^2+1").gcd(&polynomial("x")),
+        Some(rationals(&[(1, 1)]))
+    );
+    assert_eq!(
+        polynomial("(x+1)^{10}").coefficient(5),
+        Rational64::from(252)
+    );
+
+    for latex in ["x^2-\\frac{3}{4}*x+2", "-x^3+x", "7", "0"] {
+        let p = polynomial(latex);
+        assert_eq!(Polynomial::from_equation(&p.to_equation(&x), &x), Some(p));
+    }
+
+    for latex in [
+        "\\sin(x)",
+        "\\frac{1}{x}",
+        "x^{\\frac{1}{2}}",
+        "a*x",
+        "x^x",
+        "x^{1000000}-1",
+        "(3037000500*x+1)^2",
+    ] {
+        let parsed = parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+        assert_eq!(Polynomial::from_equation(&parsed, &x), None, "{}", latex);
+    }
+}
+
+#[test]
+fn expand() {
+    let x = Variable::Letter("x".to_string());
+    let parse =
+        |latex: &str| parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+    let no_products_of_sums = |equation: &Equation| {
+        let terms = match equation {
+            Equation::Addition(terms) => terms.clone(),
+            equation => vec![equation.clone()],
+        };
+        terms.iter().all(|term| !format!("{}", term).contains('+'))
+    };
+
+    for (factored, expanded) in [
+        ("(x+1)^3", "x^3+3*x^2+3*x+1"),
+        ("2*(x+1)*(x-2)", "2*x^2-2*x-4"),
+        ("(x^2+x+1)^2", "x^4+2*x^3+3*x^2+2*x+1"),
+        ("(x+2)*(2-x)", "4-x^2"),
+    ] {
+        let result = parse(factored).expand();
+        assert!(no_products_of_sums(&result), "{}", result);
+        assert_eq!(
+            Polynomial::from_equation(&result, &x),
+            Polynomial::from_equation(&parse(expanded), &x)
+        );
+    }
+    for (factored, expanded) in [
+        ("(x+y)*(x-y)", "x^2-y^2"),
+        ("\\frac{(x+1)^2}{x}", "x+2+\\frac{1}{x}"),
+        ("(a+b)^{-2}", "\\frac{1}{a^2+2*a*b+b^2}"),
+        ("\\sin((x+1)*(x-1))", "\\sin(x^2-1)"),
+    ] {
+        let result = parse(factored).expand();
+        assert!(matches!(
+            result.probably_equals(&parse(expanded), &BTreeMap::new()),
+            Equivalence::ProbablyEqual { .. }
+        ));
+    }
+
+    let expanded = Polynomial::from_equation(&parse("(x+1)^{20}").expand(), &x).unwrap();
+    assert_eq!(expanded.coefficient(10), Rational64::from(184756));
+    let expanded = parse("(x+y+z+1)^{12}").expand();
+    assert!(matches!(&expanded, Equation::Addition(terms) if terms.len() == 455));
+
+    let factored = parse("2*(x+1)*(x+2)");
+    let options = SimplifyOptions {
+        keep_factored: true,
+        ..Default::default()
+    };
+    let simplified = factored.clone().simplify_with(&options).equation;
+    assert_eq!(simplified.canonical(), factored.canonical());
+    //Rules that simplify their results with simplify_until_complete keep the products of sums too
+    let derivative = parse("\\frac{d}{dx}((x+1)*(x+2)*\\sin(x))")
+        .simplify_with(&options)
+        .equation;
+    assert!(derivative.to_string().contains("((1)+(x))*((2)+(x))"));
+    assert!(no_products_of_sums(&factored.simplify_until_complete()));
+}
+
+#[test]
+fn factor() {
+    let parse =
+        |latex: &str| parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+    for (expanded, factored) in [
+        ("x^2-1", "(x-1)*(x+1)"),
+        ("2*x^2+4*x+2", "2*(x+1)^2"),
+        ("x^4-16", "(x-2)*(x+2)*(x^2+4)"),
+        ("6*x^2-x-2", "(2*x+1)*(3*x-2)"),
+        ("x^5-x^4-2*x^3+2*x^2+x-1", "(x+1)^2*(x-1)^3"),
+        ("3*x^2-3", "3*(x-1)*(x+1)"),
+        ("x^3*y+x^2*y", "x^2*y*(x+1)"),
+        ("4*x^2*y^2-9", "(2*x*y-3)*(2*x*y+3)"),
+        ("x^2+1", "x^2+1"),
+    ] {
+        let result = parse(expanded).factor();
+        assert!(
+            result.equivalent(&parse(factored)),
+            "{} = {}",
+            expanded,
+            result
+        );
+        assert!(matches!(
+            result.probably_equals(&parse(expanded), &BTreeMap::new()),
+            Equivalence::ProbablyEqual { .. }
+        ));
+    }
+
+    //Too high to factor as a polynomial, but still a difference of squares
+    assert!(matches!(
+        parse("x^{1000000}-1").factor(),
+        Equation::Multiplication(_)
+    ));
+
+    //An integer factor can be spread over several factors of a product
+    for (equation, factor, removed) in [("2*3*x", "6", "x"), ("2*x*y", "x", "2*y")] {
+        let result = parse(equation)
+            .remove_factor(&parse(factor))
+            .simplify_until_complete();
+        assert!(result.equivalent(&parse(removed)), "{}", result);
+    }
+
+    let x = Variable::Letter("x".to_string());
+    let polynomial = Polynomial::from_equation(&parse("(x-1)^2*(x+2)^3*x"), &x).unwrap();
+    let decomposition = polynomial.square_free_decomposition().unwrap();
+    let powers = decomposition
+        .iter()
+        .map(|(factor, power)| (factor.degree(), *power));
+    assert_eq!(
+        powers.collect::<Vec<_>>(),
+        [(Some(1), 1), (Some(1), 2), (Some(1), 3)]
+    );
+}
+
+#[test]
+fn limit() {
+    let parse =
+        |latex: &str| parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+    let infinity = Equation::Variable(Variable::Constant(Constant::Infinity));
+    let minus_infinity = Equation::Negative(Box::new(infinity.clone()));
+    let half = |n| Equation::Variable(Variable::Rational(Rational64::new(n, 2)));
+    for (latex, expected) in [
+        ("\\lim_{x\\to 0}\\frac{\\sin(x)}{x}", parse("1")),
+        ("\\lim_{x\\to 1}\\frac{x^2-1}{x-1}", parse("2")),
+        ("\\lim_{x\\to\\infty}\\frac{3*x^2+1}{2*x^2-x}", half(3)),
+        ("\\lim_{x\\to 0}\\frac{1-\\cos(x)}{x^2}", half(1)),
+        ("\\lim_{x\\to 0^+}x*\\ln(x)", parse("0")),
+        ("\\lim_{x\\to\\infty}\\frac{2^x}{x^{10}}", infinity.clone()),
+        (
+            "\\lim_{x\\to\\infty}(\\frac{1}{x}-\\frac{1}{x+1})",
+            parse("0"),
+        ),
+        ("\\lim_{x\\to\\infty}(x-\\ln(x))", infinity.clone()),
+        ("\\lim_{x\\to 0}\\frac{\\sin(a*x)}{x}", parse("a")),
+        ("\\lim_{x\\to a}\\frac{x^2-a^2}{x-a}", parse("2*a")),
+        ("\\lim_{x\\to 0^+}\\frac{1}{x}", infinity.clone()),
+        ("\\lim_{x\\to 0^-}\\frac{1}{x}", minus_infinity.clone()),
+        ("\\lim_{x\\to 0}\\frac{1}{x^2}", infinity.clone()),
+        (
+            "\\lim_{x\\to-\\infty}\\frac{x^3}{x^2+1}",
+            minus_infinity.clone(),
+        ),
+        ("\\lim_{x\\to 0^+}\\ln(x)", minus_infinity),
+        ("\\lim_{x\\to\\infty}x\\cdot e^{-x}", parse("0")),
+        ("\\lim_{x\\to\\infty}\\frac{x}{e^x}", parse("0")),
+    ] {
+        let result = parse(latex).simplify_until_complete();
+        assert!(result.equivalent(&expected), "{} = {:?}", latex, result);
+    }
+
+    let e = parse("\\lim_{x\\to\\infty}(1+\\frac{1}{x})^x");
+    assert!((e.calculate(&BTreeMap::new()) - std::f64::consts::E).abs() < 1e-9);
+    let x = Variable::Letter("x".to_string());
+    for (latex, point) in [("\\frac{1}{x}", "0"), ("\\sin(x)", "\\infty")] {
+        let limit = parse(latex).limit(&x, &parse(point), LimitDirection::Both);
+        assert_eq!(limit, None);
+    }
+    //The square root is not defined left of 0
+    assert_eq!(
+        parse("\\sqrt{x}").limit(&x, &parse("0"), LimitDirection::Left),
+        None
+    );
+    assert_eq!(
+        parse("\\lim_{x\\to 0^+}\\sqrt{x}").try_calculate(&BTreeMap::new()),
+        Ok(0.)
+    );
+    let x_equation = Equation::Variable(x.clone());
+    let sign = Equation::Division(Box::new((
+        Equation::Abs(Box::new(x_equation.clone())),
+        x_equation,
+    )));
+    let zero = parse("0");
+    assert_eq!(sign.limit(&x, &zero, LimitDirection::Both), None);
+    assert_eq!(
+        sign.limit(&x, &zero, LimitDirection::Left),
+        Some(Equation::Variable(Variable::Integer(-1)))
+    );
+    assert!(matches!(
+        parse("\\lim_{x\\to 0}\\frac{1}{x}").try_calculate(&BTreeMap::new()),
+        Err(EvalError::NoLimit(_))
+    ));
+}
+
+#[test]
+fn series() {
+    let parse =
+        |latex: &str| parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+    let rational = |n, d| Equation::Variable(Variable::Rational(Rational64::new(n, d)));
+    let x = Variable::Letter("x".to_string());
+    let zero = parse("0");
+    for (latex, point, expected) in [
+        (
+            "\\sin(x)",
+            "0",
+            vec![parse("0"), parse("1"), parse("0"), rational(-1, 6)],
+        ),
+        (
+            "\\cos(x)",
+            "0",
+            vec![parse("1"), parse("0"), rational(-1, 2), parse("0")],
+        ),
+        (
+            "e^x",
+            "0",
+            vec![parse("1"), parse("1"), rational(1, 2), rational(1, 6)],
+        ),
+        (
+            "\\sqrt{1+x}",
+            "0",
+            vec![parse("1"), rational(1, 2), rational(-1, 8), rational(1, 16)],
+        ),
+        (
+            "\\ln(x)",
+            "1",
+            vec![parse("0"), parse("1"), rational(-1, 2), rational(1, 3)],
+        ),
+        (
+            "\\frac{\\sin(x)}{x}",
+            "0",
+            vec![parse("1"), parse("0"), rational(-1, 6), parse("0")],
+        ),
+        (
+            "x^2+a*x",
+            "0",
+            vec![parse("0"), parse("a"), parse("1"), parse("0")],
+        ),
+    ] {
+        let series = parse(latex)
+            .series(&x, &parse(point), 3)
+            .unwrap_or_else(|| panic!("{}", latex));
+        assert_eq!(series.lowest_power, 0, "{}", latex);
+        assert_eq!(series.order, 4, "{}", latex);
+        for (coefficient, expected) in series.coefficients.iter().zip(&expected) {
+            assert!(coefficient.equivalent(expected), "{}: {:?}", latex, series);
+        }
+    }
+
+    let laurent = parse("\\frac{1}{\\sin(x)}").series(&x, &zero, 1).unwrap();
+    assert_eq!(laurent.lowest_power, -1);
+    let expected = [parse("1"), parse("0"), rational(1, 6)];
+    for (coefficient, expected) in laurent.coefficients.iter().zip(&expected) {
+        assert!(coefficient.equivalent(expected), "{:?}", laurent);
+    }
+    assert_eq!(
+        laurent.to_latex(),
+        "(\\frac{1}{x})+((\\frac{1}{6})*(x))+O((x)^{2})"
+    );
+
+    let sin = parse("\\sin(x)").series(&x, &zero, 4).unwrap();
+    let probably_equal = |a: &Equation, b: &str| {
+        matches!(
+            a.probably_equals(&parse(b), &BTreeMap::new()),
+            Equivalence::ProbablyEqual { .. }
+        )
+    };
+    assert!(probably_equal(&sin.to_equation(), "x-\\frac{x^3}{6}"));
+    assert_eq!(sin.order_term(), parse("x^5"));
+    let shifted = parse("e^x").series(&x, &parse("1"), 1).unwrap();
+    assert!(probably_equal(&shifted.to_equation(), "e+e*(x-1)"));
+    assert_eq!(parse("e^{\\frac{1}{x}}").series(&x, &zero, 2), None);
 }
 
 #[test]
@@ -24,6 +478,822 @@ fn differentiate() {
         "\\frac{x*\\frac{1}{\\cos(x)^2}-2*\\tan(x)}{x^3}",
         &valuedicts
     ));
+    assert!(derivative_is_equal(
+        "e^{\\frac{x^2}{10^5}}",
+        "\\frac{2*x*\\exp(\\frac{x^2}{10^5})}{10^5}",
+        &valuedicts
+    ));
+    assert!(derivative_is_equal(
+        "\\arctan(x^2)",
+        "\\frac{2*x}{1+x^4}",
+        &valuedicts
+    ));
+    assert!(derivative_is_equal(
+        "\\sinh(x)*\\tanh(x)",
+        "\\cosh(x)*\\tanh(x)+\\frac{\\sinh(x)}{\\cosh(x)^2}",
+        &valuedicts
+    ));
+}
+
+#[test]
+fn partial_derivatives() {
+    let parse =
+        |latex: &str| parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+    let (x, y) = (
+        Variable::Letter("x".to_string()),
+        Variable::Letter("y".to_string()),
+    );
+    let probably_equal = |a: &Equation, b: &str| {
+        matches!(
+            a.probably_equals(&parse(b), &BTreeMap::new()),
+            Equivalence::ProbablyEqual { .. }
+        )
+    };
+
+    let f = parse("x^3*y^2+\\sin(x*y)");
+    assert_eq!(f.letters(), vec![x.clone(), y.clone()]);
+    assert!(probably_equal(
+        &parse("\\sin(x)").nth_derivative(&x, 4),
+        "\\sin(x)"
+    ));
+    assert!(probably_equal(
+        &parse("x^5").nth_derivative(&x, 3),
+        "60*x^2"
+    ));
+    assert!(probably_equal(
+        &f.partial_derivative(&[x.clone(), y.clone()]),
+        "6*x^2*y+\\cos(x*y)-x*y*\\sin(x*y)"
+    ));
+
+    let gradient = f.gradient(&f.letters());
+    for (component, expected) in gradient
+        .iter()
+        .zip(["3*x^2*y^2+y*\\cos(x*y)", "2*x^3*y+x*\\cos(x*y)"])
+    {
+        assert!(probably_equal(component, expected), "{}", component);
+    }
+    let hessian = f.hessian(&f.letters());
+    let expected = [
+        [
+            "6*x*y^2-y^2*\\sin(x*y)",
+            "6*x^2*y+\\cos(x*y)-x*y*\\sin(x*y)",
+        ],
+        ["6*x^2*y+\\cos(x*y)-x*y*\\sin(x*y)", "2*x^3-x^2*\\sin(x*y)"],
+    ];
+    for (row, expected_row) in hessian.iter().zip(expected) {
+        for (entry, expected) in row.iter().zip(expected_row) {
+            assert!(probably_equal(entry, expected), "{}", entry);
+        }
+    }
+
+    let operator = parse("\\frac{\\partial^2}{\\partial x\\partial y}(x^2*y^3)+1");
+    assert!(probably_equal(
+        &operator.simplify_until_complete(),
+        "6*x*y^2+1"
+    ));
+    let mut values = BTreeMap::new();
+    values.insert(x.clone(), 2.0);
+    assert!(approx_equal(
+        parse("\\frac{d}{dx}x^3").calculate(&values),
+        12.0
+    ));
+}
+
+#[test]
+fn differentiate_with_steps() {
+    let x = Variable::Letter("x".to_string());
+    let parsed =
+        parser::IR::latex_to_equation("x^2*\\sin(3*x)+\\frac{1}{x}".chars().collect(), false)
+            .unwrap();
+    let (derivative, steps) = parsed.differentiate_with_steps(&x);
+    assert_eq!(
+        derivative,
+        parsed.differentiate(&x).simplify_until_complete()
+    );
+    assert_eq!(
+        steps.iter().map(|step| step.rule).collect::<Vec<_>>(),
+        [
+            "sum rule",
+            "product rule",
+            "power rule",
+            "chain rule",
+            "constant multiple rule",
+            "variable rule",
+            "quotient rule",
+            "variable rule",
+        ]
+    );
+    assert_eq!(steps[0].expression, parsed.to_latex());
+    assert_eq!(steps[0].derivative, derivative.to_latex());
+}
+
+#[test]
+fn integrate() {
+    let valuedicts = valuedicts();
+
+    assert!(integral_is_correct("3*x^2+2*x+1", &valuedicts));
+    assert!(integral_is_correct("\\cos(2*x+1)", &valuedicts));
+    assert!(integral_is_correct("x*\\cos(x^2)", &valuedicts));
+    assert!(integral_is_correct("x^2*\\sin(x)", &valuedicts));
+    assert!(integral_is_correct("x*\\ln(x^2)", &valuedicts));
+    assert!(integral_is_correct("\\frac{1}{x^2+2*x+5}", &valuedicts));
+    assert!(integral_is_correct("\\frac{x^3+1}{x^2-3*x+2}", &valuedicts));
+    assert!(integral_is_correct("\\frac{x}{(x+1)^2*(x-2)}", &valuedicts));
+    assert!(integral_is_correct(
+        "\\frac{3}{\\sqrt{1-(\\frac{x}{1000})^2}}",
+        &valuedicts
+    ));
+    assert!(integral_is_correct(
+        "\\frac{1}{\\sqrt{250000+x-x^2}}",
+        &valuedicts
+    ));
+    let parsed =
+        parser::IR::latex_to_equation("\\frac{1}{\\sqrt{1-x^2}}".chars().collect(), false).unwrap();
+    assert_eq!(
+        parsed.integrate(&Variable::Letter("x".to_string())),
+        Some(Equation::Arcsin(Box::new(Equation::Variable(
+            Variable::Letter("x".to_string())
+        ))))
+    );
+
+    for latex in ["e^{x^2}", "\\frac{1}{x^{100000}+1}"] {
+        let parsed = parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+        assert_eq!(parsed.integrate(&Variable::Letter("x".to_string())), None);
+    }
+    let x = Equation::Variable(Variable::Letter("x".to_string()));
+    let nested_equals = Equation::Addition(vec![
+        x.clone(),
+        Equation::Equals(Box::new((x, Equation::Variable(Variable::Integer(2))))),
+    ]);
+    assert_eq!(
+        nested_equals.integrate(&Variable::Letter("x".to_string())),
+        None
+    );
+}
+
+#[test]
+fn definite_integral() {
+    let mut values = BTreeMap::new();
+    values.insert(Variable::Letter("y".to_string()), 2.);
+    let calculate = |latex: &str| {
+        parser::IR::latex_to_equation(latex.chars().collect(), false)
+            .unwrap()
+            .try_calculate(&values)
+    };
+
+    assert_eq!(calculate("\\int_0^1 3*x^2 dx"), Ok(1.));
+    assert_eq!(calculate("\\int_{0}^{y} x*y\\,dx"), Ok(4.));
+    assert!(approx_equal(
+        calculate("\\int_0^1 e^{x^2} dx").unwrap(),
+        1.4626517459
+    ));
+    assert!(approx_equal(
+        calculate("\\int_0^1 \\int_0^y x \\mathrm{d}x dy").unwrap(),
+        1. / 6.
+    ));
+    assert!(matches!(
+        calculate("\\int x dx"),
+        Err(EvalError::IndefiniteIntegral(_))
+    ));
+    assert!(matches!(
+        calculate("\\int_0^1 \\frac{1}{x} dx"),
+        Err(EvalError::Divergent(_))
+    ));
+    assert!(matches!(
+        calculate("\\int_{-1}^{1}\\frac{1}{x^2}dx"),
+        Err(EvalError::Divergent(_))
+    ));
+    assert!(matches!(
+        calculate("\\int_0^2\\frac{1}{x-1}dx"),
+        Err(EvalError::Divergent(_))
+    ));
+    assert!(approx_equal(
+        calculate("\\int_0^1 \\frac{1}{\\sqrt{x}} dx").unwrap(),
+        2.
+    ));
+    assert!(approx_equal(
+        parser::IR::latex_to_equation("\\int_0^{\\pi}\\sin(x)dx".chars().collect(), false)
+            .unwrap()
+            .try_calculate(&BTreeMap::new())
+            .unwrap(),
+        2.
+    ));
+    //The bounds are not checked for unbound variables before the integral is calculated
+    let Equation::Integral(unbound) =
+        parser::IR::latex_to_equation("\\int_0^a x\\,dx".chars().collect(), false).unwrap()
+    else {
+        panic!("not an integral");
+    };
+    assert!(matches!(
+        unbound.try_calculate(&values),
+        Err(EvalError::UnboundVariables(_))
+    ));
+    assert!(unbound.calculate(&values).is_nan());
+    let pole =
+        parser::IR::latex_to_equation("\\int_{-1}^{1}\\frac{1}{x^2}dx".chars().collect(), false)
+            .unwrap();
+    assert!(matches!(
+        pole.simplify_until_complete(),
+        Equation::Integral(_)
+    ));
+
+    assert!(derivative_is_equal(
+        "\\int_0^x e^{\\frac{t^2}{10^5}} dt",
+        "\\exp(\\frac{x^2}{10^5})",
+        &valuedicts()
+    ));
+}
+
+#[test]
+fn integrate_numeric() {
+    let x = Variable::Letter("x".to_string());
+    let integrate = |latex: &str, a: f64, b: f64, options: &QuadratureOptions| {
+        parser::IR::latex_to_equation(latex.chars().collect(), false)
+            .unwrap()
+            .integrate_numeric(&x, a, b, options)
+    };
+
+    for method in [QuadratureMethod::GaussKronrod, QuadratureMethod::Simpson] {
+        let options = QuadratureOptions {
+            method,
+            ..Default::default()
+        };
+        for (latex, a, b, expected) in [
+            ("\\sin(x)", 0., std::f64::consts::PI, 2.),
+            ("\\frac{1}{e^{x^2}}", -3., 3., 1.7724146965),
+            ("\\frac{1}{\\sqrt{x}}", 0., 1., 2.),
+            ("\\ln(x)", 0., 1., -1.),
+            ("\\frac{1}{\\sqrt{1-x^2}}", -1., 1., std::f64::consts::PI),
+        ] {
+            let quadrature = integrate(latex, a, b, &options).unwrap();
+            assert!((quadrature.value - expected).abs() < 1e-6);
+            assert!(quadrature.error < 1e-8);
+        }
+    }
+
+    let options = QuadratureOptions::default();
+    assert!(matches!(
+        integrate("\\frac{1}{x}", 0., 1., &options),
+        Err(EvalError::NotConverged(_, _))
+    ));
+    assert_eq!(
+        integrate("a*x", 0., 1., &options),
+        Err(EvalError::UnboundVariables(vec![Variable::Letter(
+            "a".to_string()
+        )]))
+    );
+    assert!(matches!(
+        integrate("\\begin{pmatrix}x\\end{pmatrix}", 0., 1., &options),
+        Err(EvalError::NotAScalar(_))
+    ));
+    let mut values = BTreeMap::new();
+    values.insert(Variable::Letter("a".to_string()), 4.);
+    let options = QuadratureOptions { values, ..options };
+    assert_eq!(integrate("a*x", 0., 1., &options).unwrap().value, 2.);
+}
+
+#[test]
+fn find_roots() {
+    let x = Variable::Letter("x".to_string());
+    let roots = |latex: &str, a: f64, b: f64| {
+        parser::IR::latex_to_equation(latex.chars().collect(), false)
+            .unwrap()
+            .find_roots(&x, (a, b), &RootOptions::default())
+            .unwrap()
+    };
+    let assert_roots = |found: Vec<f64>, expected: &[f64]| {
+        assert_eq!(found.len(), expected.len(), "{:?}", found);
+        for (found, expected) in found.iter().zip(expected) {
+            assert!((found - expected).abs() < 1e-9, "{} != {}", found, expected);
+        }
+    };
+
+    assert_roots(roots("x=\\cos(x)", -10., 10.), &[0.7390851332151607]);
+    assert_roots(roots("x^3-2x", -2., 2.), &[-2f64.sqrt(), 0., 2f64.sqrt()]);
+    assert_roots(
+        roots("\\sin(x)", -1., 10.),
+        &[
+            0.,
+            std::f64::consts::PI,
+            2. * std::f64::consts::PI,
+            3. * std::f64::consts::PI,
+        ],
+    );
+    //A double root without a sign change is found with Newton's method
+    assert_roots(roots("(x-1.5)^2", -3., 3.), &[1.5]);
+    //Poles of the tangent are not roots
+    assert_roots(roots("\\tan(x)", 1., 5.), &[std::f64::consts::PI]);
+    //Outside the domain of the logarithm nothing is found
+    assert_roots(roots("\\ln(x)", -5., 5.), &[1.]);
+    assert_roots(roots("x^2+1", -5., 5.), &[]);
+
+    let options = RootOptions {
+        values: BTreeMap::from([(Variable::Letter("a".to_string()), 4.)]),
+        ..Default::default()
+    };
+    let equation = parser::IR::latex_to_equation("x^2=a".chars().collect(), false).unwrap();
+    assert_roots(equation.find_roots(&x, (0., 10.), &options).unwrap(), &[2.]);
+    assert!(matches!(
+        equation.find_roots(&x, (0., 10.), &RootOptions::default()),
+        Err(EvalError::UnboundVariables(_))
+    ));
+}
+
+#[test]
+fn units() {
+    let unit = |unit: &str| Unit::parse(unit).unwrap();
+    assert_eq!(unit("N").dimension, unit("kg m/s^2").dimension);
+    assert_eq!(unit("J").dimension, unit("N\\cdot m").dimension);
+    assert_eq!(unit("V").dimension, unit("W/A").dimension);
+    assert_eq!(unit("Pa").dimension, unit("N/m^{2}").dimension);
+    assert_eq!(unit("J/(mol K)").dimension, unit("J/mol/K").dimension);
+    assert_eq!(unit("m^(1/2)").dimension.pow(2.into()), unit("m").dimension);
+    assert!(approx_equal(unit("km/h").factor, 1. / 3.6));
+    assert!(approx_equal(unit("mg").factor, 1e-6));
+    assert!(approx_equal(unit("\\mu s").factor, 1e-6));
+    assert!(unit("").dimension.is_dimensionless());
+    assert!(Unit::parse("furlong").is_none());
+    assert_eq!(unit("kg m^2 s^-2").dimension.to_string(), "m^2 kg s^-2");
+
+    let parse =
+        |latex: &str| parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+    let letter = |letter: &str| Variable::Letter(letter.to_string());
+    let dimensions = BTreeMap::from([
+        (letter("F"), unit("N").dimension),
+        (letter("m"), unit("kg").dimension),
+        (letter("a"), unit("m/s^2").dimension),
+        (letter("v"), unit("m/s").dimension),
+        (letter("t"), unit("s").dimension),
+        (letter("A"), unit("m^2").dimension),
+    ]);
+    assert_eq!(
+        parse("F=m*a").dimension(&dimensions),
+        Ok(unit("N").dimension)
+    );
+    assert_eq!(
+        parse("\\sqrt{A}+v*t").dimension(&dimensions),
+        Ok(unit("m").dimension)
+    );
+    assert_eq!(
+        parse("\\frac{d}{dt}v").dimension(&dimensions),
+        Ok(unit("m/s^2").dimension)
+    );
+    assert!(matches!(
+        parse("F=m*v").dimension(&dimensions),
+        Err(EvalError::IncompatibleUnits(_, _))
+    ));
+    assert!(matches!(
+        parse("v+a").dimension(&dimensions),
+        Err(EvalError::IncompatibleUnits(_, _))
+    ));
+    assert_eq!(
+        parse("\\sin(t)").dimension(&dimensions),
+        Err(EvalError::NotDimensionless(
+            parse("t"),
+            Box::new(unit("s").dimension)
+        ))
+    );
+    assert!(matches!(
+        parse("v^t").dimension(&dimensions),
+        Err(EvalError::NotDimensionless(_, _))
+    ));
+    assert_eq!(
+        parse("\\frac{v^2}{2*a*\\sqrt{A}}").dimension(&dimensions),
+        Ok(Dimension::DIMENSIONLESS)
+    );
+    assert_eq!(
+        parse("\\int_0^t v\\,dt").dimension(&dimensions),
+        Ok(unit("m").dimension)
+    );
+    assert_eq!(
+        parse("A+0").dimension(&dimensions),
+        Ok(unit("m^2").dimension)
+    );
+    assert!(matches!(
+        parse("A+1").dimension(&dimensions),
+        Err(EvalError::IncompatibleUnits(_, _))
+    ));
+
+    let mut quantities = physicsvalues::physics_quantities();
+    quantities.insert(letter("m"), unit("g").quantity(2.));
+    let energy = parse("m*c^2").calculate_with_units(&quantities).unwrap();
+    assert_eq!(energy.dimension, unit("J").dimension);
+    assert!(approx_equal(energy.value, 2e-3 * 299792458f64.powi(2)));
+    assert!(approx_equal(
+        energy.value_in(&unit("TJ")).unwrap(),
+        2e-3 * 299792458f64.powi(2) / 1e12
+    ));
+    assert_eq!(energy.value_in(&unit("N")), None);
+    let photon = parse("\\frac{h*c}{x}")
+        .calculate_with_units(&BTreeMap::from([
+            (letter("h"), quantities[&letter("h")]),
+            (letter("c"), quantities[&letter("c")]),
+            (letter("x"), unit("nm").quantity(500.)),
+        ]))
+        .unwrap();
+    assert!((photon.value_in(&unit("eV")).unwrap() - 2.4797).abs() < 1e-4);
+    assert!(matches!(
+        parse("m+c").calculate_with_units(&quantities),
+        Err(EvalError::IncompatibleUnits(_, _))
+    ));
+    assert_eq!(
+        physicsvalues::physics_values()[&letter("G")],
+        quantities[&letter("G")].value
+    );
+}
+
+#[test]
+fn propagate_uncertainty() {
+    let letter = |name: &str| Variable::Letter(name.to_string());
+    let parsed = parser::IR::latex_to_equation("a*b-a".chars().collect(), false).unwrap();
+    let uncertainties = [(letter("a"), letter("s_a")), (letter("b"), letter("s_b"))];
+    let covariances = [(letter("a"), letter("b"), letter("c"))];
+
+    let mut values = BTreeMap::new();
+    for (name, value) in [
+        ("a", 3.),
+        ("b", 5.),
+        ("s_a", 0.1),
+        ("s_b", 0.2),
+        ("c", 0.01),
+    ] {
+        values.insert(letter(name), value);
+    }
+    let expected = f64::sqrt((4. * 0.1_f64).powi(2) + (3. * 0.2_f64).powi(2));
+    let uncertainty = parsed.propagate_uncertainty(&uncertainties);
+    assert!(approx_equal(uncertainty.calculate(&values), expected));
+    let correlated = parsed.propagate_correlated_uncertainty(&uncertainties, &covariances);
+    let expected = f64::sqrt(expected.powi(2) + 2. * 4. * 3. * 0.01);
+    assert!(approx_equal(correlated.calculate(&values), expected));
+
+    let mut measurements = BTreeMap::new();
+    measurements.insert(
+        letter("a"),
+        Measurement {
+            value: 3.,
+            uncertainty: 0.1,
+        },
+    );
+    measurements.insert(
+        letter("b"),
+        Measurement {
+            value: 5.,
+            uncertainty: 0.2,
+        },
+    );
+    let result = parsed
+        .calculate_with_uncertainty(
+            &measurements,
+            &[((letter("a"), letter("b")), 0.01)],
+            &BTreeMap::new(),
+        )
+        .unwrap();
+    assert_eq!(result.value, 12.);
+    assert!(approx_equal(result.uncertainty, expected));
+}
+
+#[test]
+fn vectors() {
+    let parse =
+        |latex: &str| parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+    let vector = |name: &str| Variable::Vector(name.to_string());
+    let t = Variable::Letter("t".to_string());
+    let vectors = BTreeMap::from([
+        (vector("a"), vec![1., 2., 3.]),
+        (vector("b"), vec![4., 5., 6.]),
+        (vector("c"), vec![1., 0.]),
+    ]);
+    let values = BTreeMap::from([(t.clone(), 2.)]);
+    let calculate = |latex: &str| parse(latex).calculate_vector(&values, &vectors);
+
+    assert_eq!(
+        calculate("\\vec{a}\\cdot\\vec{b}"),
+        Ok(VectorValue::Scalar(32.))
+    );
+    assert_eq!(
+        calculate("\\vec{a}\\times\\vec{b}"),
+        Ok(VectorValue::Vector(vec![-3., 6., -3.]))
+    );
+    assert_eq!(
+        calculate("t\\vec{a}+\\vec{b}"),
+        Ok(VectorValue::Vector(vec![6., 9., 12.]))
+    );
+    assert_eq!(calculate("|\\vec{c}|"), Ok(VectorValue::Scalar(1.)));
+    assert_eq!(calculate("|t-5|"), Ok(VectorValue::Scalar(3.)));
+    assert!(matches!(
+        calculate("\\vec{a}+\\vec{c}"),
+        Err(EvalError::DimensionMismatch(_))
+    ));
+    assert!(matches!(
+        calculate("\\sin(\\vec{a})"),
+        Err(EvalError::NotAScalar(_))
+    ));
+    assert!(matches!(
+        calculate("\\vec{d}\\cdot\\vec{a}"),
+        Err(EvalError::UnboundVariables(_))
+    ));
+    //A vector variable with a scalar value
+    assert!(matches!(
+        parse("\\vec{a}").calculate_vector(&BTreeMap::from([(vector("a"), 1.)]), &BTreeMap::new()),
+        Err(EvalError::NotAVector(_))
+    ));
+
+    assert_eq!(
+        parse("\\vec{a}\\times(2\\vec{a})").simplify_until_complete(),
+        Equation::Variable(Variable::Integer(0))
+    );
+    let simplified = parse("(t\\vec{b})\\cdot(3\\vec{a})").simplify_until_complete();
+    assert_eq!(
+        simplified.calculate_vector(&values, &vectors),
+        Ok(VectorValue::Scalar(192.))
+    );
+
+    //The derivatives are 2t a·b, |a| for t > 0 and 2t a×b, of which the squared norm is compared
+    for (equation, expected) in [
+        ("t^2*\\vec{a}\\cdot\\vec{b}", 128.),
+        ("|t\\vec{a}|", 14f64.sqrt()),
+        ("(t\\vec{a})\\times(t\\vec{b})", 864.),
+    ] {
+        let derivative = parse(equation).differentiate(&t).simplify_until_complete();
+        let value = match derivative.calculate_vector(&values, &vectors).unwrap() {
+            VectorValue::Scalar(value) => value,
+            VectorValue::Vector(components) => components.iter().map(|x| x * x).sum(),
+        };
+        assert!(
+            (value - expected).abs() < 1e-9,
+            "{}: {}",
+            equation,
+            derivative
+        );
+    }
+}
+
+#[test]
+fn matrices() {
+    let parse =
+        |latex: &str| parser::IR::latex_to_equation(latex.chars().collect(), false).unwrap();
+    let simplified = |latex: &str| parse(latex).simplify_until_complete();
+    let integer = |n: i64| Equation::Variable(Variable::Integer(n));
+    let rational = |numerator: i64, denominator: i64| Rational64::new(numerator, denominator);
+
+    let a = parse("\\begin{pmatrix}2&1&1\\\\1&3&2\\\\1&0&0\\end{pmatrix}");
+    assert_eq!(a.dimensions(), Some((3, 3)));
+    assert_eq!(a.determinant(), Some(integer(-1)));
+    let identity = simplified("\\begin{pmatrix}1&0&0\\\\0&1&0\\\\0&0&1\\end{pmatrix}");
+    let inverse = a.inverse().unwrap();
+    assert_eq!(
+        a.matrix_product(&inverse)
+            .unwrap()
+            .simplify_until_complete(),
+        identity
+    );
+    assert_eq!(
+        simplified("\\begin{pmatrix}1&2\\\\2&4\\end{pmatrix}").inverse(),
+        None
+    );
+    assert_eq!(
+        simplified("\\begin{pmatrix}1&2\\\\3&4\\end{pmatrix}^T"),
+        simplified("\\begin{pmatrix}1&3\\\\2&4\\end{pmatrix}")
+    );
+    assert_eq!(
+        simplified("\\begin{pmatrix}1&1\\\\1&0\\end{pmatrix}^{10}"),
+        simplified("\\begin{pmatrix}89&55\\\\55&34\\end{pmatrix}")
+    );
+    //Matrix multiplication keeps the order of the factors
+    assert_eq!(
+        simplified(
+            "\\begin{pmatrix}0&1\\\\0&0\\end{pmatrix}\\begin{pmatrix}0&0\\\\1&0\\end{pmatrix}"
+        ),
+        simplified("\\begin{pmatrix}1&0\\\\0&0\\end{pmatrix}")
+    );
+    assert!(matches!(
+        simplified("\\begin{pmatrix}1&2\\end{pmatrix}\\begin{pmatrix}1&2\\end{pmatrix}"),
+        Equation::Multiplication(_)
+    ));
+
+    let symbolic = parse("\\begin{pmatrix}a&b\\\\c&d\\end{pmatrix}");
+    let determinant = symbolic.determinant().unwrap();
+    assert!(matches!(
+        determinant.probably_equals(&parse("a*d-b*c"), &BTreeMap::new()),
+        Equivalence::ProbablyEqual { .. }
+    ));
+    let product = symbolic
+        .matrix_product(&symbolic.inverse().unwrap())
+        .unwrap();
+    let values = RANDOM_VALUES
+        .chunks(4)
+        .map(|chunk| {
+            ["a", "b", "c", "d"]
+                .iter()
+                .map(|name| Variable::Letter(name.to_string()))
+                .zip(chunk.iter().copied())
+                .collect::<BTreeMap<_, _>>()
+        })
+        .collect::<Vec<_>>();
+    for (i, row) in product.rows().unwrap().iter().enumerate() {
+        for (j, entry) in row.iter().enumerate() {
+            assert!(values
+                .iter()
+                .all(|values| { (entry.calculate(values) - (i == j) as i64 as f64).abs() < 1e-6 }));
+        }
+    }
+
+    //x + 2y = 5, 3x - y = 1
+    let solution = parser::IR::latex_to_equation(
+        "\\begin{pmatrix}1&2\\\\3&-1\\end{pmatrix}"
+            .chars()
+            .collect(),
+        false,
+    )
+    .unwrap()
+    .solve_linear(&parse("\\begin{pmatrix}5\\\\1\\end{pmatrix}"));
+    assert_eq!(
+        solution,
+        Some(simplified("\\begin{pmatrix}1\\\\2\\end{pmatrix}"))
+    );
+    assert_eq!(
+        solve_linear_system(
+            &[
+                vec![rational(1, 2), rational(1, 3)],
+                vec![rational(1, 1), rational(-1, 1)]
+            ],
+            &[vec![rational(1, 1)], vec![rational(0, 1)]]
+        ),
+        Some(vec![vec![rational(6, 5)], vec![rational(6, 5)]])
+    );
+    assert_eq!(
+        solve_linear_system(
+            &[
+                vec![rational(i64::MAX, 1), rational(1, 1)],
+                vec![rational(1, i64::MAX), rational(i64::MAX, 1)]
+            ],
+            &[vec![rational(1, 1)], vec![rational(1, 1)]]
+        ),
+        None
+    );
+}
+
+#[test]
+fn systems() {
+    let parse = |latex: &str| parser::IR::latex_to_system(latex.chars().collect(), false).unwrap();
+    let letters = |names: &str| {
+        names
+            .chars()
+            .map(|name| Variable::Letter(name.to_string()))
+            .collect::<Vec<_>>()
+    };
+    let solutions = |latex: &str, unknowns: &str| {
+        parse(latex)
+            .solve(&letters(unknowns))
+            .into_iter()
+            .map(|solution| {
+                solution
+                    .iter()
+                    .map(|equation| equation.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(solutions("x+2y=5, 3x-y=1", "xy"), ["{x}={1}, {y}={2}"]);
+    assert_eq!(
+        solutions("x+y+z=6\nx-y=-1\nx+z=4", "xyz"),
+        ["{x}={1}, {y}={2}, {z}={3}"]
+    );
+    assert_eq!(
+        solutions("\\begin{cases}x*y=6\\\\x+y=5\\end{cases}", "xy"),
+        ["{x}={3}, {y}={2}", "{x}={2}, {y}={3}"]
+    );
+    assert_eq!(
+        solutions("x=y=2z, z=\\frac{1}{2}", "xyz"),
+        ["{x}={1}, {y}={1}, {z}={\\frac{1}{2}}"]
+    );
+    assert!(solutions("x+y=1, x+y=2", "xy").is_empty());
+    assert_eq!(solutions("x+y=1, 2x+2y=2", "xy"), ["{x}={(1)+((-1)*(y))}"]);
+    //arcsin(2) is not a number
+    assert!(solutions("\\sin(x)=y \\\\ y=2", "xy").is_empty());
+    assert_eq!(solutions("\\ln(x)=y \\\\ y=0", "xy"), ["{x}={1}, {y}={0}"]);
+
+    //The circle and the line meet in two points
+    let circle = parse("x^2+y^2=25, y=x+1");
+    let solutions = circle.solve(&letters("xy"));
+    assert_eq!(solutions.len(), 2);
+    for solution in solutions {
+        let values = solution
+            .iter()
+            .map(|equation| {
+                let Equation::Equals(sides) = equation else {
+                    panic!("{} is not a solution", equation);
+                };
+                let Equation::Variable(variable) = &sides.0 else {
+                    panic!("{} is not a solution", equation);
+                };
+                (variable.clone(), sides.1.calculate(&BTreeMap::new()))
+            })
+            .collect::<BTreeMap<_, _>>();
+        assert!(circle.equations.iter().all(|equation| {
+            let Equation::Equals(sides) = equation else {
+                return false;
+            };
+            approx_equal(sides.0.calculate(&values), sides.1.calculate(&values))
+        }));
+    }
+
+    //Coefficients that are not numbers are eliminated symbolically
+    let symbolic = parse("a*x+b*y=c, x-y=0");
+    let solution = &symbolic.solve(&letters("xy"))[0];
+    let values = BTreeMap::from([
+        (Variable::Letter("a".to_string()), 2.),
+        (Variable::Letter("b".to_string()), 3.),
+        (Variable::Letter("c".to_string()), 10.),
+    ]);
+    for equation in solution {
+        let Equation::Equals(sides) = equation else {
+            panic!("{} is not a solution", equation);
+        };
+        assert!(approx_equal(sides.1.calculate(&values), 2.));
+    }
+    assert_eq!(symbolic.letters(), letters("abcxy"));
+}
+
+#[test]
+fn solve() {
+    let mut values = BTreeMap::new();
+    values.insert(Variable::Letter("a".to_string()), 2.5);
+    values.insert(Variable::Letter("b".to_string()), -7.25);
+    values.insert(Variable::Letter("c".to_string()), 1.75);
+    values.insert(Variable::Letter("g".to_string()), 9.81);
+    values.insert(Variable::Constant(Constant::E), std::f64::consts::E);
+
+    assert!(solutions_are_correct("a*x+b=c", 1, &values));
+    assert!(solutions_are_correct("x^2+x-6=0", 2, &values));
+    assert!(solutions_are_correct("a*x^2+b*x+c=0", 2, &values));
+    assert!(solutions_are_correct("x^2+1=0", 0, &values));
+    assert!(solutions_are_correct("\\ln(x)=a", 1, &values));
+    assert!(solutions_are_correct("c=\\sqrt{2*g*x}", 1, &values));
+    assert!(solutions_are_correct("\\frac{x}{a}+x=b", 1, &values));
+    assert!(solutions_are_correct("e^{a*x}=c", 1, &values));
+    assert!(solutions_are_correct("\\tan(x)=a", 1, &values));
+    assert!(solutions_are_correct("\\cosh(x)=a", 2, &values));
+    assert!(solutions_are_correct(
+        "\\tanh(2*x)=\\frac{1}{2}",
+        1,
+        &values
+    ));
+    assert!(solutions_are_correct("x^3=-8", 1, &values));
+    assert!(solutions_are_correct("(x+1)^2*(x-3)=0", 2, &values));
+    assert!(solutions_are_correct("2*x^3-3*x^2-3*x+2=0", 3, &values));
+    assert!(solutions_are_correct("x^4=16", 2, &values));
+    assert!(solutions_are_correct("x^2=-4", 0, &values));
+    assert!(solutions_are_correct("\\sin(x)=2", 0, &values));
+    assert!(solutions_are_correct("\\arcsin(x)=2", 0, &values));
+    assert!(solutions_are_correct("|x|=-1", 0, &values));
+
+    let x = Variable::Letter("x".to_string());
+    let solutions = parser::IR::latex_to_equation("x^3=-8".chars().collect(), false)
+        .unwrap()
+        .solve(&x);
+    assert_eq!(
+        solutions,
+        vec![Equation::Equals(Box::new((
+            Equation::Variable(x),
+            Equation::Variable(Variable::Integer(-2))
+        )))]
+    );
+}
+
+#[test]
+fn try_calculate() {
+    let mut values = BTreeMap::new();
+    values.insert(Variable::Letter("x".to_string()), 2.);
+    let calculate = |latex: &str| {
+        parser::IR::latex_to_equation(latex.chars().collect(), false)
+            .unwrap()
+            .try_calculate(&values)
+    };
+
+    assert_eq!(calculate("\\ln(e)*x"), Ok(2.));
+    assert_eq!(
+        calculate("a*x+\\frac{b}{a}"),
+        Err(EvalError::UnboundVariables(vec![
+            Variable::Letter("a".to_string()),
+            Variable::Letter("b".to_string())
+        ]))
+    );
+    assert!(matches!(
+        calculate("\\frac{1}{x-2}"),
+        Err(EvalError::DivisionByZero(_))
+    ));
+    assert!(matches!(
+        calculate("\\ln(1-x)"),
+        Err(EvalError::NonPositiveLogarithm(_, _))
+    ));
+    assert!(matches!(
+        calculate("\\sqrt{1-x}"),
+        Err(EvalError::NegativeRoot(_, _))
+    ));
+    assert_eq!(calculate("x=2"), Err(EvalError::Equals));
 }
 
 fn valuedicts() -> [BTreeMap<Variable, f64>; 30] {
@@ -44,7 +1314,7 @@ fn valuedicts() -> [BTreeMap<Variable, f64>; 30] {
 }
 
 fn simplified_is_equal(equation: &str, valuedicts: &[BTreeMap<Variable, f64>]) -> bool {
-    let parsed = parser::IR::latex_to_equation(equation.chars().collect(), false);
+    let parsed = parser::IR::latex_to_equation(equation.chars().collect(), false).unwrap();
     let simplified = parsed.clone().simplify_until_complete();
 
     valuedicts
@@ -57,8 +1327,8 @@ fn derivative_is_equal(
     expected_result: &str,
     valuedicts: &[BTreeMap<Variable, f64>],
 ) -> bool {
-    let parsed = parser::IR::latex_to_equation(equation.chars().collect(), false);
-    let correct = parser::IR::latex_to_equation(expected_result.chars().collect(), false);
+    let parsed = parser::IR::latex_to_equation(equation.chars().collect(), false).unwrap();
+    let correct = parser::IR::latex_to_equation(expected_result.chars().collect(), false).unwrap();
     let derivative = parsed
         .differentiate(&Variable::Letter("x".to_string()))
         .simplify_until_complete();
@@ -67,6 +1337,41 @@ fn derivative_is_equal(
         .all(|values| approx_equal(derivative.calculate(values), correct.calculate(values)))
 }
 
+fn integral_is_correct(equation: &str, valuedicts: &[BTreeMap<Variable, f64>]) -> bool {
+    let x = Variable::Letter("x".to_string());
+    let parsed = parser::IR::latex_to_equation(equation.chars().collect(), false).unwrap();
+    let Some(integral) = parsed.integrate(&x) else {
+        return false;
+    };
+    let derivative = integral.differentiate(&x).simplify_until_complete();
+    valuedicts
+        .iter()
+        .all(|values| approx_equal(parsed.calculate(values), derivative.calculate(values)))
+}
+
+fn solutions_are_correct(
+    equation: &str,
+    number_of_solutions: usize,
+    values: &BTreeMap<Variable, f64>,
+) -> bool {
+    let x = Variable::Letter("x".to_string());
+    let parsed = parser::IR::latex_to_equation(equation.chars().collect(), false).unwrap();
+    let Equation::Equals(sides) = &parsed else {
+        return false;
+    };
+    let solutions = parsed.solve(&x);
+    solutions.len() == number_of_solutions
+        && solutions.iter().all(|solution| {
+            let Equation::Equals(solution) = solution else {
+                return false;
+            };
+            let mut values = values.clone();
+            values.insert(x.clone(), solution.1.calculate(&values));
+            let (lhs, rhs) = (sides.0.calculate(&values), sides.1.calculate(&values));
+            (lhs - rhs).abs() < 1e-9 * (1. + lhs.abs())
+        })
+}
+
 fn approx_equal(a: f64, b: f64) -> bool {
     let p = a / 10000.;
     (a - b).abs() < p.abs()